@@ -1,12 +1,19 @@
 use crate::core::CCIHSCore;
-use crate::types::{CrossChainMessage, ChainId, CCIHSResult};
+use crate::types::{CrossChainMessage, ChainId, CCIHSResult, RequestEnvelope, Nonce, correlation_id};
+use crate::CCIHSError;
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
 use crate::config::CCIHSConfig;
 use super::endpoints;
 use super::error::APIError;
+use super::request_response::{RequestHandler, RequestOutcome, RequestTracker};
 use crate::protocols::ProtocolType;
+use crate::utility::Clock;
+use std::sync::Arc;
 
 pub struct CCIHSAPI {
     core: CCIHSCore,
+    requests: RequestTracker,
+    request_handler: Option<RequestHandler>,
 }
 
 impl CCIHSAPI {
@@ -14,7 +21,7 @@ impl CCIHSAPI {
         // let core = CCIHSCore::new(config)?;
         // Ok(Self { core })
         let core = CCIHSCore::new(config, config.protocol_adapters, config.supported_chains).map_err(APIError::from)?;
-        Ok(Self { core })
+        Ok(Self { core, requests: RequestTracker::new(), request_handler: None })
     }
 
     pub fn send_message(&self, message: CrossChainMessage) -> Result<String, APIError> {
@@ -48,4 +55,176 @@ impl CCIHSAPI {
     pub fn update_config(&mut self, new_config: CCIHSConfig) -> Result<(), APIError> {
         self.core.update_config(new_config).map_err(APIError::from)
     }
-}
\ No newline at end of file
+
+    /// Sends `message` as a request and starts waiting for its response.
+    /// The request times out `timeout` seconds from now. Returns the
+    /// correlation ID to poll with [`request_outcome`](Self::request_outcome).
+    pub fn send_request(&mut self, mut message: CrossChainMessage, timeout: u64) -> Result<[u8; 32], APIError> {
+        let deadline = self.current_time()? + timeout;
+        let request_nonce = Nonce::new(self.core.clock().as_ref())?.0;
+        let id = correlation_id(&message, request_nonce);
+        self.requests.make_room(&id)?;
+
+        message.payload = RequestEnvelope::Request {
+            correlation_id: id,
+            deadline,
+            payload: message.payload,
+        }
+        .try_to_vec()
+        .map_err(|e| CCIHSError::SerializationError(e.to_string()))?;
+
+        self.core.send_message(&mut message)?;
+        self.requests.track(id, message, deadline)?;
+        Ok(id)
+    }
+
+    /// Sets the handler that answers requests arriving on this chain.
+    pub fn set_request_handler(&mut self, handler: RequestHandler) {
+        self.request_handler = Some(handler);
+    }
+
+    /// Receives the next message from `source_chain`. Requests are answered
+    /// through the request handler and the response is routed back to the
+    /// origin chain; responses resolve the matching pending request if they
+    /// come from the request's recipient on its destination chain. Both are
+    /// consumed and `None` is returned. Any other message is returned as is.
+    pub fn process_incoming(&mut self, source_chain: ChainId) -> Result<Option<CrossChainMessage>, APIError> {
+        let message = self.receive_message(source_chain)?;
+
+        let envelope = match RequestEnvelope::try_from_slice(&message.payload) {
+            Ok(envelope) => envelope,
+            Err(_) => return Ok(Some(message)),
+        };
+
+        match envelope {
+            RequestEnvelope::Request { correlation_id, deadline, payload } => {
+                if self.current_time()? > deadline {
+                    log::warn!("Dropping request {:?}: deadline passed", correlation_id);
+                    return Ok(None);
+                }
+
+                let handler = self.request_handler.as_ref()
+                    .ok_or_else(|| APIError::ConfigurationError("no request handler set".to_string()))?;
+
+                let mut request = message.clone();
+                request.payload = payload;
+                let (success, payload) = match handler(&request) {
+                    Ok(payload) => (true, payload),
+                    Err(error) => (false, error.into_bytes()),
+                };
+
+                let mut response = message;
+                std::mem::swap(&mut response.source_chain, &mut response.destination_chain);
+                std::mem::swap(&mut response.sender, &mut response.recipient);
+                response.amount = 0;
+                response.token_address = None;
+                // A new message: it must not take over the request's ID or
+                // execution window.
+                response.message_id = None;
+                response.expires_at = None;
+                response.execute_after = None;
+                response.payload = RequestEnvelope::Response { correlation_id, success, payload }
+                    .try_to_vec()
+                    .map_err(|e| CCIHSError::SerializationError(e.to_string()))?;
+
+                self.core.send_message(&mut response)?;
+            }
+            RequestEnvelope::Response { correlation_id, success, payload } => {
+                let now = self.current_time()?;
+                if !self.requests.resolve(&correlation_id, message.source_chain, &message.sender, success, payload, now) {
+                    log::warn!("Dropping response {:?}: no matching pending request", correlation_id);
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub fn request_outcome(&self, correlation_id: &[u8; 32]) -> Option<RequestOutcome> {
+        self.requests.get(correlation_id).map(|request| request.outcome.clone())
+    }
+
+    /// Times out every pending request whose deadline has passed. Returns
+    /// their correlation IDs.
+    pub fn expire_requests(&mut self) -> Result<Vec<[u8; 32]>, APIError> {
+        let now = self.current_time()?;
+        Ok(self.requests.expire(now))
+    }
+
+    pub fn requests(&mut self) -> &mut RequestTracker {
+        &mut self.requests
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.core.set_clock(clock);
+    }
+
+    fn current_time(&self) -> Result<u64, APIError> {
+        Ok(self.core.clock().unix_timestamp()?)
+    }
+}
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::config::NetworkProfile;
+    use crate::protocols::{FakeAdapter, ProtocolAdapter};
+    use crate::types::{CrossChainAddress, MessageId, MessageStatus, MessageType};
+    use crate::utility::TestClock;
+    use anchor_lang::prelude::Pubkey;
+    use std::collections::HashMap;
+
+    fn api(adapter: Arc<FakeAdapter>) -> CCIHSAPI {
+        let mut adapters: HashMap<ProtocolType, Box<dyn ProtocolAdapter>> = HashMap::new();
+        adapters.insert(ProtocolType::Wormhole, Box::new(adapter));
+        let mut config = CCIHSConfig::empty();
+        config.apply_network(NetworkProfile::Mainnet);
+        let mut core = CCIHSCore::new(config, adapters, vec![ChainId::SOLANA, ChainId::ETHEREUM]).unwrap();
+        core.add_chain_conversion(ChainId::ETHEREUM, ChainId::SOLANA, |address| Ok(address.to_vec()));
+        core.add_chain_conversion(ChainId::SOLANA, ChainId::ETHEREUM, |address| Ok(address.to_vec()));
+        core.set_clock(Arc::new(TestClock::new(1_000)));
+        CCIHSAPI { core, requests: RequestTracker::new(), request_handler: None }
+    }
+
+    fn request(message_id: MessageId) -> CrossChainMessage {
+        CrossChainMessage {
+            message_type: MessageType::General,
+            payload: RequestEnvelope::Request { correlation_id: [7; 32], deadline: 2_000, payload: b"ping".to_vec() }
+                .try_to_vec()
+                .unwrap(),
+            amount: 0,
+            token_address: None,
+            sender: CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(0x22)),
+            recipient: CrossChainAddress::Solana(Pubkey::new_from_array([0x11; 32])),
+            source_chain: ChainId::ETHEREUM,
+            destination_chain: ChainId::SOLANA,
+            nonce: 1,
+            timestamp: 900,
+            consistency_level: 0,
+            expires_at: Some(1_500),
+            execute_after: None,
+            message_id: Some(message_id),
+        }
+    }
+
+    #[test]
+    fn test_rejects_replays_of_answered_requests() {
+        let adapter = Arc::new(FakeAdapter::new(vec![ChainId::SOLANA, ChainId::ETHEREUM]));
+        let mut api = api(adapter.clone());
+        api.set_request_handler(Box::new(|request| Ok(request.payload.clone())));
+        let id = MessageId::compute(ChainId::ETHEREUM, &[0x22; 32], 5, b"ping");
+
+        adapter.deliver(request(id)).unwrap();
+        assert!(api.process_incoming(ChainId::ETHEREUM).unwrap().is_none());
+        let sent = adapter.sent_messages();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].destination_chain, ChainId::ETHEREUM);
+        assert_eq!(sent[0].expires_at, None);
+        assert_ne!(sent[0].id(), id);
+        assert_eq!(api.core.message_status(&id), Some(MessageStatus::Executed));
+
+        adapter.deliver(request(id)).unwrap();
+        assert!(api.process_incoming(ChainId::ETHEREUM).is_err());
+        assert_eq!(adapter.sent_messages().len(), 1);
+        assert_eq!(api.core.message_status(&id), Some(MessageStatus::Executed));
+    }
+}
@@ -3,6 +3,8 @@
 mod ccihs_api;
 mod endpoints;
 mod error;
+mod request_response;
 
 pub use ccihs_api::CCIHSAPI;
-pub use error::APIError;
\ No newline at end of file
+pub use error::APIError;
+pub use request_response::{RequestHandler, RequestOutcome, PendingRequest, RequestTracker};
\ No newline at end of file
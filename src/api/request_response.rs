@@ -0,0 +1,237 @@
+// api/request_response.rs
+
+use crate::types::{ChainId, CrossChainAddress, CrossChainMessage, CrossChainTransaction, CCIHSResult, MessageStatus};
+use crate::CCIHSError;
+use std::collections::HashMap;
+
+/// Requests a [`RequestTracker`] keeps by default, pending or completed.
+pub const DEFAULT_MAX_TRACKED_REQUESTS: usize = 1024;
+
+/// Handler run on the receiving chain for every incoming request. Its return
+/// value is sent back to the origin chain: `Ok` as a successful response,
+/// `Err` as a failed one carrying the error message.
+pub type RequestHandler = Box<dyn Fn(&CrossChainMessage) -> Result<Vec<u8>, String> + Send + Sync>;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Where a request sent from this chain currently stands.
+pub enum RequestOutcome {
+    /// No response yet and the deadline has not passed.
+    Pending,
+    /// The receiver's handler succeeded and returned this payload.
+    Responded(Vec<u8>),
+    /// The receiver's handler failed with this payload (an error message).
+    Rejected(Vec<u8>),
+    /// No response arrived before the deadline.
+    TimedOut,
+}
+
+/// A request sent from this chain that is waiting for its response.
+pub struct PendingRequest {
+    /// The request as sent. Its status follows the request lifecycle:
+    /// `Sent` while waiting, `Executed` once a successful response arrived,
+    /// `Failed` on a rejected response and `Expired` on timeout.
+    pub transaction: CrossChainTransaction,
+    pub deadline: u64,
+    pub outcome: RequestOutcome,
+}
+
+/// Book-keeping for the request/response pattern on the origin chain.
+/// Holds at most `capacity` requests: completed ones are dropped to make
+/// room for new ones, and new requests are refused while every tracked
+/// request is still pending.
+pub struct RequestTracker {
+    requests: HashMap<[u8; 32], PendingRequest>,
+    capacity: usize,
+}
+
+impl RequestTracker {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_MAX_TRACKED_REQUESTS)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            requests: HashMap::new(),
+            capacity,
+        }
+    }
+
+    /// Checks that a request with this correlation ID can be tracked,
+    /// dropping completed requests if the tracker is full. Call it before
+    /// sending the request.
+    pub fn make_room(&mut self, correlation_id: &[u8; 32]) -> CCIHSResult<()> {
+        if self.requests.contains_key(correlation_id) {
+            return Err(CCIHSError::RequestAlreadyPending);
+        }
+        if self.requests.len() >= self.capacity {
+            self.requests.retain(|_, request| request.outcome == RequestOutcome::Pending);
+        }
+        if self.requests.len() >= self.capacity {
+            return Err(CCIHSError::TooManyPendingRequests);
+        }
+        Ok(())
+    }
+
+    pub fn track(&mut self, correlation_id: [u8; 32], message: CrossChainMessage, deadline: u64) -> CCIHSResult<()> {
+        self.make_room(&correlation_id)?;
+        self.requests.insert(correlation_id, PendingRequest {
+            transaction: CrossChainTransaction {
+                message,
+                status: MessageStatus::Sent,
+                transaction_hash: None,
+            },
+            deadline,
+            outcome: RequestOutcome::Pending,
+        });
+        Ok(())
+    }
+
+    /// Resolves a pending request with its response, sent by `responder` on
+    /// `responder_chain`. Responses arriving after the request timed out,
+    /// for unknown correlation IDs, or from anyone but the request's
+    /// recipient on its destination chain are dropped and `false` is
+    /// returned.
+    pub fn resolve(
+        &mut self,
+        correlation_id: &[u8; 32],
+        responder_chain: ChainId,
+        responder: &CrossChainAddress,
+        success: bool,
+        payload: Vec<u8>,
+        now: u64,
+    ) -> bool {
+        let request = match self.requests.get_mut(correlation_id) {
+            Some(request) if request.outcome == RequestOutcome::Pending => request,
+            _ => return false,
+        };
+
+        let sent = &request.transaction.message;
+        if responder_chain != sent.destination_chain || responder.to_bytes32() != sent.recipient.to_bytes32() {
+            log::warn!("Response {:?} from {} on {} does not come from the request's recipient", correlation_id, responder, responder_chain);
+            return false;
+        }
+
+        if now > request.deadline {
+            request.outcome = RequestOutcome::TimedOut;
+            request.transaction.status = MessageStatus::Expired;
+            return false;
+        }
+
+        if success {
+            request.outcome = RequestOutcome::Responded(payload);
+            request.transaction.status = MessageStatus::Executed;
+        } else {
+            request.outcome = RequestOutcome::Rejected(payload);
+            request.transaction.status = MessageStatus::Failed;
+        }
+        true
+    }
+
+    /// Marks every pending request whose deadline has passed as timed out and
+    /// returns their correlation IDs.
+    pub fn expire(&mut self, now: u64) -> Vec<[u8; 32]> {
+        let mut expired = Vec::new();
+        for (correlation_id, request) in self.requests.iter_mut() {
+            if request.outcome == RequestOutcome::Pending && now > request.deadline {
+                request.outcome = RequestOutcome::TimedOut;
+                request.transaction.status = MessageStatus::Expired;
+                expired.push(*correlation_id);
+            }
+        }
+        expired
+    }
+
+    pub fn get(&self, correlation_id: &[u8; 32]) -> Option<&PendingRequest> {
+        self.requests.get(correlation_id)
+    }
+
+    /// Removes a request that is no longer pending and returns it.
+    pub fn take_completed(&mut self, correlation_id: &[u8; 32]) -> Option<PendingRequest> {
+        match self.requests.get(correlation_id) {
+            Some(request) if request.outcome != RequestOutcome::Pending => self.requests.remove(correlation_id),
+            _ => None,
+        }
+    }
+}
+
+impl Default for RequestTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::types::{correlation_id, MessageType};
+    use anchor_lang::prelude::Pubkey;
+
+    fn recipient() -> CrossChainAddress {
+        CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(0x22))
+    }
+
+    fn request() -> CrossChainMessage {
+        CrossChainMessage {
+            message_type: MessageType::General,
+            payload: b"ping".to_vec(),
+            amount: 0,
+            token_address: None,
+            sender: CrossChainAddress::Solana(Pubkey::new_from_array([0x11; 32])),
+            recipient: recipient(),
+            source_chain: ChainId::SOLANA,
+            destination_chain: ChainId::ETHEREUM,
+            nonce: 0,
+            timestamp: 0,
+            consistency_level: 0,
+            expires_at: None,
+            execute_after: None,
+            message_id: None,
+        }
+    }
+
+    #[test]
+    fn test_identical_requests_get_distinct_ids() {
+        assert_ne!(correlation_id(&request(), 1), correlation_id(&request(), 2));
+
+        let mut tracker = RequestTracker::new();
+        let id = correlation_id(&request(), 1);
+        tracker.track(id, request(), 100).unwrap();
+        assert!(matches!(tracker.track(id, request(), 100), Err(CCIHSError::RequestAlreadyPending)));
+    }
+
+    #[test]
+    fn test_resolve_only_from_recipient() {
+        let mut tracker = RequestTracker::new();
+        let id = correlation_id(&request(), 1);
+        tracker.track(id, request(), 100).unwrap();
+
+        let impostor = CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(0x33));
+        assert!(!tracker.resolve(&id, ChainId::ETHEREUM, &impostor, true, b"pong".to_vec(), 50));
+        assert!(!tracker.resolve(&id, ChainId::SOLANA, &recipient(), true, b"pong".to_vec(), 50));
+        assert_eq!(tracker.get(&id).unwrap().outcome, RequestOutcome::Pending);
+
+        assert!(tracker.resolve(&id, ChainId::ETHEREUM, &recipient(), true, b"pong".to_vec(), 50));
+        assert_eq!(tracker.get(&id).unwrap().outcome, RequestOutcome::Responded(b"pong".to_vec()));
+        assert!(!tracker.resolve(&id, ChainId::ETHEREUM, &recipient(), false, Vec::new(), 60));
+    }
+
+    #[test]
+    fn test_timeout_and_capacity() {
+        let mut tracker = RequestTracker::with_capacity(2);
+        let first = correlation_id(&request(), 1);
+        let second = correlation_id(&request(), 2);
+        let third = correlation_id(&request(), 3);
+        tracker.track(first, request(), 100).unwrap();
+        tracker.track(second, request(), 200).unwrap();
+        assert!(matches!(tracker.track(third, request(), 300), Err(CCIHSError::TooManyPendingRequests)));
+
+        assert_eq!(tracker.expire(150), vec![first]);
+        assert!(!tracker.resolve(&first, ChainId::ETHEREUM, &recipient(), true, Vec::new(), 160));
+        assert_eq!(tracker.get(&first).unwrap().transaction.status, MessageStatus::Expired);
+
+        // The timed out request makes room for the new one.
+        tracker.track(third, request(), 300).unwrap();
+        assert!(tracker.get(&first).is_none());
+        assert!(tracker.get(&second).is_some());
+    }
+}
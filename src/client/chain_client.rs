@@ -0,0 +1,72 @@
+// src/client/chain_client.rs
+
+use crate::CCIHSResult;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// How final a slot or transaction is, from least to most.
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    /// The name used by the JSON-RPC API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Commitment::Processed => "processed",
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "processed" => Some(Commitment::Processed),
+            "confirmed" => Some(Commitment::Confirmed),
+            "finalized" => Some(Commitment::Finalized),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountData {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+    pub executable: bool,
+}
+
+/// The chain operations off-chain code needs.
+pub trait ChainClient: Send + Sync {
+    /// `None` if the account does not exist.
+    fn get_account(&self, address: &Pubkey) -> CCIHSResult<Option<AccountData>>;
+
+    /// Log messages of a transaction, `None` if it is unknown.
+    fn get_transaction_logs(&self, signature: &str) -> CCIHSResult<Option<Vec<String>>>;
+
+    /// Submits a signed, serialized transaction and returns its signature.
+    fn send_transaction(&self, transaction: &[u8]) -> CCIHSResult<String>;
+
+    fn get_slot(&self, commitment: Commitment) -> CCIHSResult<u64>;
+
+    /// Commitment a transaction has reached, `None` if it is unknown.
+    fn get_signature_status(&self, signature: &str) -> CCIHSResult<Option<Commitment>>;
+
+    fn get_latest_blockhash(&self) -> CCIHSResult<Hash>;
+
+    /// Whether the transaction has reached `commitment`.
+    fn is_final(&self, signature: &str, commitment: Commitment) -> CCIHSResult<bool> {
+        Ok(self.get_signature_status(signature)?.map_or(false, |status| status >= commitment))
+    }
+}
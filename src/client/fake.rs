@@ -0,0 +1,116 @@
+// src/client/fake.rs
+
+use super::{AccountData, ChainClient, Commitment};
+use crate::{CCIHSError, CCIHSResult};
+use anchor_lang::solana_program::hash::{hash, Hash};
+use anchor_lang::solana_program::pubkey::Pubkey;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// Slots between the confirmed and the finalized slot.
+const FINALIZATION_LAG: u64 = 32;
+
+struct FakeTransaction {
+    logs: Vec<String>,
+    commitment: Commitment,
+}
+
+#[derive(Default)]
+struct FakeState {
+    accounts: HashMap<Pubkey, AccountData>,
+    transactions: HashMap<String, FakeTransaction>,
+    sent: Vec<Vec<u8>>,
+    slot: u64,
+    failure: Option<String>,
+}
+
+#[derive(Default)]
+/// In-memory [`ChainClient`]. Seed it with accounts and transactions; sent
+/// transactions are recorded and reported as processed.
+pub struct FakeChainClient {
+    state: Mutex<FakeState>,
+}
+
+impl FakeChainClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_account(&self, address: Pubkey, account: AccountData) {
+        self.state.lock().accounts.insert(address, account);
+    }
+
+    pub fn remove_account(&self, address: &Pubkey) -> Option<AccountData> {
+        self.state.lock().accounts.remove(address)
+    }
+
+    pub fn add_transaction(&self, signature: impl Into<String>, logs: Vec<String>, commitment: Commitment) {
+        self.state.lock().transactions.insert(signature.into(), FakeTransaction { logs, commitment });
+    }
+
+    pub fn set_commitment(&self, signature: &str, commitment: Commitment) {
+        if let Some(transaction) = self.state.lock().transactions.get_mut(signature) {
+            transaction.commitment = commitment;
+        }
+    }
+
+    /// Transactions passed to [`send_transaction`](ChainClient::send_transaction), in order.
+    pub fn sent_transactions(&self) -> Vec<Vec<u8>> {
+        self.state.lock().sent.clone()
+    }
+
+    pub fn set_slot(&self, slot: u64) {
+        self.state.lock().slot = slot;
+    }
+
+    /// Makes `send_transaction` fail with `reason` until cleared with `None`.
+    pub fn fail_sends(&self, reason: Option<String>) {
+        self.state.lock().failure = reason;
+    }
+}
+
+impl ChainClient for FakeChainClient {
+    fn get_account(&self, address: &Pubkey) -> CCIHSResult<Option<AccountData>> {
+        Ok(self.state.lock().accounts.get(address).cloned())
+    }
+
+    fn get_transaction_logs(&self, signature: &str) -> CCIHSResult<Option<Vec<String>>> {
+        Ok(self.state.lock().transactions.get(signature).map(|transaction| transaction.logs.clone()))
+    }
+
+    /// The signature is the base58 hash of the transaction.
+    fn send_transaction(&self, transaction: &[u8]) -> CCIHSResult<String> {
+        let mut state = self.state.lock();
+        if let Some(reason) = &state.failure {
+            return Err(CCIHSError::NetworkError(reason.clone()));
+        }
+        let signature = hash(transaction).to_string();
+        state.sent.push(transaction.to_vec());
+        state.transactions.insert(
+            signature.clone(),
+            FakeTransaction {
+                logs: Vec::new(),
+                commitment: Commitment::Processed,
+            },
+        );
+        Ok(signature)
+    }
+
+    /// The finalized slot trails the others by 32 slots.
+    fn get_slot(&self, commitment: Commitment) -> CCIHSResult<u64> {
+        let slot = self.state.lock().slot;
+        Ok(match commitment {
+            Commitment::Finalized => slot.saturating_sub(FINALIZATION_LAG),
+            Commitment::Processed | Commitment::Confirmed => slot,
+        })
+    }
+
+    fn get_signature_status(&self, signature: &str) -> CCIHSResult<Option<Commitment>> {
+        Ok(self.state.lock().transactions.get(signature).map(|transaction| transaction.commitment))
+    }
+
+    /// Derived from the current slot, so it changes when the slot does.
+    fn get_latest_blockhash(&self) -> CCIHSResult<Hash> {
+        Ok(hash(&self.state.lock().slot.to_le_bytes()))
+    }
+}
@@ -0,0 +1,18 @@
+// src/client/mod.rs
+
+//! Reading from and submitting to Solana off-chain. Code that needs chain
+//! data takes a [`ChainClient`]; production uses [`RpcClient`] on the chain's
+//! `rpc_url`, tests a seeded [`FakeChainClient`]. Transactions are signed by
+//! a [`TransactionSigner`].
+
+mod chain_client;
+mod rpc;
+mod fake;
+mod signer;
+
+pub use chain_client::{AccountData, ChainClient, Commitment};
+pub use rpc::{DefaultTransport, HttpTransport, RpcClient, TcpTransport};
+#[cfg(feature = "rpc")]
+pub use rpc::UreqTransport;
+pub use fake::FakeChainClient;
+pub use signer::{sign_transaction, TransactionSigner};
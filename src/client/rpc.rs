@@ -0,0 +1,309 @@
+// src/client/rpc.rs
+
+use super::{AccountData, ChainClient, Commitment};
+use crate::config::ChainConfig;
+use crate::utility::encoding::{from_base64, to_base64};
+use crate::{CCIHSError, CCIHSResult};
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Posts a JSON body to a URL and returns the response body.
+pub trait HttpTransport: Send + Sync {
+    fn post(&self, url: &str, body: &str) -> CCIHSResult<String>;
+}
+
+/// Plain HTTP/1.0 over a TCP socket, enough for a local validator. For
+/// `https` endpoints use [`UreqTransport`] of the `rpc` feature.
+pub struct TcpTransport {
+    pub timeout: Duration,
+}
+
+impl Default for TcpTransport {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl HttpTransport for TcpTransport {
+    fn post(&self, url: &str, body: &str) -> CCIHSResult<String> {
+        let error = |reason: String| CCIHSError::NetworkError(format!("{}: {}", url, reason));
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| error("TcpTransport only supports http:// URLs, enable the `rpc` feature for https".to_string()))?;
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+        let address = if authority.contains(':') {
+            authority.to_string()
+        } else {
+            format!("{}:80", authority)
+        };
+
+        let mut stream = TcpStream::connect(&address).map_err(|e| error(e.to_string()))?;
+        stream.set_read_timeout(Some(self.timeout)).map_err(|e| error(e.to_string()))?;
+        stream.set_write_timeout(Some(self.timeout)).map_err(|e| error(e.to_string()))?;
+        let request = format!(
+            "POST {} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            path,
+            authority,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).map_err(|e| error(e.to_string()))?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).map_err(|e| error(e.to_string()))?;
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| error("malformed HTTP response".to_string()))?;
+        let status = head.split_whitespace().nth(1).unwrap_or_default();
+        if status != "200" {
+            return Err(error(format!("HTTP status {}", status)));
+        }
+        Ok(body.to_string())
+    }
+}
+
+#[cfg(feature = "rpc")]
+/// HTTP and HTTPS through `ureq`, verifying servers against the Mozilla
+/// root certificates.
+pub struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "rpc")]
+impl UreqTransport {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(timeout).build(),
+        }
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl Default for UreqTransport {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30))
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl HttpTransport for UreqTransport {
+    fn post(&self, url: &str, body: &str) -> CCIHSResult<String> {
+        let error = |reason: String| CCIHSError::NetworkError(format!("{}: {}", url, reason));
+        let response = self
+            .agent
+            .post(url)
+            .set("Content-Type", "application/json")
+            .send_string(body)
+            .map_err(|e| match e {
+                ureq::Error::Status(status, _) => error(format!("HTTP status {}", status)),
+                e => error(e.to_string()),
+            })?;
+        response.into_string().map_err(|e| error(e.to_string()))
+    }
+}
+
+/// What [`RpcClient::new`] uses: [`UreqTransport`] with the `rpc` feature,
+/// which every public cluster needs, [`TcpTransport`] without it.
+#[cfg(feature = "rpc")]
+pub type DefaultTransport = UreqTransport;
+#[cfg(not(feature = "rpc"))]
+pub type DefaultTransport = TcpTransport;
+
+/// [`ChainClient`] over Solana's JSON-RPC API.
+pub struct RpcClient<T = DefaultTransport> {
+    url: String,
+    commitment: Commitment,
+    transport: T,
+    next_id: AtomicU64,
+}
+
+impl RpcClient<DefaultTransport> {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_transport(url, DefaultTransport::default())
+    }
+
+    /// Uses the chain's `rpc_url`.
+    pub fn from_chain_config(config: &ChainConfig) -> Self {
+        Self::new(config.rpc_url.clone())
+    }
+}
+
+impl<T: HttpTransport> RpcClient<T> {
+    pub fn with_transport(url: impl Into<String>, transport: T) -> Self {
+        Self {
+            url: url.into(),
+            commitment: Commitment::Confirmed,
+            transport,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Commitment of reads. Defaults to `Confirmed`.
+    pub fn with_commitment(mut self, commitment: Commitment) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Calls `method` and returns the `result` member of the response.
+    pub fn call(&self, method: &str, params: Value) -> CCIHSResult<Value> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id.fetch_add(1, Ordering::Relaxed),
+            "method": method,
+            "params": params,
+        });
+        let body = self.transport.post(&self.url, &request.to_string())?;
+        let mut response: Value = serde_json::from_str(&body)
+            .map_err(|e| CCIHSError::NetworkError(format!("{}: invalid response: {}", method, e)))?;
+        if let Some(error) = response.get("error") {
+            let message = error.get("message").and_then(Value::as_str).unwrap_or("unknown error");
+            return Err(CCIHSError::NetworkError(format!("{}: {}", method, message)));
+        }
+        Ok(response["result"].take())
+    }
+
+    fn config(&self) -> Value {
+        json!({ "commitment": self.commitment.as_str() })
+    }
+}
+
+fn unexpected(method: &str) -> CCIHSError {
+    CCIHSError::NetworkError(format!("{}: unexpected response shape", method))
+}
+
+impl<T: HttpTransport> ChainClient for RpcClient<T> {
+    fn get_account(&self, address: &Pubkey) -> CCIHSResult<Option<AccountData>> {
+        let method = "getAccountInfo";
+        let mut config = self.config();
+        config["encoding"] = json!("base64");
+        let result = self.call(method, json!([address.to_string(), config]))?;
+        let account = match &result["value"] {
+            Value::Null => return Ok(None),
+            account => account,
+        };
+        let data = account["data"][0].as_str().ok_or_else(|| unexpected(method))?;
+        Ok(Some(AccountData {
+            lamports: account["lamports"].as_u64().ok_or_else(|| unexpected(method))?,
+            owner: account["owner"]
+                .as_str()
+                .and_then(|owner| Pubkey::from_str(owner).ok())
+                .ok_or_else(|| unexpected(method))?,
+            data: from_base64(data)?,
+            executable: account["executable"].as_bool().unwrap_or(false),
+        }))
+    }
+
+    fn get_transaction_logs(&self, signature: &str) -> CCIHSResult<Option<Vec<String>>> {
+        let method = "getTransaction";
+        let mut config = self.config();
+        config["maxSupportedTransactionVersion"] = json!(0);
+        let result = self.call(method, json!([signature, config]))?;
+        if result.is_null() {
+            return Ok(None);
+        }
+        let logs = result["meta"]["logMessages"].as_array().ok_or_else(|| unexpected(method))?;
+        Ok(Some(logs.iter().filter_map(Value::as_str).map(str::to_string).collect()))
+    }
+
+    fn send_transaction(&self, transaction: &[u8]) -> CCIHSResult<String> {
+        let method = "sendTransaction";
+        let config = json!({ "encoding": "base64", "preflightCommitment": self.commitment.as_str() });
+        let result = self.call(method, json!([to_base64(transaction), config]))?;
+        result.as_str().map(str::to_string).ok_or_else(|| unexpected(method))
+    }
+
+    fn get_slot(&self, commitment: Commitment) -> CCIHSResult<u64> {
+        let method = "getSlot";
+        let result = self.call(method, json!([{ "commitment": commitment.as_str() }]))?;
+        result.as_u64().ok_or_else(|| unexpected(method))
+    }
+
+    fn get_signature_status(&self, signature: &str) -> CCIHSResult<Option<Commitment>> {
+        let method = "getSignatureStatuses";
+        let result = self.call(method, json!([[signature], { "searchTransactionHistory": true }]))?;
+        let status = &result["value"][0];
+        if status.is_null() {
+            return Ok(None);
+        }
+        if !status["err"].is_null() {
+            return Err(CCIHSError::NetworkError(format!("transaction {} failed: {}", signature, status["err"])));
+        }
+        status["confirmationStatus"]
+            .as_str()
+            .and_then(Commitment::from_name)
+            .map(Some)
+            .ok_or_else(|| unexpected(method))
+    }
+
+    fn get_latest_blockhash(&self) -> CCIHSResult<Hash> {
+        let method = "getLatestBlockhash";
+        let result = self.call(method, json!([self.config()]))?;
+        result["value"]["blockhash"]
+            .as_str()
+            .and_then(|hash| Hash::from_str(hash).ok())
+            .ok_or_else(|| unexpected(method))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use parking_lot::Mutex;
+
+    /// Answers with canned results and records the requests.
+    struct Canned {
+        results: Mutex<Vec<Value>>,
+        requests: Mutex<Vec<Value>>,
+    }
+
+    impl HttpTransport for Canned {
+        fn post(&self, _url: &str, body: &str) -> CCIHSResult<String> {
+            self.requests.lock().push(serde_json::from_str(body).unwrap());
+            Ok(json!({ "jsonrpc": "2.0", "id": 1, "result": self.results.lock().remove(0) }).to_string())
+        }
+    }
+
+    #[test]
+    fn test_parses_rpc_responses() {
+        let owner = Pubkey::new_unique();
+        let transport = Canned {
+            results: Mutex::new(vec![
+                json!({ "context": { "slot": 5 }, "value": {
+                    "data": [to_base64(b"account"), "base64"],
+                    "lamports": 1_000,
+                    "owner": owner.to_string(),
+                    "executable": false,
+                }}),
+                json!({ "context": { "slot": 5 }, "value": null }),
+                json!({ "context": { "slot": 5 }, "value": [{ "confirmationStatus": "finalized", "err": null }] }),
+            ]),
+            requests: Mutex::new(Vec::new()),
+        };
+        let client = RpcClient::with_transport("http://127.0.0.1:8899", transport);
+
+        let account = client.get_account(&Pubkey::new_unique()).unwrap().unwrap();
+        assert_eq!(account.data, b"account");
+        assert_eq!(account.owner, owner);
+        assert_eq!(client.get_account(&Pubkey::new_unique()).unwrap(), None);
+        assert!(client.is_final("sig", Commitment::Confirmed).unwrap());
+
+        let requests = client.transport.requests.lock();
+        assert_eq!(requests[0]["method"], "getAccountInfo");
+        assert_eq!(requests[0]["params"][1]["encoding"], "base64");
+    }
+}
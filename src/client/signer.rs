@@ -0,0 +1,47 @@
+// src/client/signer.rs
+
+use crate::{CCIHSError, CCIHSResult};
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::message::Message;
+use anchor_lang::solana_program::pubkey::Pubkey;
+
+/// Holds the key that pays for and signs off-chain transactions.
+pub trait TransactionSigner: Send + Sync {
+    fn pubkey(&self) -> Pubkey;
+
+    /// Ed25519 signature of a serialized transaction message.
+    fn sign_message(&self, message: &[u8]) -> [u8; 64];
+}
+
+#[cfg(feature = "rpc")]
+impl TransactionSigner for ed25519_dalek::Keypair {
+    fn pubkey(&self) -> Pubkey {
+        Pubkey::new_from_array(self.public.to_bytes())
+    }
+
+    fn sign_message(&self, message: &[u8]) -> [u8; 64] {
+        ed25519_dalek::Signer::sign(self, message).to_bytes()
+    }
+}
+
+/// Serializes `instructions` as a transaction paid and signed by `signer`,
+/// ready for [`ChainClient::send_transaction`](super::ChainClient::send_transaction).
+/// Instructions that need any other signature are rejected.
+pub fn sign_transaction(
+    signer: &dyn TransactionSigner,
+    instructions: &[Instruction],
+    recent_blockhash: Hash,
+) -> CCIHSResult<Vec<u8>> {
+    let message = Message::new_with_blockhash(instructions, Some(&signer.pubkey()), &recent_blockhash);
+    if message.header.num_required_signatures != 1 {
+        return Err(CCIHSError::UnsupportedOperation);
+    }
+    let message = message.serialize();
+
+    // A compact-u16 signature count of one, the signature, the message.
+    let mut transaction = vec![1];
+    transaction.extend_from_slice(&signer.sign_message(&message));
+    transaction.extend_from_slice(&message);
+    Ok(transaction)
+}
@@ -1,6 +1,6 @@
 // config/ccihs_config.rs
 
-use super::{ChainConfig, ProtocolConfig};
+use super::{ChainConfig, ConfigLoader, HookSpec, NetworkProfile, ProtocolConfig, ProtocolConfigTrait, QuorumConfig};
 use crate::types::{ChainId, ProtocolType};
 use crate::{CCIHSResult, CCIHSError};
 use std::collections::HashMap;
@@ -13,20 +13,42 @@ pub struct CCIHSConfig {
     pub default_protocol: ProtocolType,
     pub max_retries: u32,
     pub retry_delay: u64,
+    /// Hook pipeline installed by `CCIHSCore::new`, in order.
+    pub hooks: Vec<HookSpec>,
+    /// Chain names used in configuration files.
+    pub chain_aliases: HashMap<String, ChainId>,
+    /// Network profile the configuration was built from, if any.
+    pub network: Option<NetworkProfile>,
+    /// Routes whose messages travel over several protocols at once.
+    pub quorum: QuorumConfig,
 }
 
 impl CCIHSConfig {
     pub fn new() -> Self {
-        let mut config = Self {
+        let mut config = Self::empty();
+        config.load_from_env();
+        config
+    }
+
+    /// Defaults only, without reading the environment.
+    pub fn empty() -> Self {
+        Self {
             chains: HashMap::new(),
             protocols: HashMap::new(),
             default_protocol: ProtocolType::Wormhole,
             max_retries: 3,
             retry_delay: 1000,
-        };
+            hooks: Vec::new(),
+            chain_aliases: HashMap::new(),
+            network: None,
+            quorum: QuorumConfig::default(),
+        }
+    }
 
-        config.load_from_env();
-        config
+    /// Loads a TOML or JSON configuration file, then applies environment
+    /// overrides. Use [`ConfigLoader`] for more layers.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> CCIHSResult<Self> {
+        ConfigLoader::new().file(path.as_ref()).load()
     }
 
     pub fn add_chain(&mut self, chain_config: ChainConfig) {
@@ -34,7 +56,7 @@ impl CCIHSConfig {
     }
 
     pub fn add_protocol(&mut self, protocol_config: ProtocolConfig) {
-        self.protocols.insert(protocol_config.protocol_type.clone(), protocol_config);
+        self.protocols.insert(protocol_config.protocol_type(), protocol_config);
     }
 
     pub fn set_default_protocol(&mut self, protocol_type: ProtocolType) -> CCIHSResult<()> {
@@ -54,12 +76,20 @@ impl CCIHSConfig {
         self.protocols.get(protocol_type)
     }
 
-    fn load_from_env(&mut self) {
+    /// Environment overrides: `CCIHS_NETWORK` (see [`NetworkProfile::from_name`]),
+    /// `CCIHS_DEFAULT_PROTOCOL`, `CCIHS_MAX_RETRIES`, `CCIHS_RETRY_DELAY` and
+    /// `CCIHS_RPC_URL_<chain id>` for configured chains.
+    pub(crate) fn load_from_env(&mut self) {
+        if let Ok(network) = env::var("CCIHS_NETWORK") {
+            match NetworkProfile::from_name(&network) {
+                Some(profile) => self.apply_network(profile),
+                None => log::warn!("Ignoring unknown CCIHS_NETWORK `{}`", network),
+            }
+        }
+
         if let Ok(protocol) = env::var("CCIHS_DEFAULT_PROTOCOL") {
-            match protocol.as_str() {
-                "wormhole" => self.default_protocol = ProtocolType::Wormhole,
-                "layerzero" => self.default_protocol = ProtocolType::LayerZero,
-                _ => {},
+            if let Some(protocol) = ProtocolType::from_name(&protocol) {
+                self.default_protocol = protocol;
             }
         }
 
@@ -75,20 +105,23 @@ impl CCIHSConfig {
             }
         }
 
+        for (chain_id, chain) in self.chains.iter_mut() {
+            if let Ok(rpc_url) = env::var(format!("CCIHS_RPC_URL_{}", chain_id.0)) {
+                chain.rpc_url = rpc_url;
+            }
+        }
+
         // Add more environment variable loads as needed
     }
 
+    /// Fails with every problem found by [`validation_report`](Self::validation_report).
     pub fn validate(&self) -> CCIHSResult<()> {
-        if self.chains.is_empty() {
-            return Err(CCIHSError::NoConfiguredChains);
-        }
-        if self.protocols.is_empty() {
-            return Err(CCIHSError::NoConfiguredProtocols);
-        }
-        if !self.protocols.contains_key(&self.default_protocol) {
-            return Err(CCIHSError::InvalidDefaultProtocol);
+        let report = self.validation_report();
+        if report.is_ok() {
+            Ok(())
+        } else {
+            Err(CCIHSError::InvalidConfiguration(report))
         }
-        Ok(())
     }
 }
 
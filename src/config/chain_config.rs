@@ -1,5 +1,6 @@
 // config/chain_config.rs
 
+use super::NetworkProfile;
 use crate::types::ChainId;
 use std::collections::HashMap;
 
@@ -8,6 +9,9 @@ pub struct ChainConfig {
     pub chain_id: ChainId,
     pub rpc_url: String,
     pub contract_addresses: HashMap<String, String>,
+    /// Network the chain belongs to. Routes between chains of different
+    /// networks are refused.
+    pub network: Option<NetworkProfile>,
 }
 
 impl ChainConfig {
@@ -16,6 +20,7 @@ impl ChainConfig {
             chain_id,
             rpc_url,
             contract_addresses: HashMap::new(),
+            network: None,
         }
     }
 
@@ -0,0 +1,48 @@
+// config/hook_config.rs
+
+use crate::hooks::{
+    EncryptionHook, FeeCalculationHook, Hook, LoggingHook, MetricsHook, RateLimitingHook, ValidationHook,
+};
+use crate::types::HookType;
+use crate::utility::Clock;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Clone, Debug, PartialEq)]
+/// One of the built-in hooks, with its parameters.
+pub enum HookKind {
+    Logging,
+    Metrics,
+    Validation { max_payload_size: usize },
+    RateLimiting { max_messages: usize, window_secs: u64 },
+    FeeCalculation { fee_percentage: f64 },
+    Encryption { key: [u8; 32] },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A hook of the configured pipeline: which hook runs at which stage. Hooks
+/// are installed in configuration order by `CCIHSCore::new`.
+pub struct HookSpec {
+    pub stage: HookType,
+    pub kind: HookKind,
+}
+
+impl HookSpec {
+    pub fn new(stage: HookType, kind: HookKind) -> Self {
+        Self { stage, kind }
+    }
+
+    /// Builds the hook. Time based hooks read the time from `clock`.
+    pub fn build(&self, clock: Arc<dyn Clock>) -> Box<dyn Hook> {
+        match &self.kind {
+            HookKind::Logging => Box::new(LoggingHook),
+            HookKind::Metrics => Box::new(MetricsHook::new()),
+            HookKind::Validation { max_payload_size } => Box::new(ValidationHook::new(*max_payload_size)),
+            HookKind::RateLimiting { max_messages, window_secs } => {
+                Box::new(RateLimitingHook::with_clock(*max_messages, Duration::from_secs(*window_secs), clock))
+            }
+            HookKind::FeeCalculation { fee_percentage } => Box::new(FeeCalculationHook::new(*fee_percentage)),
+            HookKind::Encryption { key } => Box::new(EncryptionHook::new(*key)),
+        }
+    }
+}
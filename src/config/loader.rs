@@ -0,0 +1,624 @@
+// config/loader.rs
+
+use super::{CCIHSConfig, ChainConfig, ProtocolConfig, HookKind, HookSpec, NetworkProfile, QuorumPolicy};
+use crate::types::{ChainId, HookType, ProtocolType};
+use crate::{CCIHSResult, CCIHSError};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Programmatic override, applied after the file and environment layers.
+pub type ConfigOverride = Box<dyn FnOnce(&mut CCIHSConfig) -> CCIHSResult<()>>;
+
+/// Builds a [`CCIHSConfig`] from layers, later layers taking precedence:
+///
+/// 1. configuration files (TOML or JSON, by extension), in the order added
+/// 2. environment variables (see `CCIHSConfig::load_from_env`)
+/// 3. programmatic overrides, in the order added
+///
+/// String values in files may reference environment variables as `${NAME}`
+/// or `${NAME:-default}`, so secrets such as RPC API keys stay out of the
+/// file. `$$` is a literal `$`.
+///
+/// ```toml
+/// network = "mainnet"
+/// default_protocol = "wormhole"
+///
+/// [retry]
+/// max_retries = 5
+/// retry_delay = 2000
+///
+/// [chains.solana]
+/// chain_id = 1
+/// rpc_url = "https://api.mainnet-beta.solana.com"
+///
+/// [chains.ethereum]
+/// chain_id = 2
+/// rpc_url = "https://mainnet.infura.io/v3/${INFURA_KEY}"
+/// contract_addresses = { core_bridge = "0x98f3c9e6E3fAce36bAAd05FE09d375Ef1464288B" }
+///
+/// [protocols.wormhole]
+/// supported_chains = ["solana", "ethereum"]
+/// core_bridge_program = "worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth"
+/// finality = "finalized"
+///
+/// [protocols.hyperlane]
+/// supported_chains = ["solana", "ethereum"]
+/// mailbox = "E588QtVUvresuXq2KoNEwAmoifCzYGpRBdHByN9KQMbi"
+///
+/// # Validators for messages from origin domain 1 (Ethereum)
+/// [protocols.hyperlane.validator_sets.1]
+/// validators = ["0x03c842db86a6a3e524d4a6615390c1ea8e2b9541"]
+/// threshold = 1
+///
+/// # Send over both protocols, execute once both delivered the message
+/// [quorum]
+/// protocols = ["wormhole", "hyperlane"]
+/// threshold = 2
+///
+/// [[quorum.routes]]
+/// source = "ethereum"
+/// destination = "solana"
+/// protocols = ["wormhole", "hyperlane"]
+/// threshold = 1
+///
+/// [[hooks]]
+/// stage = "pre_dispatch"
+/// kind = "rate_limiting"
+/// max_messages = 100
+/// window_secs = 60
+/// ```
+pub struct ConfigLoader {
+    files: Vec<PathBuf>,
+    use_env: bool,
+    overrides: Vec<ConfigOverride>,
+}
+
+impl ConfigLoader {
+    pub fn new() -> Self {
+        Self {
+            files: Vec::new(),
+            use_env: true,
+            overrides: Vec::new(),
+        }
+    }
+
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.files.push(path.into());
+        self
+    }
+
+    /// Skips the environment layer.
+    pub fn without_env(mut self) -> Self {
+        self.use_env = false;
+        self
+    }
+
+    pub fn override_with<F>(mut self, apply: F) -> Self
+    where
+        F: FnOnce(&mut CCIHSConfig) -> CCIHSResult<()> + 'static,
+    {
+        self.overrides.push(Box::new(apply));
+        self
+    }
+
+    pub fn load(self) -> CCIHSResult<CCIHSConfig> {
+        let mut config = CCIHSConfig::empty();
+        for path in &self.files {
+            apply_file(&mut config, path)?;
+        }
+        if self.use_env {
+            config.load_from_env();
+        }
+        for apply in self.overrides {
+            apply(&mut config)?;
+        }
+        Ok(config)
+    }
+}
+
+impl Default for ConfigLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads one configuration file into `config`. Chains are merged by chain
+/// ID; protocols, the default protocol, retry settings and the hook pipeline
+/// replace what earlier layers set.
+pub fn apply_file(config: &mut CCIHSConfig, path: &Path) -> CCIHSResult<()> {
+    let file = FileContext { path: path.display().to_string() };
+
+    let contents = std::fs::read_to_string(path).map_err(|e| file.error("", e.to_string()))?;
+    let root = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("toml") => {
+            let value: toml::Value = toml::from_str(&contents).map_err(|e| file.error("", e.to_string()))?;
+            serde_json::to_value(value).map_err(|e| file.error("", e.to_string()))?
+        }
+        Some("json") => serde_json::from_str(&contents).map_err(|e| file.error("", e.to_string()))?,
+        _ => return Err(file.error("", "unsupported format, expected a .toml or .json file")),
+    };
+    let root = file.object("", &root)?;
+
+    // The network profile first, so the file's own entries take precedence
+    // over the profile's defaults.
+    if let Some(network) = root.get("network") {
+        let name = file.string("network", network)?;
+        let profile = NetworkProfile::from_name(&name)
+            .ok_or_else(|| file.error("network", format!("unknown network `{}`", name)))?;
+        config.apply_network(profile);
+    }
+
+    // Chains next, so protocols can refer to them by name. Names defined by
+    // earlier files stay usable.
+    if let Some(chains) = root.get("chains") {
+        for (name, chain) in file.object("chains", chains)? {
+            let key = format!("chains.{}", name);
+            let mut chain_config = file.chain(&key, chain)?;
+            if let Some(existing) = config.chains.get(&chain_config.chain_id) {
+                if chain_config.network.is_none() {
+                    chain_config.network = existing.network;
+                }
+                for (address_name, address) in &existing.contract_addresses {
+                    chain_config.contract_addresses
+                        .entry(address_name.clone())
+                        .or_insert_with(|| address.clone());
+                }
+            }
+            config.chain_aliases.insert(name.clone(), chain_config.chain_id);
+            config.add_chain(chain_config);
+        }
+    }
+
+    let chain_names = config.chain_aliases.clone();
+    for (name, value) in root {
+        match name.as_str() {
+            "chains" | "network" => {}
+            "default_protocol" => {
+                let protocol = file.string(name, value)?;
+                config.default_protocol = ProtocolType::from_name(&protocol)
+                    .ok_or_else(|| file.error(name, format!("unknown protocol `{}`", protocol)))?;
+            }
+            "retry" => {
+                for (field, value) in file.object(name, value)? {
+                    let key = format!("retry.{}", field);
+                    match field.as_str() {
+                        "max_retries" => config.max_retries = file.number(&key, value)?,
+                        "retry_delay" => config.retry_delay = file.number(&key, value)?,
+                        _ => return Err(file.error(&key, "unknown key")),
+                    }
+                }
+            }
+            "protocols" => {
+                config.protocols.clear();
+                for (protocol_name, protocol) in file.object(name, value)? {
+                    let key = format!("protocols.{}", protocol_name);
+                    let protocol_config = file.protocol(&key, protocol_name, protocol, &chain_names)?;
+                    config.add_protocol(protocol_config);
+                }
+            }
+            "quorum" => {
+                let table = file.object(name, value)?;
+                config.quorum.default = None;
+                config.quorum.routes.clear();
+                if table.contains_key("protocols") || table.contains_key("threshold") {
+                    config.quorum.default = Some(file.quorum_policy(name, table)?);
+                }
+                for (field, value) in table {
+                    let key = format!("quorum.{}", field);
+                    match field.as_str() {
+                        "protocols" | "threshold" => {}
+                        "routes" => {
+                            for (index, route) in file.array(&key, value)?.iter().enumerate() {
+                                let route_key = format!("{}[{}]", key, index);
+                                let route = file.object(&route_key, route)?;
+                                let source = file.chain_ref(
+                                    &format!("{}.source", route_key),
+                                    file.required(&route_key, route, "source")?,
+                                    &chain_names,
+                                )?;
+                                let destination = file.chain_ref(
+                                    &format!("{}.destination", route_key),
+                                    file.required(&route_key, route, "destination")?,
+                                    &chain_names,
+                                )?;
+                                for field in route.keys() {
+                                    if !["source", "destination", "protocols", "threshold"].contains(&field.as_str()) {
+                                        return Err(file.error(&format!("{}.{}", route_key, field), "unknown key"));
+                                    }
+                                }
+                                config.quorum.set_route(source, destination, file.quorum_policy(&route_key, route)?);
+                            }
+                        }
+                        _ => return Err(file.error(&key, "unknown key")),
+                    }
+                }
+            }
+            "hooks" => {
+                config.hooks = file.array(name, value)?
+                    .iter()
+                    .enumerate()
+                    .map(|(index, hook)| file.hook(&format!("hooks[{}]", index), hook))
+                    .collect::<CCIHSResult<_>>()?;
+            }
+            _ => return Err(file.error(name, "unknown key")),
+        }
+    }
+
+    Ok(())
+}
+
+struct FileContext {
+    path: String,
+}
+
+impl FileContext {
+    fn error(&self, key: &str, reason: impl Into<String>) -> CCIHSError {
+        CCIHSError::InvalidConfigEntry {
+            path: self.path.clone(),
+            key: key.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    fn object<'a>(&self, key: &str, value: &'a Value) -> CCIHSResult<&'a Map<String, Value>> {
+        value.as_object().ok_or_else(|| self.error(key, "expected a table"))
+    }
+
+    fn array<'a>(&self, key: &str, value: &'a Value) -> CCIHSResult<&'a Vec<Value>> {
+        value.as_array().ok_or_else(|| self.error(key, "expected an array"))
+    }
+
+    /// String value with environment variables interpolated.
+    fn string(&self, key: &str, value: &Value) -> CCIHSResult<String> {
+        let raw = value.as_str().ok_or_else(|| self.error(key, "expected a string"))?;
+        interpolate(raw).map_err(|reason| self.error(key, reason))
+    }
+
+    fn number<T: TryFrom<u64>>(&self, key: &str, value: &Value) -> CCIHSResult<T> {
+        value
+            .as_u64()
+            .and_then(|number| T::try_from(number).ok())
+            .ok_or_else(|| self.error(key, "expected a non-negative integer in range"))
+    }
+
+    fn required<'a>(&self, key: &str, table: &'a Map<String, Value>, field: &str) -> CCIHSResult<&'a Value> {
+        table.get(field).ok_or_else(|| self.error(&format!("{}.{}", key, field), "missing"))
+    }
+
+    fn chain(&self, key: &str, value: &Value) -> CCIHSResult<ChainConfig> {
+        let table = self.object(key, value)?;
+        let chain_id = self.number::<u16>(&format!("{}.chain_id", key), self.required(key, table, "chain_id")?)?;
+        let rpc_url = self.string(&format!("{}.rpc_url", key), self.required(key, table, "rpc_url")?)?;
+        let mut chain = ChainConfig::new(ChainId(chain_id), rpc_url);
+
+        for (field, value) in table {
+            match field.as_str() {
+                "chain_id" | "rpc_url" => {}
+                "network" => {
+                    let name = self.string(&format!("{}.network", key), value)?;
+                    chain.network = Some(NetworkProfile::from_name(&name).ok_or_else(|| {
+                        self.error(&format!("{}.network", key), format!("unknown network `{}`", name))
+                    })?);
+                }
+                "contract_addresses" => {
+                    let field_key = format!("{}.contract_addresses", key);
+                    for (name, address) in self.object(&field_key, value)? {
+                        let address = self.string(&format!("{}.{}", field_key, name), address)?;
+                        chain.add_contract_address(name, &address);
+                    }
+                }
+                _ => return Err(self.error(&format!("{}.{}", key, field), "unknown key")),
+            }
+        }
+        Ok(chain)
+    }
+
+    fn protocol(
+        &self,
+        key: &str,
+        name: &str,
+        value: &Value,
+        chain_names: &HashMap<String, ChainId>,
+    ) -> CCIHSResult<ProtocolConfig> {
+        let protocol_type = ProtocolType::from_name(name)
+            .ok_or_else(|| self.error(key, format!("unknown protocol `{}`", name)))?;
+        let table = self.object(key, value)?;
+
+        let mut supported_chains = HashSet::new();
+        let mut params = HashMap::new();
+        for (field, value) in table {
+            let field_key = format!("{}.{}", key, field);
+            if field == "supported_chains" {
+                for (index, chain) in self.array(&field_key, value)?.iter().enumerate() {
+                    let chain_key = format!("{}[{}]", field_key, index);
+                    supported_chains.insert(self.chain_ref(&chain_key, chain, chain_names)?);
+                }
+                continue;
+            }
+
+            self.param(&field_key, field, value, &mut params)?;
+        }
+
+        ProtocolConfig::from_params(protocol_type, supported_chains, &params).map_err(|e| match e {
+            CCIHSError::InvalidProtocolSettings(reason) => self.error(key, reason),
+            other => other,
+        })
+    }
+
+    /// A chain given by its name in `[chains]` or by its ID.
+    fn chain_ref(&self, key: &str, value: &Value, chain_names: &HashMap<String, ChainId>) -> CCIHSResult<ChainId> {
+        match value {
+            Value::Number(_) => Ok(ChainId(self.number(key, value)?)),
+            Value::String(chain_name) => chain_names
+                .get(chain_name)
+                .copied()
+                .ok_or_else(|| self.error(key, format!("unknown chain `{}`", chain_name))),
+            _ => Err(self.error(key, "expected a chain name or ID")),
+        }
+    }
+
+    fn quorum_policy(&self, key: &str, table: &Map<String, Value>) -> CCIHSResult<QuorumPolicy> {
+        let protocols_key = format!("{}.protocols", key);
+        let protocols = self
+            .array(&protocols_key, self.required(key, table, "protocols")?)?
+            .iter()
+            .enumerate()
+            .map(|(index, protocol)| {
+                let protocol_key = format!("{}[{}]", protocols_key, index);
+                let protocol = self.string(&protocol_key, protocol)?;
+                ProtocolType::from_name(&protocol)
+                    .ok_or_else(|| self.error(&protocol_key, format!("unknown protocol `{}`", protocol)))
+            })
+            .collect::<CCIHSResult<Vec<_>>>()?;
+        let threshold = self.number(&format!("{}.threshold", key), self.required(key, table, "threshold")?)?;
+        Ok(QuorumPolicy::new(protocols, threshold))
+    }
+
+    /// Adds one protocol parameter. Nested tables are flattened into dotted
+    /// names, e.g. `validator_sets.1.threshold`.
+    fn param(&self, key: &str, name: &str, value: &Value, params: &mut HashMap<String, String>) -> CCIHSResult<()> {
+        let param = match value {
+            Value::String(_) => self.string(key, value)?,
+            Value::Number(number) => number.to_string(),
+            Value::Bool(flag) => flag.to_string(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| self.string(&format!("{}[{}]", key, index), item))
+                .collect::<CCIHSResult<Vec<_>>>()?
+                .join(","),
+            Value::Object(table) => {
+                for (field, value) in table {
+                    self.param(&format!("{}.{}", key, field), &format!("{}.{}", name, field), value, params)?;
+                }
+                return Ok(());
+            }
+            _ => return Err(self.error(key, "expected a string, number, boolean, array or table")),
+        };
+        params.insert(name.to_string(), param);
+        Ok(())
+    }
+
+    fn hook(&self, key: &str, value: &Value) -> CCIHSResult<HookSpec> {
+        let table = self.object(key, value)?;
+        let field = |name: &str| self.required(key, table, name);
+        let field_key = |name: &str| format!("{}.{}", key, name);
+
+        let stage = self.string(&field_key("stage"), field("stage")?)?;
+        let stage = match stage.as_str() {
+            "pre_dispatch" => HookType::PreDispatch,
+            "post_dispatch" => HookType::PostDispatch,
+            "pre_execution" => HookType::PreExecution,
+            "post_execution" => HookType::PostExecution,
+            _ => return Err(self.error(&field_key("stage"), format!("unknown stage `{}`", stage))),
+        };
+
+        let kind = self.string(&field_key("kind"), field("kind")?)?;
+        let (kind, allowed): (HookKind, &[&str]) = match kind.as_str() {
+            "logging" => (HookKind::Logging, &[]),
+            "metrics" => (HookKind::Metrics, &[]),
+            "validation" => (
+                HookKind::Validation {
+                    max_payload_size: self.number(&field_key("max_payload_size"), field("max_payload_size")?)?,
+                },
+                &["max_payload_size"],
+            ),
+            "rate_limiting" => (
+                HookKind::RateLimiting {
+                    max_messages: self.number(&field_key("max_messages"), field("max_messages")?)?,
+                    window_secs: self.number(&field_key("window_secs"), field("window_secs")?)?,
+                },
+                &["max_messages", "window_secs"],
+            ),
+            "fee_calculation" => (
+                HookKind::FeeCalculation {
+                    fee_percentage: field("fee_percentage")?
+                        .as_f64()
+                        .filter(|fee| (0.0..=1.0).contains(fee))
+                        .ok_or_else(|| self.error(&field_key("fee_percentage"), "expected a number between 0 and 1"))?,
+                },
+                &["fee_percentage"],
+            ),
+            "encryption" => {
+                let key_hex = self.string(&field_key("key"), field("key")?)?;
+                let encryption_key = decode_key(&key_hex)
+                    .ok_or_else(|| self.error(&field_key("key"), "expected 32 bytes as 64 hex characters"))?;
+                (HookKind::Encryption { key: encryption_key }, &["key"])
+            }
+            _ => return Err(self.error(&field_key("kind"), format!("unknown hook `{}`", kind))),
+        };
+
+        for name in table.keys() {
+            if name != "stage" && name != "kind" && !allowed.contains(&name.as_str()) {
+                return Err(self.error(&field_key(name), "unknown key"));
+            }
+        }
+
+        Ok(HookSpec::new(stage, kind))
+    }
+}
+
+/// Replaces `${NAME}` and `${NAME:-default}` with environment variables.
+fn interpolate(raw: &str) -> Result<String, String> {
+    let mut output = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(position) = rest.find('$') {
+        output.push_str(&rest[..position]);
+        rest = &rest[position..];
+
+        if let Some(after) = rest.strip_prefix("$$") {
+            output.push('$');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("${") {
+            let end = after.find('}').ok_or_else(|| "unterminated `${`".to_string())?;
+            let (name, default) = match after[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&after[..end], None),
+            };
+            match (env::var(name), default) {
+                (Ok(value), _) => output.push_str(&value),
+                (Err(_), Some(default)) => output.push_str(default),
+                (Err(_), None) => return Err(format!("environment variable `{}` is not set", name)),
+            }
+            rest = &after[end + 1..];
+        } else {
+            output.push('$');
+            rest = &rest[1..];
+        }
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn decode_key(hex: &str) -> Option<[u8; 32]> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut key = [0u8; 32];
+    for (index, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(index * 2..index * 2 + 2)?, 16).ok()?;
+    }
+    Some(key)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::config::ProtocolConfigTrait;
+
+    fn write_file(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("ccihs-loader-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_interpolates_environment_variables() {
+        env::set_var("CCIHS_LOADER_TEST_KEY", "secret");
+        assert_eq!(interpolate("https://rpc/${CCIHS_LOADER_TEST_KEY}").unwrap(), "https://rpc/secret");
+        assert_eq!(interpolate("${CCIHS_LOADER_TEST_UNSET:-fallback}").unwrap(), "fallback");
+        assert_eq!(interpolate("cost: $$5").unwrap(), "cost: $5");
+        assert!(interpolate("${CCIHS_LOADER_TEST_UNSET}").is_err());
+        assert!(interpolate("${UNTERMINATED").is_err());
+    }
+
+    #[test]
+    fn test_loads_toml_and_json_files() {
+        let toml = write_file("load.toml", r#"
+default_protocol = "wormhole"
+
+[retry]
+max_retries = 7
+
+[chains.solana]
+chain_id = 1
+rpc_url = "https://solana.example"
+
+[protocols.wormhole]
+supported_chains = ["solana", 2]
+core_bridge_program = "worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth"
+"#);
+        let json = write_file("load.json", r#"{
+            "chains": { "ethereum": { "chain_id": 2, "rpc_url": "https://ethereum.example" } },
+            "retry": { "retry_delay": 250 }
+        }"#);
+
+        let config = ConfigLoader::new().file(&toml).file(&json).without_env().load().unwrap();
+        assert_eq!(config.max_retries, 7);
+        assert_eq!(config.retry_delay, 250);
+        assert_eq!(config.default_protocol, ProtocolType::Wormhole);
+        assert_eq!(config.get_chain_config(&ChainId::SOLANA).unwrap().rpc_url, "https://solana.example");
+        assert_eq!(config.get_chain_config(&ChainId::ETHEREUM).unwrap().rpc_url, "https://ethereum.example");
+        let wormhole = config.get_protocol_config(&ProtocolType::Wormhole).unwrap();
+        assert_eq!(
+            wormhole.supported_chains(),
+            &[ChainId::SOLANA, ChainId::ETHEREUM].into_iter().collect::<HashSet<_>>()
+        );
+
+        let unsupported = write_file("load.yaml", "network: mainnet");
+        assert!(ConfigLoader::new().file(&unsupported).without_env().load().is_err());
+        let unknown_key = write_file("unknown.toml", "colour = \"blue\"");
+        assert!(matches!(
+            ConfigLoader::new().file(&unknown_key).without_env().load(),
+            Err(CCIHSError::InvalidConfigEntry { key, .. }) if key == "colour"
+        ));
+
+        for path in [toml, json, unsupported, unknown_key] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_later_layers_take_precedence() {
+        let base = write_file("base.toml", r#"
+[retry]
+max_retries = 1
+retry_delay = 100
+
+[chains.solana]
+chain_id = 1
+rpc_url = "https://base.example"
+contract_addresses = { core_bridge = "base-core" }
+"#);
+        // Refers to `solana`, which only the base file names.
+        let overlay = write_file("overlay.toml", r#"
+[retry]
+max_retries = 2
+
+[chains.solana]
+chain_id = 1
+rpc_url = "https://overlay.example"
+
+[protocols.wormhole]
+supported_chains = ["solana"]
+core_bridge_program = "worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth"
+"#);
+
+        let config = ConfigLoader::new()
+            .file(&base)
+            .file(&overlay)
+            .without_env()
+            .override_with(|config| {
+                config.retry_delay = 500;
+                Ok(())
+            })
+            .load()
+            .unwrap();
+        assert_eq!(config.max_retries, 2);
+        assert_eq!(config.retry_delay, 500);
+        let solana = config.get_chain_config(&ChainId::SOLANA).unwrap();
+        assert_eq!(solana.rpc_url, "https://overlay.example");
+        assert_eq!(solana.contract_addresses.get("core_bridge").map(String::as_str), Some("base-core"));
+        assert!(config
+            .get_protocol_config(&ProtocolType::Wormhole)
+            .unwrap()
+            .supported_chains()
+            .contains(&ChainId::SOLANA));
+
+        std::fs::remove_file(base).unwrap();
+        std::fs::remove_file(overlay).unwrap();
+    }
+}
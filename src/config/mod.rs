@@ -2,11 +2,27 @@
 
 mod ccihs_config;
 mod chain_config;
-mod protocol_config;
+pub(crate) mod protocol_config;
+mod protocol_settings;
+mod hook_config;
+mod loader;
+mod validation;
+mod network;
+mod quorum;
 
 pub use ccihs_config::CCIHSConfig;
 pub use chain_config::ChainConfig;
-pub use protocol_config::ProtocolConfig;
+pub use hook_config::{HookKind, HookSpec};
+pub use quorum::{QuorumConfig, QuorumPolicy};
+pub use network::{NetworkProfile, SEPOLIA_CHAIN_ID, CORE_BRIDGE_ADDRESS, TOKEN_BRIDGE_ADDRESS};
+pub use validation::{ValidationIssue, ValidationReport};
+pub use loader::{ConfigLoader, ConfigOverride, apply_file};
+pub use protocol_config::{ProtocolConfig, ProtocolConfigTrait};
+pub use protocol_settings::{
+    ProtocolSettings, WormholeSettings, LayerZeroSettings, CctpSettings, HyperlaneSettings,
+    HyperlaneValidatorSet, AxelarSettings, IbcSettings, IbcChannel,
+    WORMHOLE_FINALITY_CONFIRMED, WORMHOLE_FINALITY_FINALIZED,
+};
 
 // If you want to re-export everything from these modules, you can use:
 // pub use ccihs_config::*;
@@ -0,0 +1,278 @@
+// config/network.rs
+
+use super::{CCIHSConfig, ChainConfig, ProtocolConfig, ProtocolSettings, WormholeSettings};
+use super::{WORMHOLE_FINALITY_CONFIRMED, WORMHOLE_FINALITY_FINALIZED};
+use crate::types::{ChainId, ProtocolType};
+use crate::{CCIHSResult, CCIHSError};
+use solana_program::pubkey::Pubkey;
+use std::fmt;
+use std::str::FromStr;
+
+/// Wormhole chain ID of Ethereum Sepolia, the Ethereum chain of the testnet.
+pub const SEPOLIA_CHAIN_ID: ChainId = ChainId(10002);
+
+/// Contract address names filled in by the network profiles.
+pub const CORE_BRIDGE_ADDRESS: &str = "core_bridge";
+pub const TOKEN_BRIDGE_ADDRESS: &str = "token_bridge";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// Wormhole deployments CCIHS ships addresses for.
+pub enum NetworkProfile {
+    Mainnet,
+    /// Solana devnet and Ethereum Sepolia.
+    Testnet,
+    /// Wormhole's local development network (Tilt): a local Solana test
+    /// validator and a local EVM devnet.
+    Localnet,
+}
+
+struct ProfileChain {
+    chain_id: ChainId,
+    rpc_url: &'static str,
+    core_bridge: &'static str,
+    token_bridge: &'static str,
+}
+
+impl NetworkProfile {
+    pub const ALL: [NetworkProfile; 3] = [NetworkProfile::Mainnet, NetworkProfile::Testnet, NetworkProfile::Localnet];
+
+    /// Parses `mainnet`, `testnet` (or `devnet`) and `localnet` (or `local`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "mainnet" => Some(NetworkProfile::Mainnet),
+            "testnet" | "devnet" => Some(NetworkProfile::Testnet),
+            "localnet" | "local" => Some(NetworkProfile::Localnet),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NetworkProfile::Mainnet => "mainnet",
+            NetworkProfile::Testnet => "testnet",
+            NetworkProfile::Localnet => "localnet",
+        }
+    }
+
+    /// Finality requested for messages posted from Solana.
+    pub fn default_finality(&self) -> u8 {
+        match self {
+            NetworkProfile::Mainnet => WORMHOLE_FINALITY_FINALIZED,
+            NetworkProfile::Testnet | NetworkProfile::Localnet => WORMHOLE_FINALITY_CONFIRMED,
+        }
+    }
+
+    /// Wormhole core bridge program on Solana.
+    pub fn wormhole_core_bridge(&self) -> Pubkey {
+        parse_pubkey(self.solana().core_bridge)
+    }
+
+    /// Wormhole token bridge program on Solana.
+    pub fn wormhole_token_bridge(&self) -> Pubkey {
+        parse_pubkey(self.solana().token_bridge)
+    }
+
+    pub fn chain_ids(&self) -> Vec<ChainId> {
+        self.chains().iter().map(|chain| chain.chain_id).collect()
+    }
+
+    /// The profile `chain_id` belongs to, if it belongs to only one, as
+    /// Sepolia does. Solana and Ethereum have the same chain ID on every
+    /// network.
+    pub fn from_chain_id(chain_id: ChainId) -> Option<Self> {
+        let mut profiles = Self::ALL.into_iter().filter(|profile| profile.chain_ids().contains(&chain_id));
+        match (profiles.next(), profiles.next()) {
+            (Some(profile), None) => Some(profile),
+            _ => None,
+        }
+    }
+
+    /// The profile whose core bridge `chain` is configured with.
+    pub fn from_core_bridge(chain: &ChainConfig) -> Option<Self> {
+        let address = chain.contract_addresses.get(CORE_BRIDGE_ADDRESS)?;
+        Self::ALL.into_iter().find(|profile| {
+            profile.chains().iter().any(|profile_chain| {
+                profile_chain.chain_id == chain.chain_id && profile_chain.core_bridge.eq_ignore_ascii_case(address)
+            })
+        })
+    }
+
+    fn solana(&self) -> &'static ProfileChain {
+        &self.chains()[0]
+    }
+
+    fn chains(&self) -> &'static [ProfileChain] {
+        match self {
+            NetworkProfile::Mainnet => &[
+                ProfileChain {
+                    chain_id: ChainId::SOLANA,
+                    rpc_url: "https://api.mainnet-beta.solana.com",
+                    core_bridge: "worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth",
+                    token_bridge: "wormDTUJ6AWPNvk59vGQbDvGJmqbDTdgWgAqcLBCgUb",
+                },
+                ProfileChain {
+                    chain_id: ChainId::ETHEREUM,
+                    rpc_url: "https://ethereum-rpc.publicnode.com",
+                    core_bridge: "0x98f3c9e6E3fAce36bAAd05FE09d375Ef1464288B",
+                    token_bridge: "0x3ee18B2214AFF97000D974cf647E7C347E8fa585",
+                },
+            ],
+            NetworkProfile::Testnet => &[
+                ProfileChain {
+                    chain_id: ChainId::SOLANA,
+                    rpc_url: "https://api.devnet.solana.com",
+                    core_bridge: "3u8hJUVTA4jH1wYAyUur7FFZVQ8H635K3tSHHF4ssjQ5",
+                    token_bridge: "DZnkkTmCiFWfYTfT41X3Rd1kDgozqzxWaHqsw6W4x2oe",
+                },
+                ProfileChain {
+                    chain_id: SEPOLIA_CHAIN_ID,
+                    rpc_url: "https://ethereum-sepolia-rpc.publicnode.com",
+                    core_bridge: "0x4a8bc80Ed5a4067f1CCf107057b8270E0cC11A78",
+                    token_bridge: "0xDB5492265f6038831E89f495670FF909aDe94bd9",
+                },
+            ],
+            NetworkProfile::Localnet => &[
+                ProfileChain {
+                    chain_id: ChainId::SOLANA,
+                    rpc_url: "http://127.0.0.1:8899",
+                    core_bridge: "Bridge1p5gheXUvJ6jGWGeCsgPKgnE3YgdGKRVCMY9o",
+                    token_bridge: "B6RHG3mfcckmrYN1UhmJzyS1XX3fZKbkeUcpJe9Sy3FE",
+                },
+                ProfileChain {
+                    chain_id: ChainId::ETHEREUM,
+                    rpc_url: "http://127.0.0.1:8545",
+                    core_bridge: "0xC89Ce4735882C9F0f0FE26686c53074E09B0D550",
+                    token_bridge: "0x0290FB167208Af455bB137780163b7B7a9a10C16",
+                },
+            ],
+        }
+    }
+}
+
+impl fmt::Display for NetworkProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+fn parse_pubkey(address: &str) -> Pubkey {
+    Pubkey::from_str(address).expect("network profile addresses are valid")
+}
+
+impl CCIHSConfig {
+    /// Configuration for `profile` with environment overrides applied.
+    pub fn for_network(profile: NetworkProfile) -> Self {
+        let mut config = Self::empty();
+        config.apply_network(profile);
+        config.load_from_env();
+        config
+    }
+
+    /// Fills in the chains and Wormhole settings of `profile`. The profile
+    /// only provides defaults: chains, their networks, contract addresses
+    /// and the Wormhole protocol configured before are kept as they are.
+    pub fn apply_network(&mut self, profile: NetworkProfile) {
+        self.network = Some(profile);
+
+        for profile_chain in profile.chains() {
+            let chain = self.chains
+                .entry(profile_chain.chain_id)
+                .or_insert_with(|| ChainConfig::new(profile_chain.chain_id, profile_chain.rpc_url.to_string()));
+            chain.network.get_or_insert(profile);
+            chain.contract_addresses
+                .entry(CORE_BRIDGE_ADDRESS.to_string())
+                .or_insert_with(|| profile_chain.core_bridge.to_string());
+            chain.contract_addresses
+                .entry(TOKEN_BRIDGE_ADDRESS.to_string())
+                .or_insert_with(|| profile_chain.token_bridge.to_string());
+        }
+
+        if !self.protocols.contains_key(&ProtocolType::Wormhole) {
+            let mut settings = WormholeSettings::new(profile.wormhole_core_bridge());
+            settings.token_bridge_program = Some(profile.wormhole_token_bridge());
+            settings.finality = profile.default_finality();
+            settings.supported_chains = profile.chain_ids().into_iter().collect();
+            self.add_protocol(ProtocolConfig::new(ProtocolSettings::Wormhole(settings)));
+        }
+    }
+
+    /// Network of `chain_id`: the one its configuration names, else the
+    /// profile whose core bridge it is configured with, else the only
+    /// profile with that chain ID, else the network of the configuration.
+    pub fn chain_network(&self, chain_id: ChainId) -> Option<NetworkProfile> {
+        let chain = self.chains.get(&chain_id);
+        chain
+            .and_then(|chain| chain.network)
+            .or_else(|| chain.and_then(NetworkProfile::from_core_bridge))
+            .or_else(|| NetworkProfile::from_chain_id(chain_id))
+            .or(self.network)
+    }
+
+    /// Refuses routes between chains of different networks, e.g. a devnet
+    /// Solana and mainnet Ethereum. See [`chain_network`](Self::chain_network).
+    pub fn check_route(&self, source_chain: ChainId, destination_chain: ChainId) -> CCIHSResult<()> {
+        match (self.chain_network(source_chain), self.chain_network(destination_chain)) {
+            (Some(source), Some(destination)) if source != destination => Err(CCIHSError::NetworkMismatch {
+                source_chain: source_chain.0,
+                source_network: source.to_string(),
+                destination_chain: destination_chain.0,
+                destination_network: destination.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_profile_addresses_are_valid() {
+        for profile in NetworkProfile::ALL {
+            for chain in profile.chains() {
+                let format = chain.chain_id.address_format().expect("known address format");
+                assert!(format.is_valid(chain.core_bridge), "{} core bridge on {}", profile, chain.chain_id);
+                assert!(format.is_valid(chain.token_bridge), "{} token bridge on {}", profile, chain.chain_id);
+            }
+            assert_eq!(NetworkProfile::from_name(profile.name()), Some(profile));
+        }
+    }
+
+    #[test]
+    fn test_infers_networks_of_chains_without_one() {
+        let mut config = CCIHSConfig::empty();
+        config.add_chain(ChainConfig::new(SEPOLIA_CHAIN_ID, "https://sepolia.example".to_string()));
+        let mut ethereum = ChainConfig::new(ChainId::ETHEREUM, "https://ethereum.example".to_string());
+        ethereum.add_contract_address(CORE_BRIDGE_ADDRESS, "0x98f3c9e6e3face36baad05fe09d375ef1464288b");
+        config.add_chain(ethereum);
+        config.add_chain(ChainConfig::new(ChainId::SOLANA, "https://solana.example".to_string()));
+
+        assert_eq!(config.chain_network(SEPOLIA_CHAIN_ID), Some(NetworkProfile::Testnet));
+        assert_eq!(config.chain_network(ChainId::ETHEREUM), Some(NetworkProfile::Mainnet));
+        assert_eq!(config.chain_network(ChainId::SOLANA), None);
+        assert!(matches!(
+            config.check_route(SEPOLIA_CHAIN_ID, ChainId::ETHEREUM),
+            Err(CCIHSError::NetworkMismatch { .. })
+        ));
+        assert!(config.check_route(ChainId::SOLANA, ChainId::ETHEREUM).is_ok());
+
+        config.network = Some(NetworkProfile::Testnet);
+        assert_eq!(config.chain_network(ChainId::SOLANA), Some(NetworkProfile::Testnet));
+        assert!(config.check_route(ChainId::SOLANA, SEPOLIA_CHAIN_ID).is_ok());
+        assert!(config.check_route(ChainId::SOLANA, ChainId::ETHEREUM).is_err());
+    }
+
+    #[test]
+    fn test_profiles_keep_explicit_networks() {
+        let mut config = CCIHSConfig::empty();
+        let mut solana = ChainConfig::new(ChainId::SOLANA, "https://api.devnet.solana.com".to_string());
+        solana.network = Some(NetworkProfile::Testnet);
+        config.add_chain(solana);
+
+        config.apply_network(NetworkProfile::Mainnet);
+        assert_eq!(config.chain_network(ChainId::SOLANA), Some(NetworkProfile::Testnet));
+        assert_eq!(config.chain_network(ChainId::ETHEREUM), Some(NetworkProfile::Mainnet));
+        assert!(config.check_route(ChainId::ETHEREUM, ChainId::SOLANA).is_err());
+    }
+}
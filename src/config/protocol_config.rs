@@ -1,49 +1,52 @@
 // config/Protocol_config.rs
 
+use super::protocol_settings::ProtocolSettings;
 use crate::types::{ChainId, ProtocolType};
+use crate::CCIHSResult;
 use std::collections::{HashSet, HashMap};
 
 pub trait ProtocolConfigTrait {
     fn protocol_type(&self) -> ProtocolType;
     fn supported_chains(&self) -> &HashSet<ChainId>;
-    fn additional_params(&self) -> &HashMap<String, String>;
+    fn validate(&self) -> CCIHSResult<()>;
 }
 
 #[derive(Clone, Debug)]
+/// Configuration of one protocol. Its protocol type is the variant of
+/// `settings`.
 pub struct ProtocolConfig {
-    pub protocol_type: ProtocolType,
-    pub supported_chains: HashSet<ChainId>,
-    pub additional_params: HashMap<String, String>,
+    pub settings: ProtocolSettings,
 }
 
 impl ProtocolConfig {
-    pub fn new(protocol_type: ProtocolType) -> Self {
-        Self {
-            protocol_type,
-            supported_chains: HashSet::new(),
-            additional_params: HashMap::new(),
-        }
+    pub fn new(settings: ProtocolSettings) -> Self {
+        Self { settings }
     }
 
-    pub fn add_supported_chain(&mut self, chain_id: ChainId) {
-        self.supported_chains.insert(chain_id);
+    /// Migrates a configuration written in the legacy string map form.
+    pub fn from_params(
+        protocol_type: ProtocolType,
+        supported_chains: HashSet<ChainId>,
+        params: &HashMap<String, String>,
+    ) -> CCIHSResult<Self> {
+        Ok(Self::new(ProtocolSettings::from_params(protocol_type, supported_chains, params)?))
     }
 
-    pub fn add_param(&mut self, key: &str, value: &str) {
-        self.additional_params.insert(key.to_string(), value.to_string());
+    pub fn add_supported_chain(&mut self, chain_id: ChainId) {
+        self.settings.supported_chains_mut().insert(chain_id);
     }
 }
 
 impl ProtocolConfigTrait for ProtocolConfig {
     fn protocol_type(&self) -> ProtocolType {
-        self.protocol_type.clone()
+        self.settings.protocol_type()
     }
 
     fn supported_chains(&self) -> &HashSet<ChainId> {
-        &self.supported_chains
+        self.settings.supported_chains()
     }
 
-    fn additional_params(&self) -> &HashMap<String, String> {
-        &self.additional_params
+    fn validate(&self) -> CCIHSResult<()> {
+        self.settings.validate()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,862 @@
+// config/protocol_settings.rs
+
+use super::protocol_config::ProtocolConfigTrait;
+use crate::types::{ChainId, ProtocolType};
+use crate::{CCIHSResult, CCIHSError};
+use crate::protocols::cctp::domain_for_chain;
+use crate::protocols::hyperlane::{self, SOLANA_MAINNET_DOMAIN};
+use crate::protocols::axelar::{self, WeightedSigner};
+use crate::protocols::wormhole::DeliveryPrice;
+use crate::utility::ecdsa::EvmAddress;
+use crate::utility::encoding::from_hex;
+use solana_program::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// Wormhole finality values, as stored in `GeneralMessageConfig::finality`.
+pub const WORMHOLE_FINALITY_CONFIRMED: u8 = 0;
+pub const WORMHOLE_FINALITY_FINALIZED: u8 = 1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WormholeSettings {
+    pub supported_chains: HashSet<ChainId>,
+    /// Wormhole core bridge program.
+    pub core_bridge_program: Pubkey,
+    /// Wormhole token bridge program. Only needed for token transfers.
+    pub token_bridge_program: Option<Pubkey>,
+    /// `WORMHOLE_FINALITY_CONFIRMED` or `WORMHOLE_FINALITY_FINALIZED`.
+    pub finality: u8,
+    /// AKA nonce.
+    pub batch_id: u32,
+    /// Relayer fee as a fraction of `relayer_fee_precision`.
+    pub relayer_fee: u32,
+    pub relayer_fee_precision: u32,
+    /// Generic relayer delivery provider, paid for delivery requests.
+    pub delivery_provider: Option<Pubkey>,
+    /// Delivery pricing by target chain.
+    pub delivery_prices: HashMap<ChainId, DeliveryPrice>,
+}
+
+impl WormholeSettings {
+    pub fn new(core_bridge_program: Pubkey) -> Self {
+        Self {
+            supported_chains: HashSet::new(),
+            core_bridge_program,
+            token_bridge_program: None,
+            finality: WORMHOLE_FINALITY_FINALIZED,
+            batch_id: 0,
+            relayer_fee: 0,
+            relayer_fee_precision: 100_000_000,
+            delivery_provider: None,
+            delivery_prices: HashMap::new(),
+        }
+    }
+
+    /// Lamports the delivery provider charges to deliver to `target_chain`.
+    pub fn quote_delivery(&self, target_chain: ChainId, gas_limit: u128, receiver_value: u128) -> CCIHSResult<u64> {
+        self.delivery_prices
+            .get(&target_chain)
+            .ok_or_else(|| invalid(format!("wormhole: no delivery price for chain ID {}", target_chain.0)))?
+            .quote(gas_limit, receiver_value)
+    }
+
+    fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.core_bridge_program == Pubkey::default() {
+            issues.push("wormhole: core_bridge_program is not set".to_string());
+        }
+        if self.token_bridge_program == Some(Pubkey::default()) {
+            issues.push("wormhole: token_bridge_program is the default pubkey".to_string());
+        }
+        if self.finality > WORMHOLE_FINALITY_FINALIZED {
+            issues.push(format!("wormhole: unknown finality {}", self.finality));
+        }
+        if self.relayer_fee_precision == 0 {
+            issues.push("wormhole: relayer_fee_precision must be non-zero".to_string());
+        } else if self.relayer_fee >= self.relayer_fee_precision {
+            issues.push("wormhole: relayer_fee must be less than relayer_fee_precision".to_string());
+        }
+        if self.delivery_provider.is_none() && !self.delivery_prices.is_empty() {
+            issues.push("wormhole: delivery_prices are set but delivery_provider is not".to_string());
+        }
+        for (chain, price) in &self.delivery_prices {
+            if price.native_price == 0 {
+                issues.push(format!("wormhole: delivery price for chain ID {} has no native_price", chain.0));
+            }
+        }
+        issues
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerZeroSettings {
+    pub supported_chains: HashSet<ChainId>,
+    /// LayerZero endpoint program.
+    pub endpoint: Pubkey,
+    /// Message libraries. The endpoint's defaults are used when unset.
+    pub send_library: Option<Pubkey>,
+    pub receive_library: Option<Pubkey>,
+    pub executor: Option<Pubkey>,
+    /// DVNs that must all verify a message.
+    pub required_dvns: Vec<Pubkey>,
+    /// DVNs of which `optional_dvn_threshold` must verify a message.
+    pub optional_dvns: Vec<Pubkey>,
+    pub optional_dvn_threshold: u8,
+    /// Block confirmations on the source chain before DVNs verify.
+    pub confirmations: u64,
+}
+
+impl LayerZeroSettings {
+    pub fn new(endpoint: Pubkey) -> Self {
+        Self {
+            supported_chains: HashSet::new(),
+            endpoint,
+            send_library: None,
+            receive_library: None,
+            executor: None,
+            required_dvns: Vec::new(),
+            optional_dvns: Vec::new(),
+            optional_dvn_threshold: 0,
+            confirmations: 1,
+        }
+    }
+
+    fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.endpoint == Pubkey::default() {
+            issues.push("layerzero: endpoint is not set".to_string());
+        }
+        if self.required_dvns.is_empty() && self.optional_dvn_threshold == 0 {
+            issues.push("layerzero: at least one DVN must verify messages".to_string());
+        }
+        if self.optional_dvn_threshold as usize > self.optional_dvns.len() {
+            issues.push("layerzero: optional_dvn_threshold exceeds the number of optional DVNs".to_string());
+        }
+        let mut seen = HashSet::new();
+        for dvn in self.required_dvns.iter().chain(self.optional_dvns.iter()) {
+            if !seen.insert(dvn) {
+                issues.push(format!("layerzero: DVN {} is listed twice", dvn));
+            }
+        }
+        if self.confirmations == 0 {
+            issues.push("layerzero: confirmations must be non-zero".to_string());
+        }
+        issues
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CctpSettings {
+    pub supported_chains: HashSet<ChainId>,
+    pub message_transmitter: Pubkey,
+    pub token_messenger_minter: Pubkey,
+    /// The only token CCTP moves.
+    pub usdc_mint: Pubkey,
+    /// EVM addresses of the enabled attesters.
+    pub attesters: Vec<EvmAddress>,
+    pub attestation_threshold: usize,
+}
+
+impl CctpSettings {
+    pub fn new(message_transmitter: Pubkey, token_messenger_minter: Pubkey, usdc_mint: Pubkey) -> Self {
+        Self {
+            supported_chains: HashSet::new(),
+            message_transmitter,
+            token_messenger_minter,
+            usdc_mint,
+            attesters: Vec::new(),
+            attestation_threshold: 1,
+        }
+    }
+
+    fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        for (name, key) in [
+            ("message_transmitter", &self.message_transmitter),
+            ("token_messenger_minter", &self.token_messenger_minter),
+            ("usdc_mint", &self.usdc_mint),
+        ] {
+            if *key == Pubkey::default() {
+                issues.push(format!("cctp: {} is not set", name));
+            }
+        }
+        if self.attestation_threshold == 0 {
+            issues.push("cctp: attestation_threshold must be non-zero".to_string());
+        } else if self.attestation_threshold > self.attesters.len() {
+            issues.push("cctp: attestation_threshold exceeds the number of attesters".to_string());
+        }
+        for chain in &self.supported_chains {
+            if domain_for_chain(*chain).is_none() {
+                issues.push(format!("cctp: chain ID {} has no CCTP domain", chain.0));
+            }
+        }
+        issues
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Validators whose checkpoints prove messages from one origin domain.
+pub struct HyperlaneValidatorSet {
+    pub validators: Vec<EvmAddress>,
+    pub threshold: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HyperlaneSettings {
+    pub supported_chains: HashSet<ChainId>,
+    /// Hyperlane mailbox program.
+    pub mailbox: Pubkey,
+    /// Hyperlane domain of this Solana cluster.
+    pub local_domain: u32,
+    /// Validator sets by origin domain.
+    pub validator_sets: HashMap<u32, HyperlaneValidatorSet>,
+}
+
+impl HyperlaneSettings {
+    pub fn new(mailbox: Pubkey) -> Self {
+        Self {
+            supported_chains: HashSet::new(),
+            mailbox,
+            local_domain: SOLANA_MAINNET_DOMAIN,
+            validator_sets: HashMap::new(),
+        }
+    }
+
+    fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.mailbox == Pubkey::default() {
+            issues.push("hyperlane: mailbox is not set".to_string());
+        }
+        let mut origins: Vec<_> = self.validator_sets.iter().collect();
+        origins.sort_by_key(|(origin, _)| **origin);
+        for (origin, set) in origins {
+            if set.threshold == 0 {
+                issues.push(format!("hyperlane: threshold of origin domain {} must be non-zero", origin));
+            } else if set.threshold > set.validators.len() {
+                issues.push(format!(
+                    "hyperlane: threshold of origin domain {} exceeds the number of validators",
+                    origin
+                ));
+            }
+        }
+        for chain in &self.supported_chains {
+            if *chain != ChainId::SOLANA && hyperlane::domain_for_chain(*chain).is_none() {
+                issues.push(format!("hyperlane: chain ID {} has no Hyperlane domain", chain.0));
+            }
+        }
+        issues
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AxelarSettings {
+    pub supported_chains: HashSet<ChainId>,
+    pub gateway: Pubkey,
+    pub gas_service: Pubkey,
+    /// Domain separator of the gateway, part of every digest operators sign.
+    pub domain_separator: [u8; 32],
+    /// Current operator set, sorted by increasing address.
+    pub operators: Vec<WeightedSigner>,
+    pub operator_threshold: u128,
+    pub operator_set_nonce: [u8; 32],
+    /// Gas service base fee in lamports.
+    pub base_fee: u64,
+    /// Destination gas prices in micro lamports per unit of gas.
+    pub gas_prices: HashMap<ChainId, u64>,
+    /// Applied to quotes, in basis points of the execution fee.
+    pub gas_multiplier_bps: u64,
+    /// Destination gas prepaid for each call.
+    pub gas_limit: u64,
+}
+
+impl AxelarSettings {
+    pub fn new(gateway: Pubkey, gas_service: Pubkey) -> Self {
+        Self {
+            supported_chains: HashSet::new(),
+            gateway,
+            gas_service,
+            domain_separator: [0; 32],
+            operators: Vec::new(),
+            operator_threshold: 1,
+            operator_set_nonce: [0; 32],
+            base_fee: 0,
+            gas_prices: HashMap::new(),
+            gas_multiplier_bps: 11_000,
+            gas_limit: 200_000,
+        }
+    }
+
+    fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        for (name, key) in [("gateway", &self.gateway), ("gas_service", &self.gas_service)] {
+            if *key == Pubkey::default() {
+                issues.push(format!("axelar: {} is not set", name));
+            }
+        }
+        let total_weight = self.operators.iter().fold(0u128, |total, operator| total.saturating_add(operator.weight));
+        if self.operator_threshold == 0 {
+            issues.push("axelar: operator_threshold must be non-zero".to_string());
+        } else if self.operator_threshold > total_weight {
+            issues.push("axelar: operator_threshold exceeds the total operator weight".to_string());
+        }
+        if self.operators.windows(2).any(|pair| pair[0].signer >= pair[1].signer) {
+            issues.push("axelar: operators must be sorted by increasing address without duplicates".to_string());
+        }
+        if self.gas_multiplier_bps < 10_000 {
+            issues.push("axelar: gas_multiplier_bps must be at least 10000".to_string());
+        }
+        for chain in &self.supported_chains {
+            if axelar::chain_name(*chain).is_none() {
+                issues.push(format!("axelar: chain ID {} has no Axelar chain name", chain.0));
+            } else if *chain != ChainId::SOLANA && !self.gas_prices.contains_key(chain) {
+                issues.push(format!("axelar: no gas price for chain ID {}", chain.0));
+            }
+        }
+        issues
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// One end of an IBC channel to a counterparty chain.
+pub struct IbcChannel {
+    pub port_id: String,
+    pub channel_id: String,
+    pub counterparty_port_id: String,
+    pub counterparty_channel_id: String,
+    /// Channel version: `ics20-1` for token transfers, `ics27-1` for
+    /// interchain accounts, anything else for CCIHS's own packets.
+    pub version: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IbcSettings {
+    pub supported_chains: HashSet<ChainId>,
+    /// Channel to each counterparty chain.
+    pub channels: HashMap<ChainId, IbcChannel>,
+    /// Packets time out this many blocks past the counterparty's latest
+    /// known height. Zero disables height timeouts.
+    pub timeout_height_offset: u64,
+    /// Packets time out this many seconds after they are sent. Zero disables
+    /// timestamp timeouts.
+    pub timeout_secs: u64,
+}
+
+impl IbcSettings {
+    pub fn new() -> Self {
+        Self {
+            supported_chains: HashSet::new(),
+            channels: HashMap::new(),
+            timeout_height_offset: 1_000,
+            timeout_secs: 600,
+        }
+    }
+
+    fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.timeout_height_offset == 0 && self.timeout_secs == 0 {
+            issues.push("ibc: timeout_height_offset and timeout_secs cannot both be zero".to_string());
+        }
+        let mut chains: Vec<_> = self.supported_chains.iter().filter(|chain| **chain != ChainId::SOLANA).collect();
+        chains.sort_by_key(|chain| chain.0);
+        for chain in chains {
+            if !chain.is_cosmos() {
+                issues.push(format!("ibc: chain ID {} is not a Cosmos chain", chain.0));
+            }
+            match self.channels.get(chain) {
+                None => issues.push(format!("ibc: no channel to chain ID {}", chain.0)),
+                Some(channel) => {
+                    let channel_ids = [
+                        ("channel_id", &channel.channel_id),
+                        ("counterparty_channel_id", &channel.counterparty_channel_id),
+                    ];
+                    for (name, id) in channel_ids {
+                        if !is_channel_id(id) {
+                            issues.push(format!("ibc: {} of chain ID {} is not `channel-<n>`: {}", name, chain.0, id));
+                        }
+                    }
+                    if channel.port_id.is_empty() || channel.counterparty_port_id.is_empty() {
+                        issues.push(format!("ibc: port IDs of chain ID {} must be set", chain.0));
+                    }
+                }
+            }
+        }
+        issues
+    }
+}
+
+impl Default for IbcSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_channel_id(id: &str) -> bool {
+    id.strip_prefix("channel-")
+        .map_or(false, |number| !number.is_empty() && number.bytes().all(|byte| byte.is_ascii_digit()))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Protocol specific configuration. Replaces the untyped
+/// `additional_params` map; use [`ProtocolSettings::from_params`] to migrate
+/// an existing map.
+pub enum ProtocolSettings {
+    Wormhole(WormholeSettings),
+    LayerZero(LayerZeroSettings),
+    Cctp(CctpSettings),
+    Hyperlane(HyperlaneSettings),
+    Axelar(AxelarSettings),
+    Ibc(IbcSettings),
+}
+
+impl ProtocolSettings {
+    /// Builds typed settings from the legacy `additional_params` form. Unknown
+    /// keys are rejected instead of being ignored.
+    ///
+    /// Wormhole keys: `core_bridge_program`, `token_bridge_program`,
+    /// `finality` (`confirmed`, `finalized` or a number), `batch_id`,
+    /// `relayer_fee`, `relayer_fee_precision`, `delivery_provider`, and per
+    /// target chain ID `delivery_prices.<chain ID>.base_fee`, `.gas_price`,
+    /// `.native_price` and `.native_decimals`.
+    ///
+    /// LayerZero keys: `endpoint`, `send_library`, `receive_library`,
+    /// `executor`, `required_dvns`, `optional_dvns` (comma separated),
+    /// `optional_dvn_threshold`, `confirmations`.
+    ///
+    /// CCTP keys: `message_transmitter`, `token_messenger_minter`,
+    /// `usdc_mint`, `attesters` (comma separated EVM addresses),
+    /// `attestation_threshold`.
+    ///
+    /// Hyperlane keys: `mailbox`, `local_domain`, and per origin domain
+    /// `validator_sets.<domain>.validators` (comma separated EVM addresses)
+    /// and `validator_sets.<domain>.threshold`.
+    ///
+    /// Axelar keys: `gateway`, `gas_service`, `domain_separator` (hex),
+    /// `operators` (comma separated `<EVM address>:<weight>`),
+    /// `operator_threshold`, `operator_set_nonce` (hex), `base_fee`,
+    /// `gas_multiplier_bps`, `gas_limit`, and per destination
+    /// `gas_prices.<Axelar chain name>`.
+    ///
+    /// IBC keys: `timeout_height_offset`, `timeout_secs`, and per
+    /// counterparty chain ID `channels.<chain ID>.port_id`, `.channel_id`,
+    /// `.counterparty_port_id`, `.counterparty_channel_id` and `.version`.
+    pub fn from_params(
+        protocol_type: ProtocolType,
+        supported_chains: HashSet<ChainId>,
+        params: &HashMap<String, String>,
+    ) -> CCIHSResult<Self> {
+        match protocol_type {
+            ProtocolType::Wormhole => {
+                let mut settings = WormholeSettings::new(required_pubkey(params, "core_bridge_program")?);
+                settings.supported_chains = supported_chains;
+                for (key, value) in params {
+                    let price = key.strip_prefix("delivery_prices.").and_then(|rest| rest.split_once('.'));
+                    if let Some((chain, field)) = price {
+                        let price = settings.delivery_prices
+                            .entry(ChainId(parse_number(key, chain)?))
+                            .or_insert_with(DeliveryPrice::default);
+                        match field {
+                            "base_fee" => price.base_fee = parse_number(key, value)?,
+                            "gas_price" => price.gas_price = parse_number(key, value)?,
+                            "native_price" => price.native_price = parse_number(key, value)?,
+                            "native_decimals" => price.native_decimals = parse_number(key, value)?,
+                            _ => return Err(invalid(format!("wormhole: unknown parameter `{}`", key))),
+                        }
+                        continue;
+                    }
+                    match key.as_str() {
+                        "core_bridge_program" => {}
+                        "token_bridge_program" => settings.token_bridge_program = Some(parse_pubkey(key, value)?),
+                        "finality" => settings.finality = match value.as_str() {
+                            "confirmed" => WORMHOLE_FINALITY_CONFIRMED,
+                            "finalized" => WORMHOLE_FINALITY_FINALIZED,
+                            _ => parse_number(key, value)?,
+                        },
+                        "batch_id" => settings.batch_id = parse_number(key, value)?,
+                        "relayer_fee" => settings.relayer_fee = parse_number(key, value)?,
+                        "relayer_fee_precision" => settings.relayer_fee_precision = parse_number(key, value)?,
+                        "delivery_provider" => settings.delivery_provider = Some(parse_pubkey(key, value)?),
+                        _ => return Err(invalid(format!("wormhole: unknown parameter `{}`", key))),
+                    }
+                }
+                Ok(ProtocolSettings::Wormhole(settings))
+            }
+            ProtocolType::LayerZero => {
+                let mut settings = LayerZeroSettings::new(required_pubkey(params, "endpoint")?);
+                settings.supported_chains = supported_chains;
+                for (key, value) in params {
+                    match key.as_str() {
+                        "endpoint" => {}
+                        "send_library" => settings.send_library = Some(parse_pubkey(key, value)?),
+                        "receive_library" => settings.receive_library = Some(parse_pubkey(key, value)?),
+                        "executor" => settings.executor = Some(parse_pubkey(key, value)?),
+                        "required_dvns" => settings.required_dvns = parse_pubkey_list(key, value)?,
+                        "optional_dvns" => settings.optional_dvns = parse_pubkey_list(key, value)?,
+                        "optional_dvn_threshold" => settings.optional_dvn_threshold = parse_number(key, value)?,
+                        "confirmations" => settings.confirmations = parse_number(key, value)?,
+                        _ => return Err(invalid(format!("layerzero: unknown parameter `{}`", key))),
+                    }
+                }
+                Ok(ProtocolSettings::LayerZero(settings))
+            }
+            ProtocolType::Cctp => {
+                let mut settings = CctpSettings::new(
+                    required_pubkey(params, "message_transmitter")?,
+                    required_pubkey(params, "token_messenger_minter")?,
+                    required_pubkey(params, "usdc_mint")?,
+                );
+                settings.supported_chains = supported_chains;
+                for (key, value) in params {
+                    match key.as_str() {
+                        "message_transmitter" | "token_messenger_minter" | "usdc_mint" => {}
+                        "attesters" => settings.attesters = parse_evm_address_list(key, value)?,
+                        "attestation_threshold" => settings.attestation_threshold = parse_number(key, value)?,
+                        _ => return Err(invalid(format!("cctp: unknown parameter `{}`", key))),
+                    }
+                }
+                Ok(ProtocolSettings::Cctp(settings))
+            }
+            ProtocolType::Hyperlane => {
+                let mut settings = HyperlaneSettings::new(required_pubkey(params, "mailbox")?);
+                settings.supported_chains = supported_chains;
+                for (key, value) in params {
+                    let validator_set = key
+                        .strip_prefix("validator_sets.")
+                        .and_then(|rest| rest.split_once('.'));
+                    match (key.as_str(), validator_set) {
+                        ("mailbox", _) => {}
+                        ("local_domain", _) => settings.local_domain = parse_number(key, value)?,
+                        (_, Some((origin, field))) => {
+                            let set = settings
+                                .validator_sets
+                                .entry(parse_number(key, origin)?)
+                                .or_insert_with(|| HyperlaneValidatorSet { validators: Vec::new(), threshold: 1 });
+                            match field {
+                                "validators" => set.validators = parse_evm_address_list(key, value)?,
+                                "threshold" => set.threshold = parse_number(key, value)?,
+                                _ => return Err(invalid(format!("hyperlane: unknown parameter `{}`", key))),
+                            }
+                        }
+                        _ => return Err(invalid(format!("hyperlane: unknown parameter `{}`", key))),
+                    }
+                }
+                Ok(ProtocolSettings::Hyperlane(settings))
+            }
+            ProtocolType::Axelar => {
+                let mut settings = AxelarSettings::new(
+                    required_pubkey(params, "gateway")?,
+                    required_pubkey(params, "gas_service")?,
+                );
+                settings.supported_chains = supported_chains;
+                for (key, value) in params {
+                    match (key.as_str(), key.strip_prefix("gas_prices.")) {
+                        ("gateway", _) | ("gas_service", _) => {}
+                        ("domain_separator", _) => settings.domain_separator = parse_bytes32(key, value)?,
+                        ("operators", _) => settings.operators = parse_weighted_signers(key, value)?,
+                        ("operator_threshold", _) => settings.operator_threshold = parse_number(key, value)?,
+                        ("operator_set_nonce", _) => settings.operator_set_nonce = parse_bytes32(key, value)?,
+                        ("base_fee", _) => settings.base_fee = parse_number(key, value)?,
+                        ("gas_multiplier_bps", _) => settings.gas_multiplier_bps = parse_number(key, value)?,
+                        ("gas_limit", _) => settings.gas_limit = parse_number(key, value)?,
+                        (_, Some(chain_name)) => {
+                            let chain = axelar::chain_for_name(chain_name)
+                                .ok_or_else(|| invalid(format!("axelar: unknown Axelar chain `{}`", chain_name)))?;
+                            settings.gas_prices.insert(chain, parse_number(key, value)?);
+                        }
+                        _ => return Err(invalid(format!("axelar: unknown parameter `{}`", key))),
+                    }
+                }
+                Ok(ProtocolSettings::Axelar(settings))
+            }
+            ProtocolType::Ibc => {
+                let mut settings = IbcSettings::new();
+                settings.supported_chains = supported_chains;
+                for (key, value) in params {
+                    let channel = key.strip_prefix("channels.").and_then(|rest| rest.split_once('.'));
+                    match (key.as_str(), channel) {
+                        ("timeout_height_offset", _) => settings.timeout_height_offset = parse_number(key, value)?,
+                        ("timeout_secs", _) => settings.timeout_secs = parse_number(key, value)?,
+                        (_, Some((chain, field))) => {
+                            let chain = ChainId(parse_number(key, chain)?);
+                            let channel = settings.channels.entry(chain).or_insert_with(|| IbcChannel {
+                                port_id: String::new(),
+                                channel_id: String::new(),
+                                counterparty_port_id: String::new(),
+                                counterparty_channel_id: String::new(),
+                                version: String::new(),
+                            });
+                            let value = value.trim().to_string();
+                            match field {
+                                "port_id" => channel.port_id = value,
+                                "channel_id" => channel.channel_id = value,
+                                "counterparty_port_id" => channel.counterparty_port_id = value,
+                                "counterparty_channel_id" => channel.counterparty_channel_id = value,
+                                "version" => channel.version = value,
+                                _ => return Err(invalid(format!("ibc: unknown parameter `{}`", key))),
+                            }
+                        }
+                        _ => return Err(invalid(format!("ibc: unknown parameter `{}`", key))),
+                    }
+                }
+                Ok(ProtocolSettings::Ibc(settings))
+            }
+        }
+    }
+
+    pub fn as_wormhole(&self) -> Option<&WormholeSettings> {
+        match self {
+            ProtocolSettings::Wormhole(settings) => Some(settings),
+            _ => None,
+        }
+    }
+
+    pub fn as_layer_zero(&self) -> Option<&LayerZeroSettings> {
+        match self {
+            ProtocolSettings::LayerZero(settings) => Some(settings),
+            _ => None,
+        }
+    }
+
+    pub fn as_cctp(&self) -> Option<&CctpSettings> {
+        match self {
+            ProtocolSettings::Cctp(settings) => Some(settings),
+            _ => None,
+        }
+    }
+
+    pub fn as_hyperlane(&self) -> Option<&HyperlaneSettings> {
+        match self {
+            ProtocolSettings::Hyperlane(settings) => Some(settings),
+            _ => None,
+        }
+    }
+
+    pub fn as_axelar(&self) -> Option<&AxelarSettings> {
+        match self {
+            ProtocolSettings::Axelar(settings) => Some(settings),
+            _ => None,
+        }
+    }
+
+    pub fn as_ibc(&self) -> Option<&IbcSettings> {
+        match self {
+            ProtocolSettings::Ibc(settings) => Some(settings),
+            _ => None,
+        }
+    }
+
+    /// Every problem with the settings, empty when they are valid.
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.supported_chains().is_empty() {
+            issues.push(format!("{}: no supported chains", self.protocol_type()));
+        }
+        issues.extend(match self {
+            ProtocolSettings::Wormhole(settings) => settings.issues(),
+            ProtocolSettings::LayerZero(settings) => settings.issues(),
+            ProtocolSettings::Cctp(settings) => settings.issues(),
+            ProtocolSettings::Hyperlane(settings) => settings.issues(),
+            ProtocolSettings::Axelar(settings) => settings.issues(),
+            ProtocolSettings::Ibc(settings) => settings.issues(),
+        });
+        issues
+    }
+
+    pub fn supported_chains_mut(&mut self) -> &mut HashSet<ChainId> {
+        match self {
+            ProtocolSettings::Wormhole(settings) => &mut settings.supported_chains,
+            ProtocolSettings::LayerZero(settings) => &mut settings.supported_chains,
+            ProtocolSettings::Cctp(settings) => &mut settings.supported_chains,
+            ProtocolSettings::Hyperlane(settings) => &mut settings.supported_chains,
+            ProtocolSettings::Axelar(settings) => &mut settings.supported_chains,
+            ProtocolSettings::Ibc(settings) => &mut settings.supported_chains,
+        }
+    }
+}
+
+impl ProtocolConfigTrait for ProtocolSettings {
+    fn protocol_type(&self) -> ProtocolType {
+        match self {
+            ProtocolSettings::Wormhole(_) => ProtocolType::Wormhole,
+            ProtocolSettings::LayerZero(_) => ProtocolType::LayerZero,
+            ProtocolSettings::Cctp(_) => ProtocolType::Cctp,
+            ProtocolSettings::Hyperlane(_) => ProtocolType::Hyperlane,
+            ProtocolSettings::Axelar(_) => ProtocolType::Axelar,
+            ProtocolSettings::Ibc(_) => ProtocolType::Ibc,
+        }
+    }
+
+    fn supported_chains(&self) -> &HashSet<ChainId> {
+        match self {
+            ProtocolSettings::Wormhole(settings) => &settings.supported_chains,
+            ProtocolSettings::LayerZero(settings) => &settings.supported_chains,
+            ProtocolSettings::Cctp(settings) => &settings.supported_chains,
+            ProtocolSettings::Hyperlane(settings) => &settings.supported_chains,
+            ProtocolSettings::Axelar(settings) => &settings.supported_chains,
+            ProtocolSettings::Ibc(settings) => &settings.supported_chains,
+        }
+    }
+
+    fn validate(&self) -> CCIHSResult<()> {
+        match self.issues().into_iter().next() {
+            Some(issue) => Err(invalid(issue)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> CCIHSError {
+    CCIHSError::InvalidProtocolSettings(reason.into())
+}
+
+fn required_pubkey(params: &HashMap<String, String>, key: &str) -> CCIHSResult<Pubkey> {
+    let value = params.get(key).ok_or_else(|| invalid(format!("missing parameter `{}`", key)))?;
+    parse_pubkey(key, value)
+}
+
+fn parse_pubkey(key: &str, value: &str) -> CCIHSResult<Pubkey> {
+    Pubkey::from_str(value.trim()).map_err(|_| invalid(format!("`{}` is not a valid pubkey: {}", key, value)))
+}
+
+fn parse_pubkey_list(key: &str, value: &str) -> CCIHSResult<Vec<Pubkey>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| parse_pubkey(key, item))
+        .collect()
+}
+
+fn parse_evm_address_list(key: &str, value: &str) -> CCIHSResult<Vec<EvmAddress>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            from_hex(item)
+                .ok()
+                .and_then(|bytes| EvmAddress::try_from(bytes.as_slice()).ok())
+                .ok_or_else(|| invalid(format!("`{}` is not a valid EVM address: {}", key, item)))
+        })
+        .collect()
+}
+
+fn parse_bytes32(key: &str, value: &str) -> CCIHSResult<[u8; 32]> {
+    from_hex(value.trim())
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes.as_slice()).ok())
+        .ok_or_else(|| invalid(format!("`{}` is not 32 bytes of hex: {}", key, value)))
+}
+
+fn parse_weighted_signers(key: &str, value: &str) -> CCIHSResult<Vec<WeightedSigner>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            let (signer, weight) = item
+                .split_once(':')
+                .ok_or_else(|| invalid(format!("`{}` entry is not `<address>:<weight>`: {}", key, item)))?;
+            Ok(WeightedSigner {
+                signer: parse_evm_address_list(key, signer)?
+                    .pop()
+                    .ok_or_else(|| invalid(format!("`{}` entry has no address: {}", key, item)))?,
+                weight: parse_number(key, weight)?,
+            })
+        })
+        .collect()
+}
+
+fn parse_number<T: FromStr>(key: &str, value: &str) -> CCIHSResult<T> {
+    value.trim().parse().map_err(|_| invalid(format!("`{}` is not a valid number: {}", key, value)))
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::config::ProtocolConfig;
+
+    fn params(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    fn chains() -> HashSet<ChainId> {
+        [ChainId::SOLANA, ChainId::ETHEREUM].into_iter().collect()
+    }
+
+    #[test]
+    fn test_from_params_migrates_wormhole_map() {
+        let core_bridge = Pubkey::new_unique();
+        let provider = Pubkey::new_unique();
+        let config = ProtocolConfig::from_params(
+            ProtocolType::Wormhole,
+            chains(),
+            &params(&[
+                ("core_bridge_program", &core_bridge.to_string()),
+                ("finality", "confirmed"),
+                ("batch_id", "7"),
+                ("delivery_provider", &provider.to_string()),
+                ("delivery_prices.2.native_price", "1000"),
+                ("delivery_prices.2.gas_price", "30"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(config.protocol_type(), ProtocolType::Wormhole);
+        let settings = config.settings.as_wormhole().unwrap();
+        assert_eq!(settings.core_bridge_program, core_bridge);
+        assert_eq!(settings.finality, WORMHOLE_FINALITY_CONFIRMED);
+        assert_eq!(settings.batch_id, 7);
+        assert_eq!(settings.delivery_provider, Some(provider));
+        let price = &settings.delivery_prices[&ChainId::ETHEREUM];
+        assert_eq!((price.native_price, price.gas_price), (1000, 30));
+        assert_eq!(settings.supported_chains, chains());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_from_params_migrates_cctp_map() {
+        let settings = ProtocolSettings::from_params(
+            ProtocolType::Cctp,
+            chains(),
+            &params(&[
+                ("message_transmitter", &Pubkey::new_unique().to_string()),
+                ("token_messenger_minter", &Pubkey::new_unique().to_string()),
+                ("usdc_mint", &Pubkey::new_unique().to_string()),
+                ("attesters", "0x1111111111111111111111111111111111111111, 0x2222222222222222222222222222222222222222"),
+                ("attestation_threshold", "2"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(settings.protocol_type(), ProtocolType::Cctp);
+        let cctp = settings.as_cctp().unwrap();
+        assert_eq!(cctp.attesters, vec![[0x11; 20], [0x22; 20]]);
+        assert_eq!(cctp.attestation_threshold, 2);
+    }
+
+    #[test]
+    fn test_from_params_rejects_unknown_keys() {
+        let core_bridge = Pubkey::new_unique().to_string();
+        for key in ["finalty", "delivery_prices.2.fee"] {
+            let result = ProtocolSettings::from_params(
+                ProtocolType::Wormhole,
+                chains(),
+                &params(&[("core_bridge_program", &core_bridge), (key, "1")]),
+            );
+            assert!(
+                matches!(result, Err(CCIHSError::InvalidProtocolSettings(ref reason)) if reason.contains(key)),
+                "{} was accepted",
+                key
+            );
+        }
+
+        let result = ProtocolSettings::from_params(ProtocolType::Ibc, chains(), &params(&[("channel", "x")]));
+        assert!(matches!(result, Err(CCIHSError::InvalidProtocolSettings(_))));
+    }
+
+    #[test]
+    fn test_from_params_requires_mandatory_keys() {
+        let result = ProtocolSettings::from_params(ProtocolType::Wormhole, chains(), &params(&[("batch_id", "1")]));
+        assert!(matches!(result, Err(CCIHSError::InvalidProtocolSettings(ref reason)) if reason.contains("core_bridge_program")));
+    }
+}
@@ -0,0 +1,48 @@
+// config/quorum.rs
+
+use crate::types::{ChainId, ProtocolType};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Sends a message over every protocol of `protocols` and executes it only
+/// once `threshold` of them delivered the identical message.
+pub struct QuorumPolicy {
+    pub protocols: Vec<ProtocolType>,
+    pub threshold: usize,
+}
+
+impl QuorumPolicy {
+    pub fn new(protocols: Vec<ProtocolType>, threshold: usize) -> Self {
+        Self { protocols, threshold }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// Quorum policies by route. Routes without a policy use the default
+/// protocol alone.
+pub struct QuorumConfig {
+    /// Policy for routes without their own.
+    pub default: Option<QuorumPolicy>,
+    /// Policies by `(source chain, destination chain)`.
+    pub routes: HashMap<(ChainId, ChainId), QuorumPolicy>,
+}
+
+impl QuorumConfig {
+    pub fn is_empty(&self) -> bool {
+        self.default.is_none() && self.routes.is_empty()
+    }
+
+    pub fn set_route(&mut self, source_chain: ChainId, destination_chain: ChainId, policy: QuorumPolicy) {
+        self.routes.insert((source_chain, destination_chain), policy);
+    }
+
+    pub fn policy(&self, source_chain: ChainId, destination_chain: ChainId) -> Option<&QuorumPolicy> {
+        self.routes.get(&(source_chain, destination_chain)).or(self.default.as_ref())
+    }
+
+    /// Whether messages from `source_chain` may need a quorum. The
+    /// destination of a received message is only known once it is read.
+    pub fn applies_from(&self, source_chain: ChainId) -> bool {
+        self.default.is_some() || self.routes.keys().any(|(source, _)| *source == source_chain)
+    }
+}
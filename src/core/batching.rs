@@ -0,0 +1,150 @@
+// src/core/batching.rs
+
+use crate::types::{BatchMember, ChainId, CrossChainMessage, CCIHSResult};
+use crate::CCIHSError;
+use std::collections::{BTreeMap, HashMap};
+
+/// Members of a single batch that have arrived so far.
+struct PendingBatch {
+    size: u16,
+    members: BTreeMap<u16, CrossChainMessage>,
+}
+
+/// Holds received batch members until every member of their batch has
+/// arrived. Nothing from a batch is released for execution before that.
+pub struct BatchVerifier {
+    pending: HashMap<(ChainId, u32, [u8; 32]), PendingBatch>,
+}
+
+impl BatchVerifier {
+    pub fn new() -> Self {
+        Self {
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Adds a received member. `message.payload` must be an encoded
+    /// [`BatchMember`]. Returns every member of the batch, in send order and
+    /// with their original payloads, once the batch is complete and matches
+    /// its batch hash.
+    pub fn accept(&mut self, mut message: CrossChainMessage, member: BatchMember) -> CCIHSResult<Option<Vec<CrossChainMessage>>> {
+        let key = (message.source_chain, member.batch_id, member.batch_hash);
+        let batch = self.pending
+            .entry(key)
+            .or_insert_with(|| PendingBatch {
+                size: member.size,
+                members: BTreeMap::new(),
+            });
+
+        if batch.size != member.size {
+            return Err(CCIHSError::InvalidBatch);
+        }
+
+        if batch.members.contains_key(&member.index) {
+            log::debug!("Ignoring duplicate member {} of batch {}", member.index, member.batch_id);
+            return Ok(None);
+        }
+
+        message.payload = member.payload;
+        batch.members.insert(member.index, message);
+
+        if batch.members.len() < batch.size as usize {
+            return Ok(None);
+        }
+
+        let batch = self.pending.remove(&key).expect("batch exists");
+        let members: Vec<CrossChainMessage> = batch.members.into_values().collect();
+
+        let hash = crate::types::batch_hash(members.iter().map(|m| m.payload.as_slice()));
+        if hash != member.batch_hash {
+            return Err(CCIHSError::BatchHashMismatch);
+        }
+
+        Ok(Some(members))
+    }
+
+    /// Number of members received so far for a batch.
+    pub fn received_count(&self, source_chain: ChainId, batch_id: u32, batch_hash: &[u8; 32]) -> usize {
+        self.pending
+            .get(&(source_chain, batch_id, *batch_hash))
+            .map_or(0, |batch| batch.members.len())
+    }
+}
+
+impl Default for BatchVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::types::{batch_hash, derive_batch_id, CrossChainAddress, MessageType};
+    use anchor_lang::prelude::Pubkey;
+
+    fn members(payloads: &[&[u8]]) -> Vec<(CrossChainMessage, BatchMember)> {
+        let hash = batch_hash(payloads.iter().copied());
+        let batch_id = derive_batch_id(&hash);
+        payloads
+            .iter()
+            .enumerate()
+            .map(|(index, payload)| {
+                let member = BatchMember {
+                    batch_id,
+                    index: index as u16,
+                    size: payloads.len() as u16,
+                    batch_hash: hash,
+                    payload: payload.to_vec(),
+                };
+                let message = CrossChainMessage {
+                    message_type: MessageType::General,
+                    payload: Vec::new(),
+                    amount: 0,
+                    token_address: None,
+                    sender: CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(0x11)),
+                    recipient: CrossChainAddress::Solana(Pubkey::new_from_array([0x22; 32])),
+                    source_chain: ChainId::ETHEREUM,
+                    destination_chain: ChainId::SOLANA,
+                    nonce: batch_id,
+                    timestamp: 1_000,
+                    consistency_level: 0,
+                    expires_at: None,
+                    execute_after: None,
+                    message_id: None,
+                };
+                (message, member)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_release_complete_batch_in_order() {
+        let mut batch = members(&[b"first", b"second", b"third"]);
+        let mut verifier = BatchVerifier::new();
+        let (last, last_member) = batch.remove(0);
+        let (hash, batch_id) = (last_member.batch_hash, last_member.batch_id);
+
+        for (message, member) in batch.into_iter().rev() {
+            assert_eq!(verifier.accept(message, member.clone()).unwrap(), None);
+        }
+        assert_eq!(verifier.received_count(ChainId::ETHEREUM, batch_id, &hash), 2);
+
+        let released = verifier.accept(last, last_member).unwrap().unwrap();
+        let payloads: Vec<&[u8]> = released.iter().map(|message| message.payload.as_slice()).collect();
+        assert_eq!(payloads, vec![b"first".as_ref(), b"second", b"third"]);
+        assert_eq!(verifier.received_count(ChainId::ETHEREUM, batch_id, &hash), 0);
+    }
+
+    #[test]
+    fn test_swapped_member_fails_batch() {
+        let mut batch = members(&[b"first", b"second"]);
+        batch[1].1.payload = b"forged".to_vec();
+        let mut verifier = BatchVerifier::new();
+
+        let (message, member) = batch.remove(0);
+        assert_eq!(verifier.accept(message, member).unwrap(), None);
+        let (message, member) = batch.remove(0);
+        assert!(matches!(verifier.accept(message, member), Err(CCIHSError::BatchHashMismatch)));
+    }
+}
@@ -0,0 +1,143 @@
+// src/core/chunking.rs
+
+use crate::types::{ChainId, ChunkKey, MessageChunk, CCIHSResult, payload_checksum};
+use crate::CCIHSError;
+use std::collections::{BTreeMap, HashMap};
+
+/// Fragments of a single transfer that have arrived so far.
+struct PartialTransfer {
+    total: u16,
+    total_length: u32,
+    chunk_size: u16,
+    checksum: [u8; 32],
+    fragments: BTreeMap<u16, Vec<u8>>,
+}
+
+impl PartialTransfer {
+    fn new(chunk: &MessageChunk) -> Self {
+        Self {
+            total: chunk.total,
+            total_length: chunk.total_length,
+            chunk_size: chunk.chunk_size,
+            checksum: chunk.checksum,
+            fragments: BTreeMap::new(),
+        }
+    }
+
+    fn matches(&self, chunk: &MessageChunk) -> bool {
+        self.total == chunk.total
+            && self.total_length == chunk.total_length
+            && self.chunk_size == chunk.chunk_size
+            && self.checksum == chunk.checksum
+    }
+
+    fn is_complete(&self) -> bool {
+        self.fragments.len() == self.total as usize
+    }
+}
+
+/// Collects [`MessageChunk`]s coming from any number of source chains and
+/// hands back the original payload once every fragment of a transfer has
+/// arrived. Fragments may arrive in any order; duplicates are ignored.
+pub struct ChunkAssembler {
+    pending: HashMap<ChunkKey, PartialTransfer>,
+}
+
+impl ChunkAssembler {
+    pub fn new() -> Self {
+        Self {
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Adds a fragment. Returns `Some(payload)` when this fragment completes
+    /// its transfer and the reassembled payload matches the shared checksum.
+    pub fn accept(&mut self, source_chain: ChainId, chunk: MessageChunk) -> CCIHSResult<Option<Vec<u8>>> {
+        if !chunk.is_well_formed() {
+            return Err(CCIHSError::InvalidChunk);
+        }
+
+        let key = (source_chain, chunk.transfer_id);
+        let transfer = self.pending
+            .entry(key)
+            .or_insert_with(|| PartialTransfer::new(&chunk));
+
+        if !transfer.matches(&chunk) {
+            return Err(CCIHSError::ChunkTransferMismatch);
+        }
+
+        if transfer.fragments.contains_key(&chunk.index) {
+            log::debug!("Ignoring duplicate chunk {} of transfer {:?}", chunk.index, chunk.transfer_id);
+            return Ok(None);
+        }
+        transfer.fragments.insert(chunk.index, chunk.data);
+
+        if !transfer.is_complete() {
+            return Ok(None);
+        }
+
+        let transfer = self.pending.remove(&key).expect("transfer exists");
+        let payload: Vec<u8> = transfer.fragments.into_values().flatten().collect();
+
+        if payload_checksum(&payload) != transfer.checksum {
+            return Err(CCIHSError::ChunkChecksumMismatch);
+        }
+
+        Ok(Some(payload))
+    }
+
+    /// Number of fragments received so far for a transfer.
+    pub fn received_count(&self, source_chain: ChainId, transfer_id: &[u8; 32]) -> usize {
+        self.pending
+            .get(&(source_chain, *transfer_id))
+            .map_or(0, |transfer| transfer.fragments.len())
+    }
+
+    /// Drops a transfer that is never going to complete.
+    pub fn discard(&mut self, source_chain: ChainId, transfer_id: &[u8; 32]) -> bool {
+        self.pending.remove(&(source_chain, *transfer_id)).is_some()
+    }
+}
+
+impl Default for ChunkAssembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::types::split_payload;
+
+    #[test]
+    fn test_out_of_order_reassembly() {
+        let payload: Vec<u8> = (0..2500u32).map(|i| i as u8).collect();
+        let mut chunks = split_payload([7u8; 32], &payload, 896).unwrap();
+        assert_eq!(chunks.len(), 3);
+        chunks.reverse();
+
+        let mut assembler = ChunkAssembler::new();
+        let last = chunks.pop().unwrap();
+        for chunk in chunks {
+            assert_eq!(assembler.accept(ChainId::ETHEREUM, chunk).unwrap(), None);
+        }
+        assert_eq!(assembler.received_count(ChainId::ETHEREUM, &[7u8; 32]), 2);
+        assert_eq!(assembler.accept(ChainId::ETHEREUM, last).unwrap(), Some(payload));
+        assert_eq!(assembler.received_count(ChainId::ETHEREUM, &[7u8; 32]), 0);
+    }
+
+    #[test]
+    fn test_checksum_mismatch() {
+        let payload = vec![1u8; 1000];
+        let mut chunks = split_payload([1u8; 32], &payload, 500).unwrap();
+        chunks[1].data[0] = 2;
+
+        let mut assembler = ChunkAssembler::new();
+        assert!(assembler.accept(ChainId::ETHEREUM, chunks[0].clone()).unwrap().is_none());
+        assert!(matches!(
+            assembler.accept(ChainId::ETHEREUM, chunks[1].clone()),
+            Err(CCIHSError::ChunkChecksumMismatch)
+        ));
+    }
+}
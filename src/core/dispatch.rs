@@ -0,0 +1,214 @@
+// src/core/dispatch.rs
+
+use crate::types::{CrossChainMessage, CCIHSResult};
+use crate::CCIHSError;
+use anchor_lang::AnchorDeserialize;
+use std::collections::HashMap;
+
+/// Type-erased handler stored in the registry. Decodes the payload body into
+/// the application's type and runs the application's closure on it.
+type ErasedHandler = Box<dyn Fn(&CrossChainMessage, &[u8]) -> CCIHSResult<()> + Send + Sync>;
+
+/// Handler for payload IDs nobody registered. Receives the payload ID and the
+/// whole message.
+pub type FallbackHandler = Box<dyn Fn(u8, &CrossChainMessage) -> CCIHSResult<()> + Send + Sync>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// What happened to a received message during dispatch.
+pub enum DispatchOutcome {
+    /// A registered handler decoded and handled the message.
+    Handled(u8),
+    /// No handler was registered for the payload ID; the fallback ran.
+    Fallback(u8),
+    /// No handler and no fallback; the message was left to the caller.
+    Unhandled(u8),
+    /// The message had an empty payload, so there was no payload ID.
+    Empty,
+}
+
+/// Routes received messages to application handlers by payload ID (the first
+/// byte of the payload). Applications register a decoder for the rest of the
+/// payload together with a closure taking the decoded value.
+pub struct HandlerRegistry {
+    handlers: HashMap<u8, ErasedHandler>,
+    fallback: Option<FallbackHandler>,
+}
+
+impl HandlerRegistry {
+    pub fn new() -> Self {
+        Self {
+            handlers: HashMap::new(),
+            fallback: None,
+        }
+    }
+
+    /// Registers `handler` for `payload_id`, replacing any previous handler.
+    /// `decoder` receives the payload without its leading payload ID byte.
+    pub fn register<T, D, H>(&mut self, payload_id: u8, decoder: D, handler: H)
+    where
+        T: 'static,
+        D: Fn(&[u8]) -> CCIHSResult<T> + Send + Sync + 'static,
+        H: Fn(&CrossChainMessage, T) -> CCIHSResult<()> + Send + Sync + 'static,
+    {
+        self.handlers.insert(payload_id, Box::new(move |message, body| {
+            let decoded = decoder(body)?;
+            handler(message, decoded)
+        }));
+    }
+
+    /// Registers a handler whose payload body is a borsh encoded `T`.
+    pub fn register_borsh<T, H>(&mut self, payload_id: u8, handler: H)
+    where
+        T: AnchorDeserialize + 'static,
+        H: Fn(&CrossChainMessage, T) -> CCIHSResult<()> + Send + Sync + 'static,
+    {
+        self.register(
+            payload_id,
+            |body: &[u8]| T::try_from_slice(body).map_err(|e| CCIHSError::DeserializationError(e.to_string())),
+            handler,
+        );
+    }
+
+    pub fn unregister(&mut self, payload_id: u8) -> bool {
+        self.handlers.remove(&payload_id).is_some()
+    }
+
+    pub fn set_fallback(&mut self, fallback: FallbackHandler) {
+        self.fallback = Some(fallback);
+    }
+
+    pub fn is_registered(&self, payload_id: u8) -> bool {
+        self.handlers.contains_key(&payload_id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty() && self.fallback.is_none()
+    }
+
+    pub fn dispatch(&self, message: &CrossChainMessage) -> CCIHSResult<DispatchOutcome> {
+        let (payload_id, body) = match message.payload.split_first() {
+            Some((payload_id, body)) => (*payload_id, body),
+            None => return Ok(DispatchOutcome::Empty),
+        };
+
+        if let Some(handler) = self.handlers.get(&payload_id) {
+            handler(message, body)?;
+            return Ok(DispatchOutcome::Handled(payload_id));
+        }
+
+        match &self.fallback {
+            Some(fallback) => {
+                fallback(payload_id, message)?;
+                Ok(DispatchOutcome::Fallback(payload_id))
+            }
+            None => {
+                log::debug!("No handler registered for payload ID {}", payload_id);
+                Ok(DispatchOutcome::Unhandled(payload_id))
+            }
+        }
+    }
+}
+
+impl Default for HandlerRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::types::{ChainId, CrossChainAddress, MessageType};
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::AnchorSerialize;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    fn message(payload: Vec<u8>) -> CrossChainMessage {
+        CrossChainMessage {
+            message_type: MessageType::General,
+            payload,
+            amount: 0,
+            token_address: None,
+            sender: CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(0x22)),
+            recipient: CrossChainAddress::Solana(Pubkey::new_from_array([0x11; 32])),
+            source_chain: ChainId::ETHEREUM,
+            destination_chain: ChainId::SOLANA,
+            nonce: 0,
+            timestamp: 0,
+            consistency_level: 0,
+            expires_at: None,
+            execute_after: None,
+            message_id: None,
+        }
+    }
+
+    fn encoded(payload_id: u8, value: u64) -> Vec<u8> {
+        let mut payload = vec![payload_id];
+        payload.extend_from_slice(&value.try_to_vec().unwrap());
+        payload
+    }
+
+    #[test]
+    fn test_dispatches_to_registered_handler() {
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = HandlerRegistry::new();
+        let record = handled.clone();
+        registry.register_borsh(9, move |_message: &CrossChainMessage, value: u64| {
+            record.lock().push(value);
+            Ok(())
+        });
+
+        assert!(registry.is_registered(9));
+        assert_eq!(registry.dispatch(&message(encoded(9, 42))).unwrap(), DispatchOutcome::Handled(9));
+        assert_eq!(*handled.lock(), vec![42]);
+        assert!(matches!(
+            registry.dispatch(&message(vec![9, 1])),
+            Err(CCIHSError::DeserializationError(_))
+        ));
+        assert_eq!(registry.dispatch(&message(Vec::new())).unwrap(), DispatchOutcome::Empty);
+    }
+
+    #[test]
+    fn test_falls_back_for_unregistered_payload_ids() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = HandlerRegistry::new();
+        let record = seen.clone();
+        registry.set_fallback(Box::new(move |payload_id, _message| {
+            record.lock().push(payload_id);
+            Ok(())
+        }));
+
+        assert!(!registry.is_empty());
+        assert_eq!(registry.dispatch(&message(encoded(5, 1))).unwrap(), DispatchOutcome::Fallback(5));
+        assert_eq!(*seen.lock(), vec![5]);
+    }
+
+    #[test]
+    fn test_leaves_unknown_payload_ids_without_fallback() {
+        let mut registry = HandlerRegistry::new();
+        registry.register_borsh(9, |_message: &CrossChainMessage, _value: u64| Ok(()));
+
+        assert_eq!(registry.dispatch(&message(encoded(5, 1))).unwrap(), DispatchOutcome::Unhandled(5));
+        assert!(registry.unregister(9));
+        assert!(!registry.unregister(9));
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_registering_a_payload_id_again_replaces_its_handler() {
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = HandlerRegistry::new();
+        for name in ["first", "second"] {
+            let record = handled.clone();
+            registry.register_borsh(9, move |_message: &CrossChainMessage, value: u64| {
+                record.lock().push((name, value));
+                Ok(())
+            });
+        }
+
+        assert_eq!(registry.dispatch(&message(encoded(9, 7))).unwrap(), DispatchOutcome::Handled(9));
+        assert_eq!(*handled.lock(), vec![("second", 7)]);
+    }
+}
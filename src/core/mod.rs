@@ -3,10 +3,20 @@
 mod operation;
 mod chain_management;
 mod error;
+mod chunking;
+mod batching;
+mod dispatch;
+mod quorum;
+mod status;
 
-pub use operation::CCIHSCore;
+pub use operation::{CCIHSCore, ChunkCompletionCallback, ExpiryCallback};
 pub use chain_management::ChainManager;
 pub use error::CoreError;
+pub use chunking::ChunkAssembler;
+pub use batching::BatchVerifier;
+pub use dispatch::{HandlerRegistry, DispatchOutcome, FallbackHandler};
+pub use quorum::{QuorumTracker, QuorumStatus, QuorumExecution, quorum_message_id};
+pub use status::MessageStatusTracker;
 
 // The core folder in CCIHS is meant to contain the central, fundamental logic of the library
 // Purpose of the core folder:
@@ -1,17 +1,49 @@
-use crate::types::{CrossChainMessage, ChainId, CCIHSResult, ProtocolType, HookType};
-use crate::config::CCIHSConfig;
+use crate::types::{CrossChainMessage, ChainId, CCIHSResult, ProtocolType, HookType, MessageChunk, split_payload, payload_checksum, chunk_transfer_id,
+    BatchMember, batch_hash, derive_batch_id, MAX_BATCH_SIZE, MessageType, RefundNotice, MessageId, MessageStatus,
+    QuorumEnvelope};
+use crate::config::{CCIHSConfig, ChainConfig, QuorumPolicy};
 use crate::CCIHSError;
 use crate::hooks::{HookManager, Hook};
+use crate::utility::Clock;
 use crate::protocols::ProtocolAdapter;
 use super::chain_management::ChainManager;
+use super::chunking::ChunkAssembler;
+use super::batching::BatchVerifier;
+use super::dispatch::{HandlerRegistry, FallbackHandler};
+use super::quorum::{quorum_message_id, QuorumExecution, QuorumStatus, QuorumTracker};
+use super::status::MessageStatusTracker;
 use super::error::CoreError;
+use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+
+/// Called with the reassembled message once every fragment of a chunked
+/// transfer has arrived and the execution hooks have run.
+pub type ChunkCompletionCallback = Box<dyn Fn(&CrossChainMessage) -> CCIHSResult<()> + Send + Sync>;
+
+/// Called with every received message rejected because its execution window
+/// closed, after the refund (if any) was requested.
+pub type ExpiryCallback = Box<dyn Fn(&CrossChainMessage) -> CCIHSResult<()> + Send + Sync>;
 
 pub struct CCIHSCore {
     config: CCIHSConfig,
     hook_manager: HookManager,
     protocol_adapters: HashMap<ProtocolType, Box<dyn ProtocolAdapter>>,
     chain_manager: ChainManager,
+    chunk_assembler: ChunkAssembler,
+    on_chunked_message: Option<ChunkCompletionCallback>,
+    batch_verifier: BatchVerifier,
+    handlers: HandlerRegistry,
+    on_expired: Option<ExpiryCallback>,
+    quorum_tracker: QuorumTracker,
+    /// Sequence of the next message sent over a quorum. Starts at a random
+    /// value, so cores started at the same time, or a restarted core whose
+    /// clock went back, do not reuse each other's sequences.
+    quorum_sequence: AtomicU64,
+    message_statuses: MessageStatusTracker,
 }
 
 impl CCIHSCore {
@@ -21,11 +53,26 @@ impl CCIHSCore {
         supported_chains: Vec<ChainId>,
     ) -> CCIHSResult<Self> {
         config.validate()?;
+        let mut hook_manager = HookManager::new();
+        let clock = hook_manager.clock().clone();
+        for spec in &config.hooks {
+            hook_manager.add_hook(spec.stage, spec.build(clock.clone()));
+        }
         Ok(Self {
             config,
-            hook_manager: HookManager::new(),
+            hook_manager,
             protocol_adapters,
             chain_manager: ChainManager::new(supported_chains),
+            chunk_assembler: ChunkAssembler::new(),
+            on_chunked_message: None,
+            batch_verifier: BatchVerifier::new(),
+            handlers: HandlerRegistry::new(),
+            on_expired: None,
+            quorum_tracker: QuorumTracker::new(),
+            // Every `RandomState` is keyed differently, from a per process
+            // random seed.
+            quorum_sequence: AtomicU64::new(RandomState::new().build_hasher().finish()),
+            message_statuses: MessageStatusTracker::new(),
         })
     }
 
@@ -41,6 +88,37 @@ impl CCIHSCore {
         self.hook_manager.clear_hooks(hook_type);
     }
 
+    /// Replaces the clock used by the hooks and every time based check,
+    /// including hooks built from the configuration or with
+    /// [`clock`](Self::clock). Programs running on-chain should pass
+    /// [`SolanaClock`](crate::SolanaClock).
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.hook_manager.set_clock(clock);
+    }
+
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        self.hook_manager.clock()
+    }
+
+    /// Registers a typed handler for received messages whose payload starts
+    /// with `payload_id`. See [`HandlerRegistry::register`].
+    pub fn register_handler<T, D, H>(&mut self, payload_id: u8, decoder: D, handler: H)
+    where
+        T: 'static,
+        D: Fn(&[u8]) -> CCIHSResult<T> + Send + Sync + 'static,
+        H: Fn(&CrossChainMessage, T) -> CCIHSResult<()> + Send + Sync + 'static,
+    {
+        self.handlers.register(payload_id, decoder, handler);
+    }
+
+    pub fn set_fallback_handler(&mut self, fallback: FallbackHandler) {
+        self.handlers.set_fallback(fallback);
+    }
+
+    pub fn handlers_mut(&mut self) -> &mut HandlerRegistry {
+        &mut self.handlers
+    }
+
     pub fn send_message(&self, message: &mut CrossChainMessage) -> CCIHSResult<()> {
         if !self.chain_manager.is_supported_chain(message.source_chain) {
             return Err(CoreError::UnsupportedChain(message.source_chain).into());
@@ -48,6 +126,7 @@ impl CCIHSCore {
         if !self.chain_manager.is_supported_chain(message.destination_chain) {
             return Err(CoreError::UnsupportedChain(message.destination_chain).into());
         }
+        self.config.check_route(message.source_chain, message.destination_chain)?;
 
         self.hook_manager.execute_hooks(HookType::PreDispatch, message, message.source_chain, message.destination_chain)?;
 
@@ -58,30 +137,163 @@ impl CCIHSCore {
         )?;
         message.recipient = converted_recipient;
 
-        let adapter = self.protocol_adapters.get(&self.config.default_protocol)
-            .ok_or(CCIHSError::ProtocolNotConfigured(self.config.default_protocol.to_string()))?;
+        let source_config = self.config.get_chain_config(&message.source_chain)?;
+        let destination_config = self.config.get_chain_config(&message.destination_chain)?;
 
-        adapter.send_message(message, 
-            self.config.get_chain_config(&message.source_chain)?,
-            self.config.get_chain_config(&message.destination_chain)?
-        )?;
+        match self.config.quorum.policy(message.source_chain, message.destination_chain) {
+            Some(policy) => self.send_over_quorum(message, policy, source_config, destination_config)?,
+            None => {
+                let adapter = self.protocol_adapters.get(&self.config.default_protocol)
+                    .ok_or(CCIHSError::ProtocolNotConfigured(self.config.default_protocol.to_string()))?;
+                adapter.send_message(message, source_config, destination_config)?;
+            }
+        }
+        self.message_statuses.set(message.id(), MessageStatus::Sent);
 
         self.hook_manager.execute_hooks(HookType::PostDispatch, message, message.source_chain, message.destination_chain)?;
 
         Ok(())
     }
 
+    /// Sends `message` over every protocol of `policy`, its payload wrapped
+    /// in a [`QuorumEnvelope`] with a sequence of its own, and gives it the
+    /// [`quorum_message_id`] the receiving side executes it under. A
+    /// protocol failing is tolerated as long as enough others succeed to
+    /// still reach the quorum.
+    fn send_over_quorum(
+        &self,
+        message: &mut CrossChainMessage,
+        policy: &QuorumPolicy,
+        source_config: &ChainConfig,
+        destination_config: &ChainConfig,
+    ) -> CCIHSResult<()> {
+        let sequence = self.quorum_sequence.fetch_add(1, Ordering::Relaxed);
+        message.message_id = Some(quorum_message_id(message, sequence));
+        let mut enveloped = message.clone();
+        enveloped.payload = QuorumEnvelope {
+            sequence,
+            payload: message.payload.clone(),
+        }
+        .try_to_vec()
+        .map_err(|e| CCIHSError::SerializationError(e.to_string()))?;
+        let message = &enveloped;
+
+        let mut sent = 0;
+        let mut last_error = None;
+        for protocol in &policy.protocols {
+            let result = self.get_protocol_adapter(protocol)
+                .and_then(|adapter| adapter.send_message(message, source_config, destination_config));
+            match result {
+                Ok(()) => sent += 1,
+                Err(e) => {
+                    log::warn!("Sending message {} over {} failed: {}", message.nonce, protocol, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) if sent < policy.threshold => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn set_expiry_callback(&mut self, callback: ExpiryCallback) {
+        self.on_expired = Some(callback);
+    }
+
+    /// Runs the `PreExecution` hooks. A message whose execution window has
+    /// closed gets its refund requested (token transfers) and is reported to
+    /// the expiry callback before `MessageExpired` is returned, even if the
+    /// refund notice could not be sent. `id` is the message's ID as
+    /// received.
+    fn pre_execution(&self, id: MessageId, message: &mut CrossChainMessage, source_chain: ChainId) -> CCIHSResult<()> {
+        match self.hook_manager.execute_hooks(HookType::PreExecution, message, source_chain, message.destination_chain) {
+            Err(CCIHSError::MessageExpired) => {
+                log::warn!("Message {} from {} expired at {:?}", id, source_chain, message.expires_at);
+                if matches!(message.message_type, MessageType::TokenTransfer) && message.amount > 0 {
+                    if let Err(e) = self.request_refund(id, message) {
+                        log::warn!("Sending the refund notice of message {} failed: {}", id, e);
+                    }
+                }
+                if let Some(callback) = &self.on_expired {
+                    callback(message)?;
+                }
+                Err(CCIHSError::MessageExpired)
+            }
+            result => result,
+        }
+    }
+
+    /// Tells the source chain of an expired transfer, received here as `id`,
+    /// that the transfer was not executed. This side never redeemed the
+    /// tokens, so it sends a [`RefundNotice`] without any. The notice is
+    /// only a notification: returning the tokens is up to the application,
+    /// e.g. from a handler registered for
+    /// [`PAYLOAD_ID_REFUND`](crate::types::PAYLOAD_ID_REFUND). Requested at
+    /// most once per message, however often the transfer is received.
+    pub fn request_refund(&self, id: MessageId, message: &CrossChainMessage) -> CCIHSResult<()> {
+        if !self.message_statuses.record_refund(id) {
+            log::info!("Refund of message {} was already requested", id);
+            return Ok(());
+        }
+
+        let mut notice = message.clone();
+        std::mem::swap(&mut notice.source_chain, &mut notice.destination_chain);
+        std::mem::swap(&mut notice.sender, &mut notice.recipient);
+        notice.message_type = MessageType::General;
+        notice.amount = 0;
+        notice.token_address = None;
+        notice.payload = RefundNotice {
+            original_id: id,
+            original_nonce: message.nonce,
+            original_timestamp: message.timestamp,
+            amount: message.amount,
+        }
+        .try_to_vec()
+        .map_err(|e| CCIHSError::SerializationError(e.to_string()))?;
+        notice.message_id = None;
+        notice.expires_at = None;
+        notice.execute_after = None;
+
+        self.send_message(&mut notice)
+    }
+
     pub fn receive_message(&self, source_chain: ChainId) -> CCIHSResult<CrossChainMessage> {
         if !self.chain_manager.is_supported_chain(source_chain) {
             return Err(CoreError::UnsupportedChain(source_chain).into());
         }
 
+        // A single adapter must not release a message its route requires
+        // a quorum for.
+        if self.config.quorum.applies_from(source_chain) {
+            return Err(CCIHSError::QuorumRequired);
+        }
+
         let adapter = self.protocol_adapters.get(&self.config.default_protocol)
             .ok_or(CCIHSError::ProtocolNotConfigured(self.config.default_protocol.to_string()))?;
 
-        let mut message = adapter.receive_message(self.config.get_chain_config(&source_chain)?)?;
+        let message = adapter.receive_message(self.config.get_chain_config(&source_chain)?)?;
 
-        self.hook_manager.execute_hooks(HookType::PreExecution, &mut message, source_chain, message.destination_chain)?;
+        self.execute_received(message, source_chain)
+    }
+
+    /// Executes a received message once: replays of a message being executed
+    /// or already executed fail with `MessageAlreadyProcessed`. Messages
+    /// between chains of different networks fail with `NetworkMismatch`.
+    fn execute_received(&self, mut message: CrossChainMessage, source_chain: ChainId) -> CCIHSResult<CrossChainMessage> {
+        self.config.check_route(message.source_chain, message.destination_chain)?;
+        let id = message.id();
+        self.message_statuses.claim(&[id])?;
+        let result = self.run_execution(id, &mut message, source_chain);
+        self.message_statuses.finish(&[id], &result);
+        result.map(|()| message)
+    }
+
+    fn run_execution(&self, id: MessageId, message: &mut CrossChainMessage, source_chain: ChainId) -> CCIHSResult<()> {
+        self.pre_execution(id, message, source_chain)?;
+
+        self.handlers.dispatch(message)?;
 
         let converted_sender = self.chain_manager.convert_address(
             message.source_chain,
@@ -90,9 +302,333 @@ impl CCIHSCore {
         )?;
         message.sender = converted_sender;
 
-        self.hook_manager.execute_hooks(HookType::PostExecution, &mut message, source_chain, message.destination_chain)?;
+        self.hook_manager.execute_hooks(HookType::PostExecution, message, source_chain, message.destination_chain)
+    }
+
+    /// Drains every protocol that takes part in a quorum for messages from
+    /// `source_chain` and records each delivered message as an attestation
+    /// by that protocol. Messages whose quorum is reached go through the
+    /// execution pipeline; the rest stay pending (see
+    /// [`quorum_status`](Self::quorum_status)). Messages on routes without
+    /// a quorum policy are executed as usual if they came over the default
+    /// protocol, and dropped otherwise.
+    ///
+    /// Every message executes on its own: one failing does not keep the
+    /// others from executing, and each outcome is returned. A quorum message
+    /// that failed stays pending and can be retried with
+    /// [`execute_quorum_message`](Self::execute_quorum_message).
+    pub fn receive_quorum_messages(&mut self, source_chain: ChainId) -> CCIHSResult<Vec<QuorumExecution>> {
+        if !self.chain_manager.is_supported_chain(source_chain) {
+            return Err(CoreError::UnsupportedChain(source_chain).into());
+        }
+        let source_config = self.config.get_chain_config(&source_chain)?;
+
+        let mut protocols: Vec<ProtocolType> = Vec::new();
+        let policies = self.config.quorum.default.iter().chain(
+            self.config.quorum.routes
+                .iter()
+                .filter(|((source, _), _)| *source == source_chain)
+                .map(|(_, policy)| policy),
+        );
+        for policy in policies {
+            for protocol in &policy.protocols {
+                if !protocols.contains(protocol) {
+                    protocols.push(protocol.clone());
+                }
+            }
+        }
+
+        let mut reached = Vec::new();
+        let mut direct = Vec::new();
+        for protocol in &protocols {
+            let adapter = self.protocol_adapters.get(protocol)
+                .ok_or_else(|| CCIHSError::ProtocolNotConfigured(protocol.to_string()))?;
+            loop {
+                let message = match adapter.receive_message(source_config) {
+                    Ok(message) => message,
+                    Err(CCIHSError::NoPendingMessage) => break,
+                    Err(e) => {
+                        log::warn!("Receiving from {} over {} failed: {}", source_chain, protocol, e);
+                        break;
+                    }
+                };
+                match self.config.quorum.policy(message.source_chain, message.destination_chain) {
+                    Some(policy) => {
+                        let envelope = match QuorumEnvelope::try_from_slice(&message.payload) {
+                            Ok(envelope) => envelope,
+                            Err(e) => {
+                                log::warn!(
+                                    "Dropping message {} from {} over {}: not in a quorum envelope: {}",
+                                    message.nonce, source_chain, protocol, e
+                                );
+                                continue;
+                            }
+                        };
+                        let mut message = message;
+                        message.payload = envelope.payload;
+                        if let Some(message_id) = self.quorum_tracker.accept(protocol, message, envelope.sequence, policy)? {
+                            reached.push(message_id);
+                        }
+                    }
+                    None if *protocol == self.config.default_protocol => direct.push(message),
+                    None => log::warn!(
+                        "Dropping message {} from {} over {}: its route has no quorum policy",
+                        message.nonce, source_chain, protocol
+                    ),
+                }
+            }
+        }
+
+        let mut executions = Vec::new();
+        for message_id in reached {
+            let message = self.quorum_tracker.ready(&message_id)?;
+            let result = self.execute_quorum_message(&message_id);
+            executions.push(QuorumExecution { message_id: Some(message_id), message, result });
+        }
+        for message in direct {
+            let result = self.execute_received(message.clone(), source_chain);
+            executions.push(QuorumExecution { message_id: None, message, result });
+        }
+        Ok(executions)
+    }
+
+    /// Executes a message whose quorum is reached, by its
+    /// [`quorum_message_id`]. Fails with `QuorumRequired` if the quorum is
+    /// not reached yet and with `MessageAlreadyProcessed` if the message
+    /// already executed.
+    pub fn execute_quorum_message(&mut self, message_id: &MessageId) -> CCIHSResult<CrossChainMessage> {
+        let message = self.quorum_tracker.ready(message_id)?;
+        let source_chain = message.source_chain;
+        let result = self.execute_received(message, source_chain);
+        if result.is_ok() {
+            self.quorum_tracker.mark_executed(message_id);
+        }
+        result
+    }
+
+    /// Attestations recorded for a message, by its [`quorum_message_id`].
+    pub fn quorum_status(&self, message_id: &MessageId) -> Option<QuorumStatus> {
+        self.quorum_tracker.status(message_id)
+    }
+
+    /// Messages attested by some protocols that have not executed: those
+    /// still short of their quorum and those whose execution failed.
+    pub fn pending_quorums(&self) -> Vec<QuorumStatus> {
+        self.quorum_tracker.pending()
+    }
+
+    /// Status of a message sent or received through this core, by its
+    /// canonical [`MessageId`]: for received messages the one their
+    /// transport derived from its emitter and sequence. IDs are computed
+    /// before the sender address is converted for the destination chain.
+    pub fn message_status(&self, id: &MessageId) -> Option<MessageStatus> {
+        self.message_statuses.status(id)
+    }
+
+    /// Sends a payload that is too large for a single message as a series of
+    /// fragments sharing one transfer ID. Dispatch hooks run once against the
+    /// whole message, not per fragment. Returns the transfer ID.
+    pub fn send_chunked_message(&self, message: &mut CrossChainMessage, chunk_size: usize) -> CCIHSResult<[u8; 32]> {
+        if !self.chain_manager.is_supported_chain(message.source_chain) {
+            return Err(CoreError::UnsupportedChain(message.source_chain).into());
+        }
+        if !self.chain_manager.is_supported_chain(message.destination_chain) {
+            return Err(CoreError::UnsupportedChain(message.destination_chain).into());
+        }
+        self.config.check_route(message.source_chain, message.destination_chain)?;
+
+        self.hook_manager.execute_hooks(HookType::PreDispatch, message, message.source_chain, message.destination_chain)?;
+
+        let converted_recipient = self.chain_manager.convert_address(
+            message.source_chain,
+            message.destination_chain,
+            &message.recipient,
+        )?;
+        message.recipient = converted_recipient;
+
+        let checksum = payload_checksum(&message.payload);
+        let transfer_id = chunk_transfer_id(message, &checksum);
+        let chunks = split_payload(transfer_id, &message.payload, chunk_size)
+            .map_err(|e| CCIHSError::SerializationError(e.to_string()))?;
+
+        let adapter = self.protocol_adapters.get(&self.config.default_protocol)
+            .ok_or(CCIHSError::ProtocolNotConfigured(self.config.default_protocol.to_string()))?;
+        let source_config = self.config.get_chain_config(&message.source_chain)?;
+        let destination_config = self.config.get_chain_config(&message.destination_chain)?;
+
+        for chunk in chunks {
+            let mut fragment = message.clone();
+            fragment.payload = chunk.try_to_vec()
+                .map_err(|e| CCIHSError::SerializationError(e.to_string()))?;
+            adapter.send_message(&fragment, source_config, destination_config)?;
+        }
+        self.message_statuses.set(message.id(), MessageStatus::Sent);
+
+        self.hook_manager.execute_hooks(HookType::PostDispatch, message, message.source_chain, message.destination_chain)?;
+
+        Ok(transfer_id)
+    }
+
+    /// Feeds a received fragment into the reassembly buffer. Execution hooks
+    /// are held back until the last fragment arrives; only then is the whole
+    /// message run through `PreExecution`/`PostExecution`, handed to the
+    /// completion callback and returned.
+    pub fn receive_chunk(&mut self, fragment: CrossChainMessage) -> CCIHSResult<Option<CrossChainMessage>> {
+        let source_chain = fragment.source_chain;
+        if !self.chain_manager.is_supported_chain(source_chain) {
+            return Err(CoreError::UnsupportedChain(source_chain).into());
+        }
+
+        let chunk = MessageChunk::try_from_slice(&fragment.payload)
+            .map_err(|e| CCIHSError::DeserializationError(e.to_string()))?;
+
+        let payload = match self.chunk_assembler.accept(source_chain, chunk)? {
+            Some(payload) => payload,
+            None => return Ok(None),
+        };
+
+        let mut message = fragment;
+        message.payload = payload;
+
+        let message = self.execute_received(message, source_chain)?;
+
+        if let Some(callback) = &self.on_chunked_message {
+            callback(&message)?;
+        }
+
+        Ok(Some(message))
+    }
+
+    pub fn set_chunk_completion_callback(&mut self, callback: ChunkCompletionCallback) {
+        self.on_chunked_message = Some(callback);
+    }
+
+    pub fn chunk_assembler(&self) -> &ChunkAssembler {
+        &self.chunk_assembler
+    }
+
+    /// Sends several messages as one batch under a common batch ID. All
+    /// messages go through the `PreDispatch` hooks before any of them is sent,
+    /// so a single rejected message fails the whole batch with nothing sent.
+    /// Returns the batch ID.
+    ///
+    /// The batch is not atomic. Hooks keep what they recorded for the
+    /// members before a rejected one, e.g. a rate limiter counts them as
+    /// sent. Sending itself is not atomic either, see
+    /// [`ProtocolAdapter::send_batch`].
+    pub fn send_batch(&self, mut messages: Vec<CrossChainMessage>) -> CCIHSResult<u32> {
+        if messages.is_empty() || messages.len() > MAX_BATCH_SIZE {
+            return Err(CCIHSError::InvalidBatch);
+        }
+
+        let source_chain = messages[0].source_chain;
+        let destination_chain = messages[0].destination_chain;
+        if messages.iter().any(|m| m.source_chain != source_chain || m.destination_chain != destination_chain) {
+            return Err(CCIHSError::InvalidBatch);
+        }
+        if !self.chain_manager.is_supported_chain(source_chain) {
+            return Err(CoreError::UnsupportedChain(source_chain).into());
+        }
+        if !self.chain_manager.is_supported_chain(destination_chain) {
+            return Err(CoreError::UnsupportedChain(destination_chain).into());
+        }
+        self.config.check_route(source_chain, destination_chain)?;
+
+        for message in messages.iter_mut() {
+            self.hook_manager.execute_hooks(HookType::PreDispatch, message, source_chain, destination_chain)?;
+            message.recipient = self.chain_manager.convert_address(source_chain, destination_chain, &message.recipient)?;
+        }
 
-        Ok(message)
+        let hash = batch_hash(messages.iter().map(|m| m.payload.as_slice()));
+        let batch_id = derive_batch_id(&hash);
+        let size = messages.len() as u16;
+
+        let mut wrapped = Vec::with_capacity(messages.len());
+        for (index, message) in messages.iter().enumerate() {
+            let mut member = message.clone();
+            member.nonce = batch_id;
+            member.payload = BatchMember {
+                batch_id,
+                index: index as u16,
+                size,
+                batch_hash: hash,
+                payload: message.payload.clone(),
+            }
+            .try_to_vec()
+            .map_err(|e| CCIHSError::SerializationError(e.to_string()))?;
+            wrapped.push(member);
+        }
+
+        let adapter = self.protocol_adapters.get(&self.config.default_protocol)
+            .ok_or(CCIHSError::ProtocolNotConfigured(self.config.default_protocol.to_string()))?;
+
+        adapter.send_batch(batch_id, &wrapped,
+            self.config.get_chain_config(&source_chain)?,
+            self.config.get_chain_config(&destination_chain)?
+        )?;
+
+        for message in messages.iter_mut() {
+            message.nonce = batch_id;
+            self.message_statuses.set(message.id(), MessageStatus::Sent);
+            self.hook_manager.execute_hooks(HookType::PostDispatch, message, source_chain, destination_chain)?;
+        }
+
+        Ok(batch_id)
+    }
+
+    /// Feeds a received batch member into the batch verifier. Nothing is
+    /// executed until every member of the batch has arrived. Then every
+    /// member goes through `PreExecution` and sender conversion before any
+    /// handler runs, so a member rejected there fails the batch with nothing
+    /// executed. Handlers and `PostExecution` hooks are not transactional: if
+    /// one fails, the handlers of the members before it have already run.
+    /// The batch is then reported `Failed` as a whole.
+    pub fn receive_batch_member(&mut self, message: CrossChainMessage) -> CCIHSResult<Option<Vec<CrossChainMessage>>> {
+        let source_chain = message.source_chain;
+        if !self.chain_manager.is_supported_chain(source_chain) {
+            return Err(CoreError::UnsupportedChain(source_chain).into());
+        }
+
+        self.config.check_route(source_chain, message.destination_chain)?;
+
+        let member = BatchMember::try_from_slice(&message.payload)
+            .map_err(|e| CCIHSError::DeserializationError(e.to_string()))?;
+        if member.batch_id != message.nonce {
+            return Err(CCIHSError::InvalidBatch);
+        }
+
+        let mut members = match self.batch_verifier.accept(message, member)? {
+            Some(members) => members,
+            None => return Ok(None),
+        };
+
+        let ids: Vec<MessageId> = members.iter().map(CrossChainMessage::id).collect();
+        self.message_statuses.claim(&ids)?;
+        let result = self.run_batch_execution(&ids, &mut members, source_chain);
+        self.message_statuses.finish(&ids, &result);
+        result.map(|()| Some(members))
+    }
+
+    fn run_batch_execution(&self, ids: &[MessageId], members: &mut [CrossChainMessage], source_chain: ChainId) -> CCIHSResult<()> {
+        for (id, message) in ids.iter().zip(members.iter_mut()) {
+            self.pre_execution(*id, message, source_chain)?;
+        }
+
+        let senders = members
+            .iter()
+            .map(|message| self.chain_manager.convert_address(message.source_chain, message.destination_chain, &message.sender))
+            .collect::<CCIHSResult<Vec<_>>>()?;
+
+        for message in members.iter() {
+            self.handlers.dispatch(message)?;
+        }
+
+        for (message, sender) in members.iter_mut().zip(senders) {
+            message.sender = sender;
+            self.hook_manager.execute_hooks(HookType::PostExecution, message, source_chain, message.destination_chain)?;
+        }
+
+        Ok(())
     }
 
     pub fn verify_message(&self, message: &CrossChainMessage) -> CCIHSResult<bool> {
@@ -102,6 +638,25 @@ impl CCIHSCore {
         if !self.chain_manager.is_supported_chain(message.destination_chain) {
             return Err(CoreError::UnsupportedChain(message.destination_chain).into());
         }
+        self.config.check_route(message.source_chain, message.destination_chain)?;
+
+        // Under a quorum policy, enough of its protocols must vouch for the
+        // message.
+        if let Some(policy) = self.config.quorum.policy(message.source_chain, message.destination_chain) {
+            let source_config = self.config.get_chain_config(&message.source_chain)?;
+            let destination_config = self.config.get_chain_config(&message.destination_chain)?;
+            let mut verified = 0;
+            for protocol in &policy.protocols {
+                let result = self.get_protocol_adapter(protocol)
+                    .and_then(|adapter| adapter.verify_message(message, source_config, destination_config));
+                match result {
+                    Ok(true) => verified += 1,
+                    Ok(false) => {}
+                    Err(e) => log::warn!("Verifying message {} over {} failed: {}", message.nonce, protocol, e),
+                }
+            }
+            return Ok(verified >= policy.threshold);
+        }
 
         let adapter = self.protocol_adapters.get(&self.config.default_protocol)
             .ok_or(CCIHSError::ProtocolNotConfigured(self.config.default_protocol.to_string()))?;
@@ -141,4 +696,118 @@ impl CCIHSCore {
         self.protocol_adapters.get(protocol_type)
             .ok_or_else(|| CCIHSError::ProtocolNotConfigured(protocol_type.to_string()))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::config::NetworkProfile;
+    use crate::hooks::{RateLimitingHook, ValidationHook};
+    use crate::protocols::FakeAdapter;
+    use crate::types::CrossChainAddress;
+    use crate::utility::TestClock;
+    use anchor_lang::prelude::Pubkey;
+    use std::time::Duration;
+
+    pub fn core(adapter: Arc<FakeAdapter>, clock: TestClock) -> CCIHSCore {
+        let mut adapters: HashMap<ProtocolType, Box<dyn ProtocolAdapter>> = HashMap::new();
+        adapters.insert(ProtocolType::Wormhole, Box::new(adapter));
+        let mut config = CCIHSConfig::empty();
+        config.apply_network(NetworkProfile::Mainnet);
+        let mut core = CCIHSCore::new(config, adapters, vec![ChainId::SOLANA, ChainId::ETHEREUM]).unwrap();
+        core.add_chain_conversion(ChainId::ETHEREUM, ChainId::SOLANA, |address| Ok(address.to_vec()));
+        core.add_chain_conversion(ChainId::SOLANA, ChainId::ETHEREUM, |address| Ok(address.to_vec()));
+        core.set_clock(Arc::new(clock));
+        core
+    }
+
+    fn transfer(expires_at: Option<u64>) -> CrossChainMessage {
+        CrossChainMessage {
+            message_type: MessageType::TokenTransfer,
+            payload: b"transfer".to_vec(),
+            amount: 10,
+            token_address: None,
+            sender: CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(0x22)),
+            recipient: CrossChainAddress::Solana(Pubkey::new_from_array([0x11; 32])),
+            source_chain: ChainId::ETHEREUM,
+            destination_chain: ChainId::SOLANA,
+            nonce: 1,
+            timestamp: 400,
+            consistency_level: 0,
+            expires_at,
+            execute_after: None,
+            message_id: Some(MessageId::compute(ChainId::ETHEREUM, &[0x22; 32], 9, b"transfer")),
+        }
+    }
+
+    #[test]
+    fn test_expires_messages_whose_refund_notice_fails() {
+        let adapter = Arc::new(FakeAdapter::new(vec![ChainId::SOLANA, ChainId::ETHEREUM]));
+        let core = core(adapter.clone(), TestClock::new(1_000));
+        adapter.fail_sends(Some("down".to_string()));
+        let id = transfer(Some(500)).id();
+
+        adapter.deliver(transfer(Some(500))).unwrap();
+        assert!(matches!(core.receive_message(ChainId::ETHEREUM), Err(CCIHSError::MessageExpired)));
+        assert_eq!(core.message_status(&id), Some(MessageStatus::Expired));
+
+        adapter.fail_sends(None);
+        adapter.deliver(transfer(Some(500))).unwrap();
+        assert!(matches!(core.receive_message(ChainId::ETHEREUM), Err(CCIHSError::MessageAlreadyProcessed)));
+        assert!(adapter.sent_messages().is_empty());
+    }
+
+    fn message(payload: &[u8]) -> CrossChainMessage {
+        CrossChainMessage {
+            message_type: MessageType::General,
+            payload: payload.to_vec(),
+            amount: 0,
+            token_address: None,
+            sender: CrossChainAddress::Solana(Pubkey::new_from_array([0x11; 32])),
+            recipient: CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(0x22)),
+            source_chain: ChainId::SOLANA,
+            destination_chain: ChainId::ETHEREUM,
+            nonce: 0,
+            timestamp: 0,
+            consistency_level: 0,
+            expires_at: None,
+            execute_after: None,
+            message_id: None,
+        }
+    }
+
+    #[test]
+    fn test_rejected_batch_members_fail_the_batch_but_count_towards_hooks() {
+        let adapter = Arc::new(FakeAdapter::new(vec![ChainId::SOLANA, ChainId::ETHEREUM]));
+        let mut core = core(adapter.clone(), TestClock::new(1_000));
+        let clock = core.clock().clone();
+        core.add_hook(HookType::PreDispatch, Box::new(RateLimitingHook::with_clock(3, Duration::from_secs(10), clock)));
+        core.add_hook(HookType::PreDispatch, Box::new(ValidationHook::new(8)));
+
+        let batch = vec![message(b"first"), message(b"second"), message(b"far too large")];
+        assert!(matches!(core.send_batch(batch), Err(CCIHSError::PayloadTooLarge)));
+        assert!(adapter.sent_messages().is_empty());
+
+        // Not atomic: the rate limiter counted every member it saw.
+        assert!(matches!(core.send_message(&mut message(b"next")), Err(CCIHSError::RateLimitExceeded)));
+        assert!(adapter.sent_messages().is_empty());
+    }
+
+    #[test]
+    fn test_cores_started_together_use_distinct_quorum_ids() {
+        let clock = TestClock::new(1_000);
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let adapter = Arc::new(FakeAdapter::new(vec![ChainId::SOLANA, ChainId::ETHEREUM]));
+            let mut core = core(adapter.clone(), clock.clone());
+            let mut config = core.get_config().clone();
+            config.quorum.set_route(ChainId::SOLANA, ChainId::ETHEREUM, QuorumPolicy::new(vec![ProtocolType::Wormhole], 1));
+            core.update_config(config).unwrap();
+
+            let mut sent = message(b"hello");
+            core.send_message(&mut sent).unwrap();
+            assert_eq!(adapter.sent_messages()[0].message_id, sent.message_id);
+            ids.push(sent.id());
+        }
+        assert_ne!(ids[0], ids[1]);
+    }
+}
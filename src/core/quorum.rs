@@ -0,0 +1,245 @@
+// src/core/quorum.rs
+
+use crate::config::QuorumPolicy;
+use crate::types::{CrossChainMessage, CCIHSResult, MessageId, MessageType, ProtocolType};
+use crate::CCIHSError;
+use std::collections::HashMap;
+
+/// [`MessageId`] of a message sent over a quorum, which its protocols
+/// attest to. The sender is the emitter and the `sequence` the sending core
+/// gave the message in its [`QuorumEnvelope`](crate::types::QuorumEnvelope)
+/// the sequence. The hashed payload also covers the destination, the
+/// recipient and the transfer, but none of the fields each transport sets
+/// on its own (`nonce`, `timestamp`, `consistency_level`, the execution
+/// window, its `message_id`). So the same message delivered by different
+/// protocols has one ID, while an identical message sent again does not.
+pub fn quorum_message_id(message: &CrossChainMessage, sequence: u64) -> MessageId {
+    let message_type: u8 = match message.message_type {
+        MessageType::General => 0,
+        MessageType::TokenTransfer => 1,
+    };
+    let token = message.token_address.map(|token| token.to_bytes()).unwrap_or_default();
+    let content = [
+        &message.destination_chain.0.to_be_bytes()[..],
+        &message.recipient.to_bytes32(),
+        &[message_type],
+        &message.amount.to_be_bytes(),
+        &token,
+        &message.payload,
+    ]
+    .concat();
+    MessageId::compute(message.source_chain, &message.sender.to_bytes32(), sequence, &content)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Attestations recorded for one message.
+pub struct QuorumStatus {
+    pub message_id: MessageId,
+    /// Protocols that delivered the message, in arrival order.
+    pub attested_by: Vec<ProtocolType>,
+    pub threshold: usize,
+    /// Whether the message executed successfully. A message whose quorum is
+    /// reached but whose execution failed can be executed again.
+    pub executed: bool,
+}
+
+impl QuorumStatus {
+    pub fn is_reached(&self) -> bool {
+        self.attested_by.len() >= self.threshold
+    }
+
+    /// Attestations still missing before the message can execute.
+    pub fn missing(&self) -> usize {
+        self.threshold.saturating_sub(self.attested_by.len())
+    }
+}
+
+#[derive(Debug)]
+/// Outcome of executing one message released by
+/// [`receive_quorum_messages`](super::CCIHSCore::receive_quorum_messages).
+pub struct QuorumExecution {
+    /// Quorum message ID. `None` for a message on a route without a quorum
+    /// policy.
+    pub message_id: Option<MessageId>,
+    /// The message as received.
+    pub message: CrossChainMessage,
+    /// The executed message, or why execution failed.
+    pub result: CCIHSResult<CrossChainMessage>,
+}
+
+struct Attestations {
+    message: CrossChainMessage,
+    status: QuorumStatus,
+}
+
+/// Counts the protocols that delivered each message and tells when a
+/// message reaches its quorum. The message stays here until it executed:
+/// attestations arriving after the quorum is reached are recorded but do
+/// not report it again.
+pub struct QuorumTracker {
+    messages: HashMap<MessageId, Attestations>,
+}
+
+impl QuorumTracker {
+    pub fn new() -> Self {
+        Self {
+            messages: HashMap::new(),
+        }
+    }
+
+    /// Records that `protocol` delivered `message`, unwrapped from a
+    /// quorum envelope with `sequence`. Returns the
+    /// [`quorum_message_id`], which the kept message carries as its
+    /// `message_id`, when this attestation reaches the quorum under
+    /// `policy`.
+    pub fn accept(
+        &mut self,
+        protocol: &ProtocolType,
+        mut message: CrossChainMessage,
+        sequence: u64,
+        policy: &QuorumPolicy,
+    ) -> CCIHSResult<Option<MessageId>> {
+        if !policy.protocols.contains(protocol) {
+            return Err(CCIHSError::ProtocolError(format!(
+                "`{}` is not part of the quorum for {} -> {}",
+                protocol, message.source_chain, message.destination_chain
+            )));
+        }
+
+        let message_id = quorum_message_id(&message, sequence);
+        message.message_id = Some(message_id);
+        let entry = self.messages.entry(message_id).or_insert_with(|| Attestations {
+            message,
+            status: QuorumStatus {
+                message_id,
+                attested_by: Vec::new(),
+                threshold: policy.threshold,
+                executed: false,
+            },
+        });
+
+        let status = &mut entry.status;
+        if status.attested_by.contains(protocol) {
+            log::debug!("Ignoring repeated attestation by {} for {}", protocol, message_id);
+            return Ok(None);
+        }
+        status.attested_by.push(protocol.clone());
+
+        if status.executed || status.attested_by.len() != status.threshold {
+            return Ok(None);
+        }
+        Ok(Some(message_id))
+    }
+
+    /// The message of `message_id`, if its quorum is reached and it has not
+    /// executed yet.
+    pub fn ready(&self, message_id: &MessageId) -> CCIHSResult<CrossChainMessage> {
+        let entry = self.messages.get(message_id).ok_or(CCIHSError::QuorumRequired)?;
+        if entry.status.executed {
+            return Err(CCIHSError::MessageAlreadyProcessed);
+        }
+        if !entry.status.is_reached() {
+            return Err(CCIHSError::QuorumRequired);
+        }
+        Ok(entry.message.clone())
+    }
+
+    pub fn mark_executed(&mut self, message_id: &MessageId) {
+        if let Some(entry) = self.messages.get_mut(message_id) {
+            entry.status.executed = true;
+        }
+    }
+
+    pub fn status(&self, message_id: &MessageId) -> Option<QuorumStatus> {
+        self.messages.get(message_id).map(|entry| entry.status.clone())
+    }
+
+    /// Messages that have not executed: those short of their quorum and
+    /// those whose execution failed.
+    pub fn pending(&self) -> Vec<QuorumStatus> {
+        self.messages
+            .values()
+            .filter(|entry| !entry.status.executed)
+            .map(|entry| entry.status.clone())
+            .collect()
+    }
+}
+
+impl Default for QuorumTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::types::{ChainId, CrossChainAddress};
+    use anchor_lang::prelude::Pubkey;
+
+    fn message(nonce: u32) -> CrossChainMessage {
+        CrossChainMessage {
+            message_type: MessageType::General,
+            payload: b"hello".to_vec(),
+            amount: 0,
+            token_address: None,
+            sender: CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(0x11)),
+            recipient: CrossChainAddress::Solana(Pubkey::new_from_array([0x22; 32])),
+            source_chain: ChainId::ETHEREUM,
+            destination_chain: ChainId::SOLANA,
+            nonce,
+            timestamp: 1_000 + nonce as u64,
+            consistency_level: 0,
+            expires_at: None,
+            execute_after: None,
+            message_id: None,
+        }
+    }
+
+    #[test]
+    fn test_reports_quorum_once_and_keeps_message_until_executed() {
+        let policy = QuorumPolicy::new(vec![ProtocolType::Wormhole, ProtocolType::Hyperlane, ProtocolType::Axelar], 2);
+        let mut tracker = QuorumTracker::new();
+        let id = quorum_message_id(&message(1), 9);
+        assert_eq!(id, quorum_message_id(&message(7), 9));
+
+        assert!(tracker.accept(&ProtocolType::Wormhole, message(1), 9, &policy).unwrap().is_none());
+        assert!(tracker.accept(&ProtocolType::Wormhole, message(1), 9, &policy).unwrap().is_none());
+        let status = tracker.status(&id).unwrap();
+        assert_eq!(status.attested_by, vec![ProtocolType::Wormhole]);
+        assert_eq!(status.missing(), 1);
+        assert_eq!(tracker.pending().len(), 1);
+        assert!(matches!(tracker.ready(&id), Err(CCIHSError::QuorumRequired)));
+
+        assert_eq!(tracker.accept(&ProtocolType::Hyperlane, message(7), 9, &policy).unwrap(), Some(id));
+        assert!(tracker.accept(&ProtocolType::Axelar, message(3), 9, &policy).unwrap().is_none());
+        // Not executed yet, e.g. because execution failed: still ready.
+        let ready = tracker.ready(&id).unwrap();
+        assert_eq!(ready.payload, b"hello");
+        assert_eq!(ready.id(), id);
+        assert_eq!(tracker.pending().len(), 1);
+
+        tracker.mark_executed(&id);
+        assert!(tracker.status(&id).unwrap().executed);
+        assert!(matches!(tracker.ready(&id), Err(CCIHSError::MessageAlreadyProcessed)));
+        assert!(tracker.pending().is_empty());
+
+        assert!(tracker.accept(&ProtocolType::Cctp, message(1), 9, &policy).is_err());
+    }
+
+    #[test]
+    fn test_tracks_identical_messages_separately() {
+        let policy = QuorumPolicy::new(vec![ProtocolType::Wormhole, ProtocolType::Hyperlane], 2);
+        let mut tracker = QuorumTracker::new();
+        let first = quorum_message_id(&message(1), 1);
+        let second = quorum_message_id(&message(1), 2);
+        assert_ne!(first, second);
+
+        tracker.accept(&ProtocolType::Wormhole, message(1), 1, &policy).unwrap();
+        tracker.accept(&ProtocolType::Hyperlane, message(1), 1, &policy).unwrap();
+        tracker.mark_executed(&first);
+
+        assert!(tracker.accept(&ProtocolType::Wormhole, message(1), 2, &policy).unwrap().is_none());
+        assert_eq!(tracker.accept(&ProtocolType::Hyperlane, message(1), 2, &policy).unwrap(), Some(second));
+    }
+}
@@ -0,0 +1,116 @@
+// src/core/status.rs
+
+use crate::types::{CCIHSResult, MessageId, MessageStatus};
+use crate::CCIHSError;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+
+/// Status of every message sent or received through the core, keyed by
+/// [`MessageId`]. Doubles as replay protection: a message is claimed as
+/// `Delivered` before it executes, and a message being executed, executed or
+/// expired cannot be claimed again. Failed messages can.
+pub struct MessageStatusTracker {
+    statuses: Mutex<HashMap<MessageId, MessageStatus>>,
+    refunds: Mutex<HashSet<MessageId>>,
+}
+
+impl MessageStatusTracker {
+    pub fn new() -> Self {
+        Self {
+            statuses: Mutex::new(HashMap::new()),
+            refunds: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn status(&self, id: &MessageId) -> Option<MessageStatus> {
+        self.statuses.lock().get(id).cloned()
+    }
+
+    pub fn set(&self, id: MessageId, status: MessageStatus) {
+        self.statuses.lock().insert(id, status);
+    }
+
+    /// Claims the messages for execution, all or none: fails with
+    /// `MessageAlreadyProcessed` if any of them is being executed, was
+    /// executed or expired. An expired message can never become executable
+    /// again.
+    pub fn claim(&self, ids: &[MessageId]) -> CCIHSResult<()> {
+        let mut statuses = self.statuses.lock();
+        let taken = ids.iter().any(|id| {
+            matches!(
+                statuses.get(id),
+                Some(MessageStatus::Delivered | MessageStatus::Executed | MessageStatus::Expired)
+            )
+        });
+        if taken {
+            return Err(CCIHSError::MessageAlreadyProcessed);
+        }
+        for id in ids {
+            statuses.insert(*id, MessageStatus::Delivered);
+        }
+        Ok(())
+    }
+
+    /// Records the outcome of executing claimed messages.
+    pub fn finish<T>(&self, ids: &[MessageId], result: &CCIHSResult<T>) {
+        let status = match result {
+            Ok(_) => MessageStatus::Executed,
+            Err(CCIHSError::MessageExpired) => MessageStatus::Expired,
+            Err(_) => MessageStatus::Failed,
+        };
+        let mut statuses = self.statuses.lock();
+        for id in ids {
+            statuses.insert(*id, status.clone());
+        }
+    }
+
+    /// Records that the refund of a message was requested. Returns false if
+    /// it already was, so every message is refunded at most once.
+    pub fn record_refund(&self, id: MessageId) -> bool {
+        self.refunds.lock().insert(id)
+    }
+}
+
+impl Default for MessageStatusTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::types::ChainId;
+
+    #[test]
+    fn test_rejects_replays_of_executed_messages() {
+        let tracker = MessageStatusTracker::new();
+        let id = MessageId::compute(ChainId::ETHEREUM, &[1; 32], 1, b"hello");
+        let other = MessageId::compute(ChainId::ETHEREUM, &[1; 32], 2, b"hello");
+
+        tracker.claim(&[id]).unwrap();
+        assert_eq!(tracker.status(&id), Some(MessageStatus::Delivered));
+        tracker.finish(&[id], &Err::<(), _>(CCIHSError::NetworkError("down".to_string())));
+        assert_eq!(tracker.status(&id), Some(MessageStatus::Failed));
+
+        tracker.claim(&[id]).unwrap();
+        tracker.finish(&[id], &Ok(()));
+        assert_eq!(tracker.status(&id), Some(MessageStatus::Executed));
+        assert!(matches!(tracker.claim(&[other, id]), Err(CCIHSError::MessageAlreadyProcessed)));
+        assert_eq!(tracker.status(&other), None);
+    }
+
+    #[test]
+    fn test_expired_messages_are_refunded_once() {
+        let tracker = MessageStatusTracker::new();
+        let id = MessageId::compute(ChainId::ETHEREUM, &[1; 32], 1, b"transfer");
+
+        tracker.claim(&[id]).unwrap();
+        tracker.finish(&[id], &Err::<(), _>(CCIHSError::MessageExpired));
+        assert_eq!(tracker.status(&id), Some(MessageStatus::Expired));
+        assert!(matches!(tracker.claim(&[id]), Err(CCIHSError::MessageAlreadyProcessed)));
+
+        assert!(tracker.record_refund(id));
+        assert!(!tracker.record_refund(id));
+    }
+}
@@ -0,0 +1,144 @@
+// src/entrypoint.rs
+//
+// Native (non-Anchor) program managing `CrossChainMessageState` accounts.
+// Built with `--no-default-features --features native`. Programs embedding
+// CCIHS as a library should also enable `no-entrypoint`.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+
+use crate::state::{derive_state_address, CrossChainMessageState, CrossChainMessageStateTrait, STATE_SEED_PREFIX};
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+/// Instructions of the native program, borsh encoded.
+pub enum StateInstruction {
+    /// Creates the sender's state account.
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` sender, pays for the account
+    /// 1. `[writable]` sender's state PDA
+    /// 2. `[]` system program
+    Initialize,
+    /// Records a message sent by the sender.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` sender
+    /// 1. `[writable]` sender's state PDA
+    Update { nonce: u64 },
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = StateInstruction::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    match instruction {
+        StateInstruction::Initialize => process_initialize(program_id, accounts),
+        StateInstruction::Update { nonce } => process_update(program_id, accounts, nonce),
+    }
+}
+
+fn process_initialize(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let sender = next_account_info(account_info_iter)?;
+    let state_account = next_account_info(account_info_iter)?;
+    let system_program_account = next_account_info(account_info_iter)?;
+
+    if !sender.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if *system_program_account.key != system_program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (state_address, bump) = derive_state_address(program_id, sender.key);
+    if *state_account.key != state_address {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !state_account.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let lamports = Rent::get()?.minimum_balance(CrossChainMessageState::LEN);
+    invoke_signed(
+        &system_instruction::create_account(
+            sender.key,
+            state_account.key,
+            lamports,
+            CrossChainMessageState::LEN as u64,
+            program_id,
+        ),
+        &[sender.clone(), state_account.clone(), system_program_account.clone()],
+        &[&[STATE_SEED_PREFIX, sender.key.as_ref(), &[bump]]],
+    )?;
+
+    CrossChainMessageState::new().serialize(&mut &mut state_account.data.borrow_mut()[..])?;
+
+    msg!("Initialized message state for {}", sender.key);
+    Ok(())
+}
+
+fn process_update(program_id: &Pubkey, accounts: &[AccountInfo], nonce: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let sender = next_account_info(account_info_iter)?;
+    let state_account = next_account_info(account_info_iter)?;
+
+    if !sender.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if state_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (state_address, _) = derive_state_address(program_id, sender.key);
+    if *state_account.key != state_address {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut state = CrossChainMessageState::try_from_slice(&state_account.data.borrow())?;
+    state.update_with_message(nonce, Clock::get()?.unix_timestamp);
+    state.serialize(&mut &mut state_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_instruction_encoding() {
+        assert_eq!(StateInstruction::Initialize.try_to_vec().unwrap(), vec![0]);
+
+        let update = StateInstruction::Update { nonce: 7 };
+        let data = update.try_to_vec().unwrap();
+        assert_eq!(data, [&[1u8][..], &7u64.to_le_bytes()].concat());
+        assert_eq!(StateInstruction::try_from_slice(&data).unwrap(), update);
+    }
+
+    #[test]
+    fn test_rejects_unknown_instruction() {
+        let program_id = Pubkey::new_unique();
+        assert_eq!(
+            process_instruction(&program_id, &[], &[2]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
@@ -3,20 +3,43 @@
 use super::{Hook, HookType};
 use crate::types::{CrossChainMessage, ChainId};
 use crate::{CCIHSResult, CCIHSError};
+use crate::utility::{Clock, SharedClock, default_clock};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 pub struct HookManager {
     hooks: HashMap<HookType, Vec<Box<dyn Hook>>>,
+    shared_clock: Arc<SharedClock>,
+    clock: Arc<dyn Clock>,
 }
 
 impl HookManager {
     pub fn new() -> Self {
+        Self::with_clock(default_clock())
+    }
 
+    /// Creates a hook manager whose default hooks read the time from `clock`.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        let shared_clock = Arc::new(SharedClock::new(clock));
         Self {
             hooks: HashMap::new(),
+            clock: shared_clock.clone(),
+            shared_clock,
         }
     }
 
+    /// Clock of the default hooks. Pass it to time based hooks so that
+    /// [`set_clock`](Self::set_clock) reaches them too.
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// Replaces the clock behind [`clock`](Self::clock), including for hooks
+    /// already built with it.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.shared_clock.replace(clock);
+    }
+
     pub fn add_hook(&mut self, hook_type: HookType, hook: Box<dyn Hook>) {
         self.hooks.entry(hook_type).or_default().push(hook);
     }
@@ -59,7 +82,7 @@ impl HookManager {
 
     pub fn execute_hooks(&self, hook_type: HookType, message: &mut CrossChainMessage, source_chain: ChainId, destination_chain: ChainId) -> CCIHSResult<()> {
         // Execute default behavior first
-        hook_type.execute_default(message, source_chain, destination_chain)?;
+        hook_type.execute_default(message, source_chain, destination_chain, self.clock.as_ref())?;
 
         // Then execute custom hooks
         if let Some(hooks) = self.hooks.get(&hook_type) {
@@ -91,4 +114,32 @@ impl HookManager {
 // hook_manager.add_hook(HookType::PreDispatch, Box::new(ValidationHook::new(1024)));
 // hook_manager.add_hook(HookType::PostDispatch, Box::new(LoggingHook));
 // hook_manager.add_hook(HookType::PreExecution, Box::new(EncryptionHook::new([0u8; 32])));
-// hook_manager.add_hook(HookType::PostExecution, Box::new(MetricsHook::new()))
\ No newline at end of file
+// hook_manager.add_hook(HookType::PostExecution, Box::new(MetricsHook::new()))
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::config::{HookKind, HookSpec};
+    use crate::hooks::rate_limiting::test::message;
+    use crate::utility::TestClock;
+
+    #[test]
+    fn test_set_clock_reaches_built_hooks() {
+        let mut manager = HookManager::new();
+        let spec = HookSpec::new(HookType::PreDispatch, HookKind::RateLimiting { max_messages: 1, window_secs: 10 });
+        let hook = spec.build(manager.clock().clone());
+
+        let clock = TestClock::new(1_000);
+        manager.set_clock(Arc::new(clock.clone()));
+        assert_eq!(manager.clock().unix_timestamp().unwrap(), 1_000);
+
+        hook.execute(&mut message(), ChainId::SOLANA, ChainId::ETHEREUM).unwrap();
+        assert!(matches!(
+            hook.execute(&mut message(), ChainId::SOLANA, ChainId::ETHEREUM),
+            Err(CCIHSError::RateLimitExceeded)
+        ));
+
+        clock.advance(10);
+        hook.execute(&mut message(), ChainId::SOLANA, ChainId::ETHEREUM).unwrap();
+    }
+}
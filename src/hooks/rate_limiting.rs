@@ -1,36 +1,46 @@
 use super::Hook;
 use crate::types::{CrossChainMessage, ChainId, CCIHSResult};
 use crate::CCIHSError;
+use crate::utility::{Clock, default_clock};
 use std::collections::VecDeque;
-use std::time::{Duration, Instant};
+use std::sync::Arc;
+use std::time::Duration;
 use parking_lot::Mutex;
 
 pub struct RateLimitingHook {
     max_messages: usize,
     time_window: Duration,
-    message_times: Mutex<VecDeque<Instant>>,
+    message_times: Mutex<VecDeque<u64>>,
+    clock: Arc<dyn Clock>,
 }
 
 impl RateLimitingHook {
     pub fn new(max_messages: usize, time_window: Duration) -> Self {
+        Self::with_clock(max_messages, time_window, default_clock())
+    }
+
+    /// Timestamps are compared in milliseconds, so sub-second windows work
+    /// with clocks that report milliseconds.
+    pub fn with_clock(max_messages: usize, time_window: Duration, clock: Arc<dyn Clock>) -> Self {
         Self {
             max_messages,
             time_window,
             message_times: Mutex::new(VecDeque::new()),
+            clock,
         }
     }
 }
 
 impl Hook for RateLimitingHook {
     fn execute(&self, _message: &mut CrossChainMessage, _source_chain: ChainId, _destination_chain: ChainId) -> CCIHSResult<()> {
-        let now = Instant::now();
+        let now = self.clock.unix_timestamp_millis()?;
         let mut message_times = self.message_times.lock();
 
         message_times.push_back(now);
 
         if message_times.len() > self.max_messages {
             let oldest = message_times.pop_front().unwrap();
-            if now.duration_since(oldest) < self.time_window {
+            if u128::from(now.saturating_sub(oldest)) < self.time_window.as_millis() {
                 return Err(CCIHSError::RateLimitExceeded);
             }
         }
@@ -39,6 +49,63 @@ impl Hook for RateLimitingHook {
     }
 }
 
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::types::{CrossChainAddress, MessageType};
+    use crate::utility::TestClock;
+    use anchor_lang::prelude::Pubkey;
+
+    pub fn message() -> CrossChainMessage {
+        CrossChainMessage {
+            message_type: MessageType::General,
+            payload: b"hello".to_vec(),
+            amount: 0,
+            token_address: None,
+            sender: CrossChainAddress::Solana(Pubkey::new_unique()),
+            recipient: CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(0x11)),
+            source_chain: ChainId::SOLANA,
+            destination_chain: ChainId::ETHEREUM,
+            nonce: 0,
+            timestamp: 1_000,
+            consistency_level: 0,
+            expires_at: None,
+            execute_after: None,
+            message_id: None,
+        }
+    }
+
+    fn send(hook: &RateLimitingHook) -> CCIHSResult<()> {
+        hook.execute(&mut message(), ChainId::SOLANA, ChainId::ETHEREUM)
+    }
+
+    #[test]
+    fn test_limits_messages_per_window() {
+        let clock = TestClock::new(1_000);
+        let hook = RateLimitingHook::with_clock(2, Duration::from_secs(10), Arc::new(clock.clone()));
+
+        send(&hook).unwrap();
+        send(&hook).unwrap();
+        assert!(matches!(send(&hook), Err(CCIHSError::RateLimitExceeded)));
+
+        clock.advance(10);
+        send(&hook).unwrap();
+    }
+
+    #[test]
+    fn test_sub_second_window() {
+        let clock = TestClock::new(1_000);
+        let hook = RateLimitingHook::with_clock(1, Duration::from_millis(500), Arc::new(clock.clone()));
+
+        send(&hook).unwrap();
+        clock.advance_millis(100);
+        assert!(matches!(send(&hook), Err(CCIHSError::RateLimitExceeded)));
+
+        clock.advance_millis(500);
+        send(&hook).unwrap();
+    }
+}
+
 
 // use std::collections::VecDeque;
 // use std::time::{Duration, Instant};
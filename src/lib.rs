@@ -1,14 +1,31 @@
+// Everything but the message state and the native entrypoint is built on
+// Anchor types, so a `native` build (without `anchor`) is just the state
+// program in `entrypoint`.
+#[cfg(feature = "anchor")]
 pub mod core;
+#[cfg(feature = "anchor")]
 pub mod protocols;
+#[cfg(feature = "anchor")]
 pub mod api;
+#[cfg(feature = "anchor")]
 pub mod hooks;
+#[cfg(feature = "anchor")]
 pub mod utility;
+#[cfg(feature = "anchor")]
 pub mod types;
 pub mod state;
+#[cfg(feature = "anchor")]
 pub mod config;
 pub mod constants;
+#[cfg(feature = "anchor")]
+pub mod relayer;
+#[cfg(feature = "anchor")]
+pub mod client;
+#[cfg(all(feature = "native", not(feature = "anchor")))]
+pub mod entrypoint;
 
-pub use utility::{CCIHSError, serialization};
+#[cfg(feature = "anchor")]
+pub use utility::{CCIHSError, serialization, Clock, SolanaClock, SystemClock, TestClock};
 
 // pub use types::{
 //     ChainId, 
@@ -19,13 +36,19 @@ pub use utility::{CCIHSError, serialization};
 //     CrossChainResult,
 // };
 
+#[cfg(feature = "anchor")]
 pub use types::*;
 
+#[cfg(feature = "anchor")]
 pub use hooks::{Hook, HookType, HookManager};
 
 pub use state::*;
+#[cfg(feature = "anchor")]
 pub use core::CCIHSCore;  // Export CCIHSCore for easy access
+#[cfg(feature = "anchor")]
 pub use config::CCIHSConfig;  
+#[cfg(feature = "anchor")]
 pub use api::CCIHSAPI; 
+#[cfg(feature = "anchor")]
 pub use protocols::*;
 pub use constants::*;
@@ -1,3 +1,389 @@
-fn main() {
-    
-}
\ No newline at end of file
+// src/main.rs
+
+//! `ccihs`: offline tooling for CCIHS deployments. Nothing here talks to a
+//! network, so every command can run on an air-gapped signing machine.
+
+use ccihs::config::{CCIHSConfig, NetworkProfile, WormholeSettings};
+use ccihs::types::{
+    BatchMember, MessageChunk, ProtocolType, QuorumEnvelope, RefundNotice, RequestEnvelope,
+    PAYLOAD_ID_BATCH_MEMBER, PAYLOAD_ID_CHUNK, PAYLOAD_ID_INITIALIZE, PAYLOAD_ID_MESSAGE, PAYLOAD_ID_QUORUM,
+    PAYLOAD_ID_REFUND, PAYLOAD_ID_REQUEST, PAYLOAD_ID_RESPONSE,
+};
+use ccihs::utility::encoding::{from_hex, from_hex_or_base64, to_base64, to_hex};
+use ccihs::wormhole::{unsigned_transaction, DecodedAccount, InstructionBuilder, ParsedVaa};
+use ccihs::{CCIHSError, CCIHSResult};
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::AnchorDeserialize;
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+#[derive(Parser)]
+#[command(name = "ccihs", version, about = "Offline tooling for CCIHS deployments")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build an unsigned transaction.
+    #[command(subcommand)]
+    Tx(TxCommand),
+    /// Decode a signed VAA given as hex or base64.
+    DecodeVaa { vaa: String },
+    /// Decode a CCIHS message payload given as hex or base64.
+    DecodePayload { payload: String },
+    /// Decode raw account data of the Wormhole program given as hex or base64.
+    DecodeAccount {
+        data: String,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Derive the PDAs of a deployment.
+    Pdas {
+        #[command(flatten)]
+        deployment: Deployment,
+        /// Also derive the foreign emitter accounts of these Wormhole chains.
+        #[arg(long = "chain")]
+        chains: Vec<u16>,
+        /// Also derive the message state account of these senders.
+        #[arg(long = "sender")]
+        senders: Vec<String>,
+    },
+    /// Quote the relayer fee of a token transfer.
+    QuoteFee {
+        amount: u64,
+        /// Read the relayer fee from the Wormhole settings of this file.
+        #[arg(long)]
+        config: Option<PathBuf>,
+        #[arg(long)]
+        relayer_fee: Option<u32>,
+        #[arg(long)]
+        relayer_fee_precision: Option<u32>,
+        /// Core bridge message fee in lamports, as stored in its bridge
+        /// account.
+        #[arg(long, default_value_t = 0)]
+        message_fee: u64,
+    },
+    /// Validate a configuration file and report every problem.
+    ValidateConfig { path: PathBuf },
+}
+
+#[derive(Subcommand)]
+enum TxCommand {
+    Initialize {
+        #[command(flatten)]
+        tx: TxArgs,
+        #[arg(long)]
+        relayer_fee: u64,
+        #[arg(long)]
+        relayer_fee_precision: u32,
+    },
+    RegisterEmitter {
+        #[command(flatten)]
+        tx: TxArgs,
+        #[arg(long)]
+        chain: u16,
+        /// 32 byte emitter address as hex.
+        #[arg(long)]
+        address: String,
+    },
+    UpdateRelayerFee {
+        #[command(flatten)]
+        tx: TxArgs,
+        #[arg(long)]
+        relayer_fee: u32,
+        #[arg(long)]
+        relayer_fee_precision: u32,
+    },
+    Send {
+        #[command(flatten)]
+        tx: TxArgs,
+        /// Next value of the program emitter's sequence tracker.
+        #[arg(long)]
+        sequence: u64,
+        /// Message as UTF-8 text.
+        #[arg(long, conflicts_with = "hex", required_unless_present = "hex")]
+        message: Option<String>,
+        /// Message as hex.
+        #[arg(long)]
+        hex: Option<String>,
+        #[arg(long)]
+        expires_at: Option<u64>,
+        #[arg(long)]
+        execute_after: Option<u64>,
+    },
+}
+
+#[derive(Args)]
+struct Deployment {
+    #[arg(long)]
+    program_id: String,
+    /// mainnet, testnet or localnet. Picks the bridge programs.
+    #[arg(long, default_value = "mainnet")]
+    network: String,
+    #[arg(long)]
+    core_bridge: Option<String>,
+    #[arg(long)]
+    token_bridge: Option<String>,
+}
+
+#[derive(Args)]
+struct TxArgs {
+    #[command(flatten)]
+    deployment: Deployment,
+    /// Fee payer and signer (the owner for admin instructions).
+    #[arg(long)]
+    payer: String,
+    /// Recent blockhash or durable nonce, base58.
+    #[arg(long)]
+    blockhash: String,
+}
+
+fn invalid(reason: impl Into<String>) -> CCIHSError {
+    CCIHSError::InvalidConfigEntry {
+        path: "command line".to_string(),
+        key: String::new(),
+        reason: reason.into(),
+    }
+}
+
+fn parse_pubkey(value: &str) -> CCIHSResult<Pubkey> {
+    Pubkey::from_str(value).map_err(|_| invalid(format!("`{value}` is not a base58 public key")))
+}
+
+fn parse_address(value: &str) -> CCIHSResult<[u8; 32]> {
+    from_hex(value)?
+        .try_into()
+        .map_err(|_| invalid(format!("`{value}` is not a 32 byte hex address")))
+}
+
+impl Deployment {
+    fn builder(&self) -> CCIHSResult<InstructionBuilder> {
+        let profile = NetworkProfile::from_name(&self.network)
+            .ok_or_else(|| invalid(format!("unknown network `{}`", self.network)))?;
+        let core_bridge = match &self.core_bridge {
+            Some(value) => parse_pubkey(value)?,
+            None => profile.wormhole_core_bridge(),
+        };
+        let token_bridge = match &self.token_bridge {
+            Some(value) => parse_pubkey(value)?,
+            None => profile.wormhole_token_bridge(),
+        };
+        Ok(InstructionBuilder::new(parse_pubkey(&self.program_id)?, core_bridge, token_bridge))
+    }
+}
+
+fn print_transaction(instruction: Instruction, tx: &TxArgs) -> CCIHSResult<()> {
+    let payer = parse_pubkey(&tx.payer)?;
+    let blockhash = Hash::from_str(&tx.blockhash)
+        .map_err(|_| invalid(format!("`{}` is not a base58 blockhash", tx.blockhash)))?;
+
+    println!("program: {}", instruction.program_id);
+    for (index, account) in instruction.accounts.iter().enumerate() {
+        let flags = match (account.is_signer, account.is_writable) {
+            (true, true) => "signer, writable",
+            (true, false) => "signer",
+            (false, true) => "writable",
+            (false, false) => "",
+        };
+        println!("  {index:>2} {} {flags}", account.pubkey);
+    }
+    println!("data: {}", to_hex(&instruction.data));
+    println!("transaction: {}", to_base64(&unsigned_transaction(&[instruction], &payer, blockhash)));
+    Ok(())
+}
+
+fn run_tx(command: TxCommand) -> CCIHSResult<()> {
+    match command {
+        TxCommand::Initialize { tx, relayer_fee, relayer_fee_precision } => {
+            let builder = tx.deployment.builder()?;
+            let instruction = builder.initialize(&parse_pubkey(&tx.payer)?, relayer_fee, relayer_fee_precision)?;
+            print_transaction(instruction, &tx)
+        }
+        TxCommand::RegisterEmitter { tx, chain, address } => {
+            let builder = tx.deployment.builder()?;
+            let instruction = builder.register_emitter(&parse_pubkey(&tx.payer)?, chain, parse_address(&address)?)?;
+            print_transaction(instruction, &tx)
+        }
+        TxCommand::UpdateRelayerFee { tx, relayer_fee, relayer_fee_precision } => {
+            let builder = tx.deployment.builder()?;
+            let instruction = builder.update_relayer_fee(&parse_pubkey(&tx.payer)?, relayer_fee, relayer_fee_precision)?;
+            print_transaction(instruction, &tx)
+        }
+        TxCommand::Send { tx, sequence, message, hex, expires_at, execute_after } => {
+            let builder = tx.deployment.builder()?;
+            let message = match (message, hex) {
+                (Some(text), _) => text.into_bytes(),
+                (None, Some(hex)) => from_hex(&hex)?,
+                (None, None) => unreachable!("clap requires --message or --hex"),
+            };
+            let instruction = builder.send_message(&parse_pubkey(&tx.payer)?, sequence, message, expires_at, execute_after)?;
+            print_transaction(instruction, &tx)
+        }
+    }
+}
+
+fn decode_error(e: std::io::Error) -> CCIHSError {
+    CCIHSError::DeserializationError(e.to_string())
+}
+
+fn print_payload(payload: &[u8]) -> CCIHSResult<()> {
+    let (payload_id, body) = payload.split_first().ok_or(CCIHSError::EmptyPayload)?;
+    match *payload_id {
+        PAYLOAD_ID_INITIALIZE => {
+            let program_id: [u8; 32] = body
+                .try_into()
+                .map_err(|_| CCIHSError::DeserializationError("initialize payload is not 32 bytes".to_string()))?;
+            println!("Initialize {{ program_id: {} }}", Pubkey::new_from_array(program_id));
+        }
+        PAYLOAD_ID_MESSAGE => match std::str::from_utf8(body) {
+            Ok(text) => println!("Message {{ content: {text:?} }}"),
+            Err(_) => println!("Message {{ content: 0x{} }}", to_hex(body)),
+        },
+        PAYLOAD_ID_CHUNK => println!("{:#?}", MessageChunk::try_from_slice(payload).map_err(decode_error)?),
+        PAYLOAD_ID_BATCH_MEMBER => println!("{:#?}", BatchMember::try_from_slice(payload).map_err(decode_error)?),
+        PAYLOAD_ID_REQUEST | PAYLOAD_ID_RESPONSE => {
+            println!("{:#?}", RequestEnvelope::try_from_slice(payload).map_err(decode_error)?)
+        }
+        PAYLOAD_ID_REFUND => println!("{:#?}", RefundNotice::try_from_slice(payload).map_err(decode_error)?),
+        PAYLOAD_ID_QUORUM => {
+            let envelope = QuorumEnvelope::try_from_slice(payload).map_err(decode_error)?;
+            print!("QuorumEnvelope {{ sequence: {} }} of ", envelope.sequence);
+            print_payload(&envelope.payload)?;
+        }
+        other => println!("unknown payload ID {other}: 0x{}", to_hex(body)),
+    }
+    Ok(())
+}
+
+fn print_vaa(vaa: &ParsedVaa) -> CCIHSResult<()> {
+    println!("version: {}", vaa.version);
+    println!("guardian_set_index: {}", vaa.guardian_set_index);
+    println!("signatures: {}", vaa.signatures.len());
+    for signature in &vaa.signatures {
+        println!("  {:>2} {}", signature.guardian_index, to_hex(&signature.signature));
+    }
+    println!("timestamp: {}", vaa.timestamp);
+    println!("nonce: {}", vaa.nonce);
+    println!("emitter_chain: {}", vaa.emitter_chain);
+    println!("emitter_address: {}", to_hex(&vaa.emitter_address));
+    println!("sequence: {}", vaa.sequence);
+    println!("consistency_level: {}", vaa.consistency_level);
+    println!("hash: {}", to_hex(&vaa.hash()));
+    println!("digest: {}", to_hex(&vaa.digest()));
+    println!("payload: {}", to_hex(&vaa.payload));
+    print!("decoded payload: ");
+    print_payload(&vaa.payload)
+}
+
+fn print_pdas(builder: &InstructionBuilder, chains: &[u16], senders: &[String]) -> CCIHSResult<()> {
+    let mut pdas = vec![
+        ("general_message_config".to_string(), builder.general_message_config()),
+        ("sender_config".to_string(), builder.sender_config()),
+        ("redeemer_config".to_string(), builder.redeemer_config()),
+        ("emitter".to_string(), builder.emitter()),
+        ("sequence".to_string(), builder.sequence()),
+        ("delivery_authority".to_string(), builder.delivery_authority()),
+        ("wormhole_bridge".to_string(), builder.wormhole_bridge()),
+        ("wormhole_fee_collector".to_string(), builder.wormhole_fee_collector()),
+        ("token_bridge_config".to_string(), builder.token_bridge_config()),
+        ("token_bridge_authority_signer".to_string(), builder.token_bridge_authority_signer()),
+        ("token_bridge_custody_signer".to_string(), builder.token_bridge_custody_signer()),
+        ("token_bridge_mint_authority".to_string(), builder.token_bridge_mint_authority()),
+        ("token_bridge_emitter".to_string(), builder.token_bridge_emitter()),
+        ("token_bridge_sequence".to_string(), builder.token_bridge_sequence()),
+    ];
+    for &chain in chains {
+        pdas.push((format!("foreign_emitter[{chain}]"), builder.foreign_emitter(chain)));
+        pdas.push((format!("foreign_token_emitter[{chain}]"), builder.foreign_token_emitter(chain)));
+    }
+    for sender in senders {
+        pdas.push((format!("sender_state[{sender}]"), builder.sender_state(&parse_pubkey(sender)?)));
+    }
+
+    let width = pdas.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, address) in pdas {
+        println!("{name:<width$}  {address}");
+    }
+    Ok(())
+}
+
+fn quote_fee(
+    amount: u64,
+    config: Option<PathBuf>,
+    relayer_fee: Option<u32>,
+    relayer_fee_precision: Option<u32>,
+    message_fee: u64,
+) -> CCIHSResult<()> {
+    let settings = match config {
+        Some(path) => CCIHSConfig::from_file(path)?
+            .protocols
+            .get(&ProtocolType::Wormhole)
+            .and_then(|protocol| protocol.settings.as_wormhole().cloned())
+            .ok_or_else(|| CCIHSError::ProtocolNotConfigured(ProtocolType::Wormhole.to_string()))?,
+        None => WormholeSettings::new(Pubkey::default()),
+    };
+    let relayer_fee = relayer_fee.unwrap_or(settings.relayer_fee);
+    let precision = relayer_fee_precision.unwrap_or(settings.relayer_fee_precision);
+    if precision == 0 || relayer_fee >= precision {
+        return Err(invalid("relayer fee must be less than a non-zero relayer fee precision"));
+    }
+
+    // Same formula as `RedeemerConfig::compute_relayer_amount`, without the
+    // overflow.
+    let relayer_amount = (amount as u128 * relayer_fee as u128 / precision as u128) as u64;
+    println!("amount: {amount}");
+    println!("relayer fee: {relayer_amount} ({relayer_fee}/{precision})");
+    println!("recipient receives: {}", amount - relayer_amount);
+    println!("core bridge message fee: {message_fee} lamports");
+    Ok(())
+}
+
+fn validate_config(path: PathBuf) -> CCIHSResult<bool> {
+    let config = CCIHSConfig::from_file(&path)?;
+    let report = config.validation_report();
+    if report.is_ok() {
+        println!("{}: ok", path.display());
+    } else {
+        println!("{}:\n{report}", path.display());
+    }
+    Ok(report.is_ok())
+}
+
+fn run(cli: Cli) -> CCIHSResult<bool> {
+    match cli.command {
+        Command::Tx(command) => run_tx(command)?,
+        Command::DecodeVaa { vaa } => print_vaa(&ParsedVaa::parse(&from_hex_or_base64(&vaa)?)?)?,
+        Command::DecodePayload { payload } => print_payload(&from_hex_or_base64(&payload)?)?,
+        Command::DecodeAccount { data, json } => {
+            let account = DecodedAccount::decode(&from_hex_or_base64(&data)?)?;
+            if json {
+                println!("{:#}", account.to_json());
+            } else {
+                print!("{account}");
+            }
+        }
+        Command::Pdas { deployment, chains, senders } => print_pdas(&deployment.builder()?, &chains, &senders)?,
+        Command::QuoteFee { amount, config, relayer_fee, relayer_fee_precision, message_fee } => {
+            quote_fee(amount, config, relayer_fee, relayer_fee_precision, message_fee)?
+        }
+        Command::ValidateConfig { path } => return validate_config(path),
+    }
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
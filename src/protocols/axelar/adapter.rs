@@ -0,0 +1,195 @@
+// protocols/axelar/adapter.rs
+
+use super::{chain_for_name, chain_name, command_id, ApprovedMessage, GasPayment, GasQuote, GmpCall, WeightedSigners};
+use crate::config::{AxelarSettings, ChainConfig};
+use crate::protocols::{ProtocolAdapter, VerifiedInbox};
+use crate::types::{ChainId, CrossChainAddress, CrossChainMessage, MessageId, MessageType, CCIHSResult};
+use crate::CCIHSError;
+use parking_lot::Mutex;
+use solana_program::keccak;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Debug, PartialEq)]
+/// What [`AxelarAdapter::send_message`] asks the caller to submit.
+pub enum AxelarOutbound {
+    /// Gateway `call_contract`.
+    CallContract(GmpCall),
+    /// Gas service prepayment for the call before it.
+    PayGas(GasPayment),
+}
+
+/// Sends and receives General Message Passing calls through the Axelar
+/// gateway.
+///
+/// Like the other off-chain adapters it does not talk to the chain. Calls
+/// are queued as [`AxelarOutbound`] items, approvals signed by the operator
+/// set are fed in through [`handle_approvals`](Self::handle_approvals), and
+/// an approved message becomes receivable once [`execute`](Self::execute)
+/// supplies a payload matching its hash.
+pub struct AxelarAdapter {
+    settings: AxelarSettings,
+    operators: WeightedSigners,
+    outbox: Mutex<Vec<AxelarOutbound>>,
+    /// Approved but not yet executed messages, by command ID.
+    approved: Mutex<HashMap<[u8; 32], ApprovedMessage>>,
+    executed: Mutex<HashSet<[u8; 32]>>,
+    inbox: VerifiedInbox,
+}
+
+impl AxelarAdapter {
+    pub fn new(settings: AxelarSettings) -> Self {
+        let operators = WeightedSigners::new(
+            settings.operators.clone(),
+            settings.operator_threshold,
+            settings.operator_set_nonce,
+        );
+        Self {
+            settings,
+            operators,
+            outbox: Mutex::new(Vec::new()),
+            approved: Mutex::new(HashMap::new()),
+            executed: Mutex::new(HashSet::new()),
+            inbox: VerifiedInbox::new(),
+        }
+    }
+
+    pub fn settings(&self) -> &AxelarSettings {
+        &self.settings
+    }
+
+    /// Everything queued by `send_message` since the last call.
+    pub fn take_outbound(&self) -> Vec<AxelarOutbound> {
+        std::mem::take(&mut *self.outbox.lock())
+    }
+
+    fn chain_name(&self, chain: ChainId) -> CCIHSResult<&'static str> {
+        if !self.settings.supported_chains.contains(&chain) {
+            return Err(CCIHSError::InvalidChainId);
+        }
+        chain_name(chain).ok_or(CCIHSError::InvalidChainId)
+    }
+
+    /// What the gas service charges to execute `gas_limit` units of gas on
+    /// `destination`.
+    pub fn quote_gas(&self, destination: ChainId, gas_limit: u64) -> CCIHSResult<GasQuote> {
+        let name = self.chain_name(destination)?;
+        let gas_price = self
+            .settings
+            .gas_prices
+            .get(&destination)
+            .ok_or_else(|| CCIHSError::ProtocolError(format!("axelar: no gas price for {}", name)))?;
+        Ok(GasQuote::new(
+            self.settings.base_fee,
+            gas_limit,
+            *gas_price,
+            self.settings.gas_multiplier_bps,
+        ))
+    }
+
+    /// Verifies a batch of approvals against the operator set and records
+    /// the messages for execution. Messages already approved or executed are
+    /// skipped, as the gateway does.
+    pub fn handle_approvals(&self, messages: &[ApprovedMessage], signatures: &[[u8; 65]]) -> CCIHSResult<()> {
+        self.operators.verify(&self.settings.domain_separator, messages, signatures)?;
+
+        let executed = self.executed.lock();
+        let mut approved = self.approved.lock();
+        for message in messages {
+            let command_id = message.command_id();
+            if !executed.contains(&command_id) {
+                approved.entry(command_id).or_insert_with(|| message.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes an approved message with its payload, queueing it for
+    /// `receive_message`.
+    pub fn execute(&self, source_chain: &str, message_id: &str, payload: &[u8]) -> CCIHSResult<()> {
+        let command_id = command_id(source_chain, message_id);
+        let mut executed = self.executed.lock();
+        if executed.contains(&command_id) {
+            return Err(CCIHSError::MessageAlreadyProcessed);
+        }
+        let mut approved = self.approved.lock();
+        let message = approved
+            .get(&command_id)
+            .ok_or_else(|| CCIHSError::ProtocolError(format!("axelar: message {} is not approved", message_id)))?;
+        if message.payload_hash != keccak::hash(payload).to_bytes() {
+            return Err(CCIHSError::ProtocolError("axelar: payload does not match the approved hash".to_string()));
+        }
+
+        let source = chain_for_name(&message.source_chain)
+            .filter(|chain| self.settings.supported_chains.contains(chain))
+            .ok_or(CCIHSError::InvalidChainId)?;
+        let received = CrossChainMessage {
+            message_type: MessageType::General,
+            payload: payload.to_vec(),
+            amount: 0,
+            token_address: None,
+            sender: CrossChainAddress::parse(source, &message.source_address)?,
+            recipient: CrossChainAddress::parse(ChainId::SOLANA, &message.contract_address)?,
+            source_chain: source,
+            destination_chain: ChainId::SOLANA,
+            // GMP messages have no nonce; they are identified by command ID,
+            // which takes the emitter's place in the message ID.
+            nonce: 0,
+            timestamp: 0,
+            consistency_level: 0,
+            expires_at: None,
+            execute_after: None,
+            message_id: Some(MessageId::compute(source, &command_id, 0, payload)),
+        };
+
+        approved.remove(&command_id);
+        executed.insert(command_id);
+        self.inbox.push(received)
+    }
+}
+
+impl ProtocolAdapter for AxelarAdapter {
+    /// Queues the gateway call and its gas prepayment, quoted for the
+    /// configured gas limit. Unused gas is refunded to the sender.
+    fn send_message(&self, message: &CrossChainMessage, _source_config: &ChainConfig, _destination_config: &ChainConfig) -> CCIHSResult<()> {
+        if message.message_type != MessageType::General {
+            return Err(CCIHSError::UnsupportedOperation);
+        }
+        if message.payload.is_empty() {
+            return Err(CCIHSError::EmptyPayload);
+        }
+        let refund_address = match message.sender {
+            CrossChainAddress::Solana(sender) => sender,
+            _ => return Err(CCIHSError::InvalidAddress),
+        };
+
+        let call = GmpCall {
+            destination_chain: self.chain_name(message.destination_chain)?.to_string(),
+            destination_address: message.recipient.to_string(),
+            payload: message.payload.clone(),
+        };
+        let gas = GasPayment {
+            destination_chain: call.destination_chain.clone(),
+            destination_address: call.destination_address.clone(),
+            payload_hash: call.payload_hash(),
+            amount: self.quote_gas(message.destination_chain, self.settings.gas_limit)?.total(),
+            refund_address,
+        };
+
+        let mut outbox = self.outbox.lock();
+        outbox.push(AxelarOutbound::CallContract(call));
+        outbox.push(AxelarOutbound::PayGas(gas));
+        Ok(())
+    }
+
+    fn receive_message(&self, source_config: &ChainConfig) -> CCIHSResult<CrossChainMessage> {
+        self.inbox.pop(source_config.chain_id)
+    }
+
+    fn verify_message(&self, message: &CrossChainMessage, _source_config: &ChainConfig, _destination_config: &ChainConfig) -> CCIHSResult<bool> {
+        self.inbox.is_verified(message)
+    }
+
+    fn supported_chains(&self) -> Vec<ChainId> {
+        self.settings.supported_chains.iter().cloned().collect()
+    }
+}
@@ -0,0 +1,35 @@
+// protocols/axelar/chain.rs
+
+use crate::types::ChainId;
+
+/// Axelar chain name of a chain, by Wormhole chain ID. These are the names
+/// the gateway expects as `destinationChain`.
+pub fn chain_name(chain: ChainId) -> Option<&'static str> {
+    match chain.0 {
+        1 => Some("solana"),
+        2 => Some("Ethereum"),
+        4 => Some("binance"),
+        5 => Some("Polygon"),
+        6 => Some("Avalanche"),
+        10 => Some("Fantom"),
+        14 => Some("celo"),
+        16 => Some("Moonbeam"),
+        23 => Some("arbitrum"),
+        24 => Some("optimism"),
+        30 => Some("base"),
+        10002 => Some("ethereum-sepolia"),
+        10003 => Some("arbitrum-sepolia"),
+        10004 => Some("base-sepolia"),
+        10005 => Some("optimism-sepolia"),
+        _ => None,
+    }
+}
+
+/// The chain with Axelar chain name `name`. Axelar compares names case
+/// insensitively, and so does this.
+pub fn chain_for_name(name: &str) -> Option<ChainId> {
+    [1, 2, 4, 5, 6, 10, 14, 16, 23, 24, 30, 10002, 10003, 10004, 10005]
+        .into_iter()
+        .map(ChainId)
+        .find(|chain| chain_name(*chain).map_or(false, |known| known.eq_ignore_ascii_case(name)))
+}
@@ -0,0 +1,87 @@
+// protocols/axelar/gmp.rs
+
+use crate::protocols::wormhole::instruction_discriminator;
+use crate::utility::abi::{encode_call, Token};
+use anchor_lang::AnchorSerialize;
+use solana_program::keccak;
+use solana_program::pubkey::Pubkey;
+
+#[derive(Clone, Debug, PartialEq)]
+/// A General Message Passing call: `payload` for the contract at
+/// `destination_address` on `destination_chain`, both as Axelar writes them.
+pub struct GmpCall {
+    /// Axelar chain name, see [`chain_name`](super::chain_name).
+    pub destination_chain: String,
+    pub destination_address: String,
+    pub payload: Vec<u8>,
+}
+
+impl GmpCall {
+    /// Keccak256 of the payload. Gateways approve messages by this hash and
+    /// the payload itself is only supplied on execution.
+    pub fn payload_hash(&self) -> [u8; 32] {
+        keccak::hash(&self.payload).to_bytes()
+    }
+
+    /// Anchor instruction data for the gateway program's `call_contract`.
+    pub fn instruction_data(&self) -> Vec<u8> {
+        let mut data = instruction_discriminator("call_contract").to_vec();
+        (self.destination_chain.clone(), self.destination_address.clone(), self.payload.clone())
+            .serialize(&mut data)
+            .expect("writing to a Vec does not fail");
+        data
+    }
+
+    /// Calldata for `callContract` on an EVM gateway.
+    pub fn evm_calldata(&self) -> Vec<u8> {
+        encode_call(
+            "callContract(string,string,bytes)",
+            &[
+                Token::String(self.destination_chain.clone()),
+                Token::String(self.destination_address.clone()),
+                Token::Bytes(self.payload.clone()),
+            ],
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Native gas prepaid to the gas service for executing a [`GmpCall`] on the
+/// destination chain. Unused gas is refunded to `refund_address`.
+pub struct GasPayment {
+    pub destination_chain: String,
+    pub destination_address: String,
+    pub payload_hash: [u8; 32],
+    /// Lamports.
+    pub amount: u64,
+    pub refund_address: Pubkey,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Gas service quote for executing a call on a destination chain, in
+/// lamports.
+pub struct GasQuote {
+    /// Flat fee covering Axelar's own processing.
+    pub base_fee: u64,
+    /// Destination execution at the destination gas price, multiplier
+    /// included.
+    pub execution_fee: u64,
+}
+
+impl GasQuote {
+    /// Quotes `gas_limit` units of destination gas at `gas_price` micro
+    /// lamports per unit, scaled by `multiplier_bps` (10,000 is 1x) to
+    /// absorb price moves before execution. Rounds up.
+    pub fn new(base_fee: u64, gas_limit: u64, gas_price: u64, multiplier_bps: u64) -> Self {
+        const SCALE: u128 = 1_000_000 * 10_000;
+        let execution_fee = (gas_limit as u128 * gas_price as u128 * multiplier_bps as u128 + SCALE - 1) / SCALE;
+        Self {
+            base_fee,
+            execution_fee: u64::try_from(execution_fee).unwrap_or(u64::MAX),
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.base_fee.saturating_add(self.execution_fee)
+    }
+}
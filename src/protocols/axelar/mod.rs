@@ -0,0 +1,9 @@
+mod adapter;
+mod chain;
+mod gmp;
+mod operators;
+
+pub use adapter::{AxelarAdapter, AxelarOutbound};
+pub use chain::{chain_for_name, chain_name};
+pub use gmp::{GasPayment, GasQuote, GmpCall};
+pub use operators::{approval_data_hash, command_id, ApprovedMessage, WeightedSigner, WeightedSigners};
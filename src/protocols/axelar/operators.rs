@@ -0,0 +1,163 @@
+// protocols/axelar/operators.rs
+
+//! Verification of message approvals signed by Axelar's weighted operator
+//! set, following the amplifier gateway: operators sign
+//! `keccak256("\x19Ethereum Signed Message:\n96" | domain_separator | signers_hash | data_hash)`
+//! and the batch is approved once the signers' combined weight reaches the
+//! threshold.
+
+use crate::utility::abi::{encode, Token};
+use crate::utility::ecdsa::{recover_address, EvmAddress};
+use crate::{CCIHSError, CCIHSResult};
+use solana_program::keccak;
+
+/// `CommandType.ApproveMessages` of the gateway.
+const APPROVE_MESSAGES: u128 = 0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightedSigner {
+    pub signer: EvmAddress,
+    pub weight: u128,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// An operator set. Signers are sorted by increasing address, and signatures
+/// of a proof are in the same order.
+pub struct WeightedSigners {
+    pub signers: Vec<WeightedSigner>,
+    pub threshold: u128,
+    /// Tells apart operator sets with the same signers and weights.
+    pub nonce: [u8; 32],
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A message approved by the operators, identified on the gateway by its
+/// command ID.
+pub struct ApprovedMessage {
+    /// Axelar name of the source chain.
+    pub source_chain: String,
+    pub message_id: String,
+    pub source_address: String,
+    /// Destination program. A string, so Solana program IDs fit where the
+    /// EVM gateway has an `address`.
+    pub contract_address: String,
+    pub payload_hash: [u8; 32],
+}
+
+impl ApprovedMessage {
+    /// `keccak256(source_chain | "_" | message_id)`.
+    pub fn command_id(&self) -> [u8; 32] {
+        command_id(&self.source_chain, &self.message_id)
+    }
+
+    fn token(&self) -> Token {
+        Token::Tuple(vec![
+            Token::String(self.source_chain.clone()),
+            Token::String(self.message_id.clone()),
+            Token::String(self.source_address.clone()),
+            Token::String(self.contract_address.clone()),
+            Token::FixedBytes32(self.payload_hash),
+        ])
+    }
+}
+
+pub fn command_id(source_chain: &str, message_id: &str) -> [u8; 32] {
+    keccak::hashv(&[source_chain.as_bytes(), b"_", message_id.as_bytes()]).to_bytes()
+}
+
+/// Hash of the data of an approve-messages batch.
+pub fn approval_data_hash(messages: &[ApprovedMessage]) -> [u8; 32] {
+    let messages = messages.iter().map(ApprovedMessage::token).collect();
+    keccak::hash(&encode(&[Token::Uint(APPROVE_MESSAGES), Token::Array(messages)])).to_bytes()
+}
+
+impl WeightedSigners {
+    pub fn new(signers: Vec<WeightedSigner>, threshold: u128, nonce: [u8; 32]) -> Self {
+        Self { signers, threshold, nonce }
+    }
+
+    /// Keccak256 of the ABI encoded operator set.
+    pub fn hash(&self) -> [u8; 32] {
+        let signers = self
+            .signers
+            .iter()
+            .map(|signer| Token::Tuple(vec![Token::Address(signer.signer), Token::Uint(signer.weight)]))
+            .collect();
+        let encoded = encode(&[Token::Tuple(vec![
+            Token::Array(signers),
+            Token::Uint(self.threshold),
+            Token::FixedBytes32(self.nonce),
+        ])]);
+        keccak::hash(&encoded).to_bytes()
+    }
+
+    /// The digest operators sign for `data_hash`.
+    pub fn message_hash_to_sign(&self, domain_separator: &[u8; 32], data_hash: &[u8; 32]) -> [u8; 32] {
+        keccak::hashv(&[b"\x19Ethereum Signed Message:\n96", domain_separator, &self.hash(), data_hash]).to_bytes()
+    }
+
+    /// Checks that `signatures` approve `messages`: each recovers to a
+    /// signer later in the set than the previous one, and together they
+    /// carry at least the threshold weight.
+    pub fn verify(&self, domain_separator: &[u8; 32], messages: &[ApprovedMessage], signatures: &[[u8; 65]]) -> CCIHSResult<()> {
+        let rejected = |reason: String| CCIHSError::InvalidAttestation(reason);
+        if self.threshold == 0 {
+            return Err(rejected("operator threshold is zero".to_string()));
+        }
+
+        let digest = self.message_hash_to_sign(domain_separator, &approval_data_hash(messages));
+        let mut signers = self.signers.iter();
+        let mut weight: u128 = 0;
+        for (position, signature) in signatures.iter().enumerate() {
+            let signer = recover_address(&digest, signature)?;
+            let operator = signers
+                .find(|operator| operator.signer == signer)
+                .ok_or_else(|| rejected(format!("signature {} is not from an operator, or is out of order", position)))?;
+            weight = weight.saturating_add(operator.weight);
+            if weight >= self.threshold {
+                return Ok(());
+            }
+        }
+        Err(rejected(format!("signed weight {} is below the threshold {}", weight, self.threshold)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utility::ecdsa::testing::{address, secret_key, sign};
+
+    #[test]
+    fn verifies_weighted_signatures() {
+        let mut keys: Vec<_> = (1..=3).map(secret_key).collect();
+        keys.sort_by_key(address);
+        let weights = [1, 2, 3];
+        let operators = WeightedSigners::new(
+            keys.iter()
+                .zip(weights)
+                .map(|(key, weight)| WeightedSigner { signer: address(key), weight })
+                .collect(),
+            4,
+            [0; 32],
+        );
+        let domain_separator = [9; 32];
+        let messages = vec![ApprovedMessage {
+            source_chain: "Ethereum".to_string(),
+            message_id: "0xabc-1".to_string(),
+            source_address: "0x00000000000000000000000000000000000000aa".to_string(),
+            contract_address: "11111111111111111111111111111111".to_string(),
+            payload_hash: [5; 32],
+        }];
+        let digest = operators.message_hash_to_sign(&domain_separator, &approval_data_hash(&messages));
+
+        operators.verify(&domain_separator, &messages, &[sign(&keys[0], &digest), sign(&keys[2], &digest)]).unwrap();
+        // Weight 1 + 2 is below the threshold.
+        assert!(operators.verify(&domain_separator, &messages, &[sign(&keys[0], &digest), sign(&keys[1], &digest)]).is_err());
+        // Out of order.
+        assert!(operators.verify(&domain_separator, &messages, &[sign(&keys[2], &digest), sign(&keys[1], &digest)]).is_err());
+
+        let mut tampered = messages.clone();
+        tampered[0].payload_hash = [6; 32];
+        assert!(operators.verify(&domain_separator, &tampered, &[sign(&keys[0], &digest), sign(&keys[2], &digest)]).is_err());
+    }
+}
@@ -0,0 +1,314 @@
+// protocols/cctp/adapter.rs
+
+use super::{chain_for_domain, domain_for_chain, AttesterSet, BurnMessage, CctpMessage, BURN_MESSAGE_LENGTH, SOLANA_DOMAIN};
+use crate::config::{CctpSettings, ChainConfig};
+use crate::protocols::wormhole::instruction_discriminator;
+use crate::protocols::{ProtocolAdapter, VerifiedInbox};
+use crate::types::{ChainId, CrossChainAddress, CrossChainMessage, MessageId, MessageType, CCIHSResult};
+use crate::CCIHSError;
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use parking_lot::Mutex;
+use solana_program::pubkey::Pubkey;
+use std::collections::HashSet;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Parameters of the TokenMessengerMinter `deposit_for_burn` instruction, or
+/// `deposit_for_burn_with_caller` when a destination caller is set.
+pub struct DepositForBurn {
+    pub amount: u64,
+    pub destination_domain: u32,
+    pub mint_recipient: [u8; 32],
+    pub burn_token: Pubkey,
+    pub destination_caller: Option<[u8; 32]>,
+}
+
+impl DepositForBurn {
+    /// Anchor instruction data for the TokenMessengerMinter program.
+    pub fn instruction_data(&self) -> Vec<u8> {
+        let name = match self.destination_caller {
+            None => "deposit_for_burn",
+            Some(_) => "deposit_for_burn_with_caller",
+        };
+        let mut args = Vec::new();
+        self.amount.serialize(&mut args).expect("writing to a Vec does not fail");
+        self.destination_domain.serialize(&mut args).expect("writing to a Vec does not fail");
+        args.extend_from_slice(&self.mint_recipient);
+        if let Some(destination_caller) = self.destination_caller {
+            args.extend_from_slice(&destination_caller);
+        }
+        [&instruction_discriminator(name)[..], &args].concat()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// What [`CctpAdapter::send_message`] asks the caller to submit.
+pub enum CctpOutbound {
+    DepositForBurn(DepositForBurn),
+    /// MessageTransmitter `send_message`, carrying the CCIHS payload since
+    /// burn messages have no room for one.
+    SendMessage {
+        destination_domain: u32,
+        recipient: [u8; 32],
+        message_body: Vec<u8>,
+    },
+}
+
+/// Moves native USDC over Circle's CCTP by burning it on the source domain
+/// and minting it on the destination domain.
+///
+/// The adapter does not talk to the chain itself. Sends are queued as
+/// [`CctpOutbound`] items for the caller to submit, and received messages
+/// are fed in with their Circle attestation through
+/// [`handle_attested_message`](Self::handle_attested_message).
+pub struct CctpAdapter {
+    settings: CctpSettings,
+    attesters: AttesterSet,
+    outbox: Mutex<Vec<CctpOutbound>>,
+    inbox: VerifiedInbox,
+    /// `(source_domain, nonce)` of every message accepted so far.
+    used_nonces: Mutex<HashSet<(u32, u64)>>,
+}
+
+impl CctpAdapter {
+    pub fn new(settings: CctpSettings) -> Self {
+        let attesters = AttesterSet::new(settings.attesters.clone(), settings.attestation_threshold);
+        Self {
+            settings,
+            attesters,
+            outbox: Mutex::new(Vec::new()),
+            inbox: VerifiedInbox::new(),
+            used_nonces: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn settings(&self) -> &CctpSettings {
+        &self.settings
+    }
+
+    /// Everything queued by `send_message` since the last call.
+    pub fn take_outbound(&self) -> Vec<CctpOutbound> {
+        std::mem::take(&mut *self.outbox.lock())
+    }
+
+    fn domain(&self, chain: ChainId) -> CCIHSResult<u32> {
+        if !self.settings.supported_chains.contains(&chain) {
+            return Err(CCIHSError::InvalidChainId);
+        }
+        domain_for_chain(chain).ok_or(CCIHSError::InvalidChainId)
+    }
+
+    /// Verifies a message relayed to Solana and its attestation, then queues
+    /// it for `receive_message`. Burn messages become token transfers of the
+    /// local USDC mint; any other body is a general message.
+    ///
+    /// CCTP nonces are 64 bits and CCIHS nonces 32, so the message gets the
+    /// low 32 bits. Replays are refused by the full nonce, which is also the
+    /// sequence of the message ID.
+    pub fn handle_attested_message(&self, message: &[u8], attestation: &[u8]) -> CCIHSResult<()> {
+        let message = CctpMessage::try_from_slice(message)
+            .map_err(|e| CCIHSError::DeserializationError(e.to_string()))?;
+        if message.destination_domain != SOLANA_DOMAIN {
+            return Err(CCIHSError::InvalidChainId);
+        }
+        self.attesters.verify(&message, attestation)?;
+
+        let source_chain = chain_for_domain(message.source_domain, &self.settings.supported_chains)
+            .ok_or(CCIHSError::InvalidChainId)?;
+        let nonce = message.nonce as u32;
+        if self.used_nonces.lock().contains(&(message.source_domain, message.nonce)) {
+            return Err(CCIHSError::MessageAlreadyProcessed);
+        }
+
+        let message_id = MessageId::compute(source_chain, &message.sender, message.nonce, &message.body);
+        let burn = match message.body.len() {
+            BURN_MESSAGE_LENGTH => BurnMessage::try_from_slice(&message.body).ok(),
+            _ => None,
+        };
+        let received = match burn {
+            Some(burn) => CrossChainMessage {
+                message_type: MessageType::TokenTransfer,
+                payload: Vec::new(),
+                amount: burn.amount,
+                token_address: Some(self.settings.usdc_mint),
+                sender: CrossChainAddress::from_bytes32(source_chain, &burn.message_sender)?,
+                recipient: CrossChainAddress::Solana(Pubkey::new_from_array(burn.mint_recipient)),
+                source_chain,
+                destination_chain: ChainId::SOLANA,
+                nonce,
+                timestamp: 0,
+                consistency_level: 0,
+                expires_at: None,
+                execute_after: None,
+                message_id: Some(message_id),
+            },
+            None => CrossChainMessage {
+                message_type: MessageType::General,
+                payload: message.body,
+                amount: 0,
+                token_address: None,
+                sender: CrossChainAddress::from_bytes32(source_chain, &message.sender)?,
+                recipient: CrossChainAddress::Solana(Pubkey::new_from_array(message.recipient)),
+                source_chain,
+                destination_chain: ChainId::SOLANA,
+                nonce,
+                timestamp: 0,
+                consistency_level: 0,
+                expires_at: None,
+                execute_after: None,
+                message_id: Some(message_id),
+            },
+        };
+        if !self.used_nonces.lock().insert((message.source_domain, message.nonce)) {
+            return Err(CCIHSError::MessageAlreadyProcessed);
+        }
+        self.inbox.push(received)
+    }
+}
+
+impl ProtocolAdapter for CctpAdapter {
+    /// Token transfers become a burn, general messages a MessageTransmitter
+    /// message. Burn messages carry no payload and two CCTP messages cannot
+    /// be tied together, so a transfer with a payload is refused; send the
+    /// payload as a general message of its own.
+    fn send_message(&self, message: &CrossChainMessage, _source_config: &ChainConfig, _destination_config: &ChainConfig) -> CCIHSResult<()> {
+        let destination_domain = self.domain(message.destination_chain)?;
+        let recipient = message.recipient.to_bytes32();
+
+        let outbound = match message.message_type {
+            MessageType::TokenTransfer => {
+                if message.amount == 0 {
+                    return Err(CCIHSError::MissingAmount);
+                }
+                if message.token_address.map_or(false, |token| token != self.settings.usdc_mint) {
+                    return Err(CCIHSError::UnsupportedToken);
+                }
+                if !message.payload.is_empty() {
+                    return Err(CCIHSError::UnsupportedOperation);
+                }
+                CctpOutbound::DepositForBurn(DepositForBurn {
+                    amount: message.amount,
+                    destination_domain,
+                    mint_recipient: recipient,
+                    burn_token: self.settings.usdc_mint,
+                    destination_caller: None,
+                })
+            }
+            MessageType::General if message.payload.is_empty() => return Err(CCIHSError::EmptyPayload),
+            MessageType::General => CctpOutbound::SendMessage {
+                destination_domain,
+                recipient,
+                message_body: message.payload.clone(),
+            },
+        };
+
+        self.outbox.lock().push(outbound);
+        Ok(())
+    }
+
+    fn receive_message(&self, source_config: &ChainConfig) -> CCIHSResult<CrossChainMessage> {
+        self.inbox.pop(source_config.chain_id)
+    }
+
+    fn verify_message(&self, message: &CrossChainMessage, _source_config: &ChainConfig, _destination_config: &ChainConfig) -> CCIHSResult<bool> {
+        self.inbox.is_verified(message)
+    }
+
+    fn supported_chains(&self) -> Vec<ChainId> {
+        self.settings.supported_chains.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::utility::ecdsa::testing::{address, secret_key, sign};
+
+    fn adapter() -> (CctpAdapter, libsecp256k1::SecretKey) {
+        let attester = secret_key(1);
+        let mut settings = CctpSettings::new(Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        settings.supported_chains = [ChainId::SOLANA, ChainId::ETHEREUM].into_iter().collect();
+        settings.attesters = vec![address(&attester)];
+        (CctpAdapter::new(settings), attester)
+    }
+
+    fn ethereum_message(nonce: u64) -> CctpMessage {
+        let mut sender = [0u8; 32];
+        sender[12..].fill(0x11);
+        CctpMessage {
+            version: 0,
+            source_domain: 0,
+            destination_domain: SOLANA_DOMAIN,
+            nonce,
+            sender,
+            recipient: Pubkey::new_unique().to_bytes(),
+            destination_caller: [0; 32],
+            body: b"hello".to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_verifies_only_received_messages() {
+        let (adapter, attester) = adapter();
+        let ethereum = ChainConfig::new(ChainId::ETHEREUM, "http://localhost:8545".to_string());
+        let solana = ChainConfig::new(ChainId::SOLANA, "http://localhost:8899".to_string());
+
+        // A sender that is not an EVM address fails without using up the
+        // nonce.
+        let mut message = ethereum_message(u32::MAX as u64 + 2);
+        message.sender = [0x11; 32];
+        let attestation = sign(&attester, &message.hash());
+        assert!(adapter.handle_attested_message(&message.try_to_vec().unwrap(), &attestation).is_err());
+
+        let message = ethereum_message(u32::MAX as u64 + 2);
+        let encoded = message.try_to_vec().unwrap();
+        let attestation = sign(&attester, &message.hash());
+        adapter.handle_attested_message(&encoded, &attestation).unwrap();
+        assert!(matches!(
+            adapter.handle_attested_message(&encoded, &attestation),
+            Err(CCIHSError::MessageAlreadyProcessed)
+        ));
+
+        let received = adapter.receive_message(&ethereum).unwrap();
+        assert_eq!(received.payload, b"hello");
+        assert_eq!(received.nonce, 1);
+        // Keyed on the full nonce, not the truncated one.
+        assert_eq!(received.id(), MessageId::compute(ChainId::ETHEREUM, &message.sender, message.nonce, b"hello"));
+        assert!(adapter.verify_message(&received, &ethereum, &solana).unwrap());
+
+        let mut forged = received.clone();
+        forged.payload = b"forged".to_vec();
+        assert!(!adapter.verify_message(&forged, &ethereum, &solana).unwrap());
+    }
+
+    #[test]
+    fn test_rejects_transfers_with_payload() {
+        let (adapter, _) = adapter();
+        let ethereum = ChainConfig::new(ChainId::ETHEREUM, "http://localhost:8545".to_string());
+        let solana = ChainConfig::new(ChainId::SOLANA, "http://localhost:8899".to_string());
+        let mut transfer = CrossChainMessage {
+            message_type: MessageType::TokenTransfer,
+            payload: b"memo".to_vec(),
+            amount: 1_000_000,
+            token_address: Some(adapter.settings().usdc_mint),
+            sender: CrossChainAddress::Solana(Pubkey::new_unique()),
+            recipient: CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(0x22)),
+            source_chain: ChainId::SOLANA,
+            destination_chain: ChainId::ETHEREUM,
+            nonce: 0,
+            timestamp: 0,
+            consistency_level: 0,
+            expires_at: None,
+            execute_after: None,
+            message_id: None,
+        };
+        assert!(matches!(
+            adapter.send_message(&transfer, &solana, &ethereum),
+            Err(CCIHSError::UnsupportedOperation)
+        ));
+        assert!(adapter.take_outbound().is_empty());
+
+        transfer.payload.clear();
+        adapter.send_message(&transfer, &solana, &ethereum).unwrap();
+        assert!(matches!(adapter.take_outbound().as_slice(), [CctpOutbound::DepositForBurn(_)]));
+    }
+}
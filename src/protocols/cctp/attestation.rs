@@ -0,0 +1,94 @@
+// protocols/cctp/attestation.rs
+
+use super::CctpMessage;
+use crate::utility::ecdsa::{recover_address, EvmAddress, SIGNATURE_LENGTH};
+use crate::{CCIHSError, CCIHSResult};
+
+#[derive(Clone, Debug, PartialEq)]
+/// Attesters enabled on the MessageTransmitter and how many of them must
+/// sign a message.
+pub struct AttesterSet {
+    pub attesters: Vec<EvmAddress>,
+    pub threshold: usize,
+}
+
+impl AttesterSet {
+    pub fn new(attesters: Vec<EvmAddress>, threshold: usize) -> Self {
+        Self { attesters, threshold }
+    }
+
+    /// Checks an attestation the way the MessageTransmitter does: exactly
+    /// `threshold` signatures over the message hash, from enabled attesters,
+    /// ordered by strictly increasing attester address (which also rules out
+    /// duplicates).
+    pub fn verify(&self, message: &CctpMessage, attestation: &[u8]) -> CCIHSResult<()> {
+        let invalid = |reason: String| CCIHSError::InvalidAttestation(reason);
+
+        if self.threshold == 0 {
+            return Err(invalid("attestation threshold is zero".to_string()));
+        }
+        if attestation.len() != self.threshold * SIGNATURE_LENGTH {
+            return Err(invalid(format!(
+                "expected {} signatures, got {} bytes",
+                self.threshold,
+                attestation.len()
+            )));
+        }
+
+        let digest = message.hash();
+        let mut previous: Option<EvmAddress> = None;
+        for (index, signature) in attestation.chunks_exact(SIGNATURE_LENGTH).enumerate() {
+            let signature: &[u8; SIGNATURE_LENGTH] = signature.try_into().expect("chunk is a signature");
+            let signer = recover_address(&digest, signature)?;
+            if previous.map_or(false, |previous| signer <= previous) {
+                return Err(invalid(format!("signature {} is out of order or a duplicate", index)));
+            }
+            if !self.attesters.contains(&signer) {
+                return Err(invalid(format!("signature {} is not from an enabled attester", index)));
+            }
+            previous = Some(signer);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::utility::ecdsa::testing::{address, secret_key, sign};
+
+    fn message() -> CctpMessage {
+        CctpMessage {
+            version: 0,
+            source_domain: 0,
+            destination_domain: 5,
+            nonce: 7,
+            sender: [1; 32],
+            recipient: [2; 32],
+            destination_caller: [0; 32],
+            body: b"body".to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_verifies_locally_signed_attestations() {
+        let mut keys: Vec<_> = (1..=3).map(secret_key).collect();
+        keys.sort_by_key(address);
+        let set = AttesterSet::new(keys.iter().map(address).collect(), 2);
+        let message = message();
+        let digest = message.hash();
+
+        let attestation = [sign(&keys[0], &digest), sign(&keys[2], &digest)].concat();
+        set.verify(&message, &attestation).unwrap();
+
+        let reversed = [sign(&keys[2], &digest), sign(&keys[0], &digest)].concat();
+        assert!(set.verify(&message, &reversed).is_err());
+
+        let outsider = [sign(&keys[0], &digest), sign(&secret_key(9), &digest)].concat();
+        assert!(set.verify(&message, &outsider).is_err());
+
+        let mut tampered = message.clone();
+        tampered.nonce += 1;
+        assert!(set.verify(&tampered, &attestation).is_err());
+    }
+}
@@ -0,0 +1,33 @@
+// protocols/cctp/domain.rs
+
+use crate::types::ChainId;
+
+/// CCTP domain of Solana, the domain this adapter runs on.
+pub const SOLANA_DOMAIN: u32 = 5;
+
+/// CCTP domain of a chain, by Wormhole chain ID. Testnets share the domain of
+/// their mainnet.
+pub fn domain_for_chain(chain: ChainId) -> Option<u32> {
+    match chain.0 {
+        2 | 10002 => Some(0),  // Ethereum, Sepolia
+        6 => Some(1),          // Avalanche, Fuji
+        24 | 10005 => Some(2), // Optimism, Optimism Sepolia
+        23 | 10003 => Some(3), // Arbitrum, Arbitrum Sepolia
+        1 => Some(SOLANA_DOMAIN),
+        30 | 10004 => Some(6), // Base, Base Sepolia
+        5 => Some(7),          // Polygon PoS
+        _ => None,
+    }
+}
+
+/// The chain among `candidates` that uses `domain`. Domains do not tell
+/// mainnet and testnet apart, so the caller passes the chains it serves.
+pub fn chain_for_domain<'a, I>(domain: u32, candidates: I) -> Option<ChainId>
+where
+    I: IntoIterator<Item = &'a ChainId>,
+{
+    candidates
+        .into_iter()
+        .find(|chain| domain_for_chain(**chain) == Some(domain))
+        .copied()
+}
@@ -0,0 +1,135 @@
+// protocols/cctp/message.rs
+
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use solana_program::keccak;
+use crate::utility::encoding::{read_bytes32, read_u32};
+use std::io;
+use std::io::Read;
+
+/// Version of the message format relayed by the MessageTransmitter.
+pub const CCTP_MESSAGE_VERSION: u32 = 0;
+/// Version of the burn message format of the TokenMessenger.
+pub const BURN_MESSAGE_VERSION: u32 = 0;
+/// Length of an encoded [`BurnMessage`].
+pub const BURN_MESSAGE_LENGTH: usize = 132;
+
+#[derive(Clone, Debug, PartialEq)]
+/// A message relayed by the CCTP MessageTransmitter. Encoded with every
+/// integer big endian and no length prefix on the body:
+/// `version: u32 | source_domain: u32 | destination_domain: u32 | nonce: u64 | sender | recipient | destination_caller | body`
+pub struct CctpMessage {
+    pub version: u32,
+    pub source_domain: u32,
+    pub destination_domain: u32,
+    pub nonce: u64,
+    pub sender: [u8; 32],
+    pub recipient: [u8; 32],
+    /// Only this address may receive the message on the destination
+    /// domain. All zeroes lets anyone receive it.
+    pub destination_caller: [u8; 32],
+    pub body: Vec<u8>,
+}
+
+impl CctpMessage {
+    /// Keccak256 of the encoded message, the digest attesters sign.
+    pub fn hash(&self) -> [u8; 32] {
+        let mut encoded = Vec::new();
+        self.serialize(&mut encoded).expect("writing to a Vec does not fail");
+        keccak::hash(&encoded).to_bytes()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Body of a [`CctpMessage`] sent by the TokenMessenger for
+/// `depositForBurn`. The amount is a uint256 on the wire.
+pub struct BurnMessage {
+    pub version: u32,
+    pub burn_token: [u8; 32],
+    pub mint_recipient: [u8; 32],
+    pub amount: u64,
+    pub message_sender: [u8; 32],
+}
+
+impl AnchorSerialize for CctpMessage {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.version.to_be_bytes())?;
+        writer.write_all(&self.source_domain.to_be_bytes())?;
+        writer.write_all(&self.destination_domain.to_be_bytes())?;
+        writer.write_all(&self.nonce.to_be_bytes())?;
+        writer.write_all(&self.sender)?;
+        writer.write_all(&self.recipient)?;
+        writer.write_all(&self.destination_caller)?;
+        writer.write_all(&self.body)
+    }
+}
+
+impl AnchorDeserialize for CctpMessage {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        Self::deserialize_reader(buf)
+    }
+
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let version = read_u32(reader)?;
+        if version != CCTP_MESSAGE_VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "unsupported message version"));
+        }
+        let source_domain = read_u32(reader)?;
+        let destination_domain = read_u32(reader)?;
+        let mut nonce = [0u8; 8];
+        reader.read_exact(&mut nonce)?;
+        let sender = read_bytes32(reader)?;
+        let recipient = read_bytes32(reader)?;
+        let destination_caller = read_bytes32(reader)?;
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)?;
+        Ok(Self {
+            version,
+            source_domain,
+            destination_domain,
+            nonce: u64::from_be_bytes(nonce),
+            sender,
+            recipient,
+            destination_caller,
+            body,
+        })
+    }
+}
+
+impl AnchorSerialize for BurnMessage {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.version.to_be_bytes())?;
+        writer.write_all(&self.burn_token)?;
+        writer.write_all(&self.mint_recipient)?;
+        writer.write_all(&[0u8; 24])?;
+        writer.write_all(&self.amount.to_be_bytes())?;
+        writer.write_all(&self.message_sender)
+    }
+}
+
+impl AnchorDeserialize for BurnMessage {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        Self::deserialize_reader(buf)
+    }
+
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let version = read_u32(reader)?;
+        if version != BURN_MESSAGE_VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "unsupported burn message version"));
+        }
+        let burn_token = read_bytes32(reader)?;
+        let mint_recipient = read_bytes32(reader)?;
+        let amount = read_bytes32(reader)?;
+        if amount[..24].iter().any(|&byte| byte != 0) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "amount does not fit in a u64"));
+        }
+        let mut amount_low = [0u8; 8];
+        amount_low.copy_from_slice(&amount[24..]);
+        Ok(Self {
+            version,
+            burn_token,
+            mint_recipient,
+            amount: u64::from_be_bytes(amount_low),
+            message_sender: read_bytes32(reader)?,
+        })
+    }
+}
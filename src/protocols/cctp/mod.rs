@@ -0,0 +1,9 @@
+mod adapter;
+mod attestation;
+mod domain;
+mod message;
+
+pub use adapter::{CctpAdapter, CctpOutbound, DepositForBurn};
+pub use attestation::AttesterSet;
+pub use domain::{chain_for_domain, domain_for_chain, SOLANA_DOMAIN};
+pub use message::{BurnMessage, CctpMessage, BURN_MESSAGE_LENGTH, BURN_MESSAGE_VERSION, CCTP_MESSAGE_VERSION};
@@ -0,0 +1,65 @@
+// protocols/fake.rs
+
+use super::{ProtocolAdapter, VerifiedInbox};
+use crate::config::ChainConfig;
+use crate::types::{ChainId, CrossChainMessage, CCIHSResult};
+use crate::CCIHSError;
+use parking_lot::Mutex;
+
+/// In-memory [`ProtocolAdapter`]. Messages handed to [`deliver`](Self::deliver)
+/// are received as verified; sent messages are recorded.
+pub struct FakeAdapter {
+    supported_chains: Vec<ChainId>,
+    inbox: VerifiedInbox,
+    sent: Mutex<Vec<CrossChainMessage>>,
+    failure: Mutex<Option<String>>,
+}
+
+impl FakeAdapter {
+    pub fn new(supported_chains: Vec<ChainId>) -> Self {
+        Self {
+            supported_chains,
+            inbox: VerifiedInbox::new(),
+            sent: Mutex::new(Vec::new()),
+            failure: Mutex::new(None),
+        }
+    }
+
+    /// Queues `message` for `receive_message`, as if the transport had
+    /// verified it.
+    pub fn deliver(&self, message: CrossChainMessage) -> CCIHSResult<()> {
+        self.inbox.push(message)
+    }
+
+    /// Messages passed to `send_message`, in order.
+    pub fn sent_messages(&self) -> Vec<CrossChainMessage> {
+        self.sent.lock().clone()
+    }
+
+    /// Makes `send_message` fail with `reason` until cleared with `None`.
+    pub fn fail_sends(&self, reason: Option<String>) {
+        *self.failure.lock() = reason;
+    }
+}
+
+impl ProtocolAdapter for FakeAdapter {
+    fn send_message(&self, message: &CrossChainMessage, _source_config: &ChainConfig, _destination_config: &ChainConfig) -> CCIHSResult<()> {
+        if let Some(reason) = &*self.failure.lock() {
+            return Err(CCIHSError::NetworkError(reason.clone()));
+        }
+        self.sent.lock().push(message.clone());
+        Ok(())
+    }
+
+    fn receive_message(&self, source_config: &ChainConfig) -> CCIHSResult<CrossChainMessage> {
+        self.inbox.pop(source_config.chain_id)
+    }
+
+    fn verify_message(&self, message: &CrossChainMessage, _source_config: &ChainConfig, _destination_config: &ChainConfig) -> CCIHSResult<bool> {
+        self.inbox.is_verified(message)
+    }
+
+    fn supported_chains(&self) -> Vec<ChainId> {
+        self.supported_chains.clone()
+    }
+}
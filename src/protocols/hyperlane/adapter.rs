@@ -0,0 +1,184 @@
+// protocols/hyperlane/adapter.rs
+
+use super::{
+    chain_for_domain, domain_for_chain, HyperlaneMessage, InterchainSecurityModule, MailboxInstruction,
+    MessageIdMultisigIsm, RoutingIsm, HYPERLANE_MESSAGE_VERSION,
+};
+use crate::config::{ChainConfig, HyperlaneSettings};
+use crate::protocols::{ProtocolAdapter, VerifiedInbox};
+use crate::types::{ChainId, CrossChainAddress, CrossChainMessage, MessageId, MessageType, CCIHSResult};
+use crate::CCIHSError;
+use anchor_lang::AnchorDeserialize;
+use parking_lot::Mutex;
+use solana_program::pubkey::Pubkey;
+use std::collections::HashSet;
+
+/// Sends and receives general messages through a Hyperlane mailbox.
+///
+/// Like the CCTP adapter it does not talk to the chain. Dispatches are
+/// queued as [`MailboxInstruction`]s for the caller to submit, and inbound
+/// messages are fed in with their ISM metadata through
+/// [`handle_process`](Self::handle_process). Which security model applies is
+/// up to the [`InterchainSecurityModule`] the adapter is built with.
+pub struct HyperlaneAdapter {
+    settings: HyperlaneSettings,
+    ism: Box<dyn InterchainSecurityModule>,
+    /// Nonce the mailbox will give the next dispatch.
+    next_nonce: Mutex<u32>,
+    outbox: Mutex<Vec<MailboxInstruction>>,
+    inbox: VerifiedInbox,
+    /// IDs of every message accepted so far.
+    delivered: Mutex<HashSet<[u8; 32]>>,
+}
+
+impl HyperlaneAdapter {
+    pub fn new(settings: HyperlaneSettings, ism: Box<dyn InterchainSecurityModule>) -> Self {
+        Self {
+            settings,
+            ism,
+            next_nonce: Mutex::new(0),
+            outbox: Mutex::new(Vec::new()),
+            inbox: VerifiedInbox::new(),
+            delivered: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Verifies messages with a message ID multisig per origin domain, using
+    /// the validator sets of the settings. Origins without a validator set
+    /// are rejected.
+    pub fn from_settings(settings: HyperlaneSettings) -> Self {
+        let ism = settings
+            .validator_sets
+            .iter()
+            .fold(RoutingIsm::new(), |ism, (origin, set)| {
+                ism.route(*origin, Box::new(MessageIdMultisigIsm::new(set.validators.clone(), set.threshold)))
+            });
+        Self::new(settings, Box::new(ism))
+    }
+
+    pub fn settings(&self) -> &HyperlaneSettings {
+        &self.settings
+    }
+
+    /// Syncs the dispatch nonce with the mailbox, so [`dispatch`](Self::dispatch)
+    /// returns the IDs the mailbox will assign.
+    pub fn set_outbound_nonce(&self, nonce: u32) {
+        *self.next_nonce.lock() = nonce;
+    }
+
+    /// Everything queued by `send_message` and `handle_process` since the
+    /// last call.
+    pub fn take_outbound(&self) -> Vec<MailboxInstruction> {
+        std::mem::take(&mut *self.outbox.lock())
+    }
+
+    fn domain(&self, chain: ChainId) -> CCIHSResult<u32> {
+        if !self.settings.supported_chains.contains(&chain) {
+            return Err(CCIHSError::InvalidChainId);
+        }
+        if chain == ChainId::SOLANA {
+            return Ok(self.settings.local_domain);
+        }
+        domain_for_chain(chain).ok_or(CCIHSError::InvalidChainId)
+    }
+
+    /// Queues the mailbox dispatch of `message` and returns the ID of the
+    /// Hyperlane message it produces.
+    pub fn dispatch(&self, message: &CrossChainMessage) -> CCIHSResult<[u8; 32]> {
+        if message.message_type != MessageType::General {
+            return Err(CCIHSError::UnsupportedOperation);
+        }
+        if message.payload.is_empty() {
+            return Err(CCIHSError::EmptyPayload);
+        }
+        let sender = match message.sender {
+            CrossChainAddress::Solana(sender) => sender,
+            _ => return Err(CCIHSError::InvalidAddress),
+        };
+        let destination = self.domain(message.destination_chain)?;
+        let recipient = message.recipient.to_bytes32();
+
+        let mut next_nonce = self.next_nonce.lock();
+        let dispatched = HyperlaneMessage {
+            version: HYPERLANE_MESSAGE_VERSION,
+            nonce: *next_nonce,
+            origin: self.settings.local_domain,
+            sender: sender.to_bytes(),
+            destination,
+            recipient,
+            body: message.payload.clone(),
+        };
+        *next_nonce = next_nonce.checked_add(1).ok_or(CCIHSError::InvalidNonce)?;
+
+        self.outbox.lock().push(MailboxInstruction::OutboxDispatch {
+            sender,
+            destination_domain: destination,
+            recipient,
+            message_body: message.payload.clone(),
+        });
+        Ok(dispatched.id())
+    }
+
+    /// Verifies an inbound message with the ISM, then queues it for
+    /// `receive_message` and its `InboxProcess` instruction for submission.
+    /// Returns the message ID.
+    pub fn handle_process(&self, metadata: &[u8], message: &[u8]) -> CCIHSResult<[u8; 32]> {
+        let parsed = HyperlaneMessage::try_from_slice(message)
+            .map_err(|e| CCIHSError::DeserializationError(e.to_string()))?;
+        if parsed.destination != self.settings.local_domain {
+            return Err(CCIHSError::InvalidChainId);
+        }
+        let source_chain = chain_for_domain(parsed.origin)
+            .filter(|chain| self.settings.supported_chains.contains(chain))
+            .ok_or(CCIHSError::InvalidChainId)?;
+        self.ism.verify(metadata, &parsed)?;
+
+        let id = parsed.id();
+        let sender = CrossChainAddress::from_bytes32(source_chain, &parsed.sender)?;
+        if !self.delivered.lock().insert(id) {
+            return Err(CCIHSError::MessageAlreadyProcessed);
+        }
+
+        self.outbox.lock().push(MailboxInstruction::InboxProcess {
+            metadata: metadata.to_vec(),
+            message: message.to_vec(),
+        });
+        // The mailbox nonce is unique per origin, like a Wormhole sequence.
+        let message_id = MessageId::compute(source_chain, &parsed.sender, parsed.nonce as u64, &parsed.body);
+        self.inbox.push(CrossChainMessage {
+            message_type: MessageType::General,
+            payload: parsed.body,
+            amount: 0,
+            token_address: None,
+            sender,
+            recipient: CrossChainAddress::Solana(Pubkey::new_from_array(parsed.recipient)),
+            source_chain,
+            destination_chain: ChainId::SOLANA,
+            nonce: parsed.nonce,
+            timestamp: 0,
+            consistency_level: 0,
+            expires_at: None,
+            execute_after: None,
+            message_id: Some(message_id),
+        })?;
+        Ok(id)
+    }
+}
+
+impl ProtocolAdapter for HyperlaneAdapter {
+    fn send_message(&self, message: &CrossChainMessage, _source_config: &ChainConfig, _destination_config: &ChainConfig) -> CCIHSResult<()> {
+        self.dispatch(message).map(|_| ())
+    }
+
+    fn receive_message(&self, source_config: &ChainConfig) -> CCIHSResult<CrossChainMessage> {
+        self.inbox.pop(source_config.chain_id)
+    }
+
+    fn verify_message(&self, message: &CrossChainMessage, _source_config: &ChainConfig, _destination_config: &ChainConfig) -> CCIHSResult<bool> {
+        self.inbox.is_verified(message)
+    }
+
+    fn supported_chains(&self) -> Vec<ChainId> {
+        self.settings.supported_chains.iter().cloned().collect()
+    }
+}
@@ -0,0 +1,39 @@
+// protocols/hyperlane/domain.rs
+
+use crate::types::ChainId;
+
+/// Hyperlane domain of Solana mainnet.
+pub const SOLANA_MAINNET_DOMAIN: u32 = 1_399_811_149;
+/// Hyperlane domain of Solana testnet.
+pub const SOLANA_TESTNET_DOMAIN: u32 = 1_399_811_150;
+
+/// Hyperlane domain of a chain, by Wormhole chain ID. EVM domains are the
+/// EVM chain ID. Solana is left out since mainnet and testnet share a
+/// Wormhole chain ID; the adapter uses the configured local domain instead.
+pub fn domain_for_chain(chain: ChainId) -> Option<u32> {
+    match chain.0 {
+        2 => Some(1),              // Ethereum
+        4 => Some(56),             // BSC
+        5 => Some(137),            // Polygon
+        6 => Some(43_114),         // Avalanche
+        10 => Some(250),           // Fantom
+        14 => Some(42_220),        // Celo
+        16 => Some(1_284),         // Moonbeam
+        23 => Some(42_161),        // Arbitrum
+        24 => Some(10),            // Optimism
+        30 => Some(8_453),         // Base
+        10002 => Some(11_155_111), // Sepolia
+        10003 => Some(421_614),    // Arbitrum Sepolia
+        10004 => Some(84_532),     // Base Sepolia
+        10005 => Some(11_155_420), // Optimism Sepolia
+        _ => None,
+    }
+}
+
+/// The chain using `domain`, the inverse of [`domain_for_chain`].
+pub fn chain_for_domain(domain: u32) -> Option<ChainId> {
+    [2, 4, 5, 6, 10, 14, 16, 23, 24, 30, 10002, 10003, 10004, 10005]
+        .into_iter()
+        .map(ChainId)
+        .find(|chain| domain_for_chain(*chain) == Some(domain))
+}
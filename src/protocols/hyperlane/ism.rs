@@ -0,0 +1,257 @@
+// protocols/hyperlane/ism.rs
+
+//! Interchain security modules (ISMs) decide whether an inbound Hyperlane
+//! message may be delivered, based on metadata the relayer supplies with it.
+//! Modules compose: a [`RoutingIsm`] picks a module by origin domain and an
+//! [`AggregationIsm`] requires several modules to agree, so each route can
+//! have its own security model.
+
+use super::HyperlaneMessage;
+use crate::utility::ecdsa::{eth_signed_message_hash, recover_address, EvmAddress, SIGNATURE_LENGTH};
+use crate::{CCIHSError, CCIHSResult};
+use solana_program::keccak;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Module types as reported by Hyperlane's `moduleType()`.
+pub enum ModuleType {
+    Routing = 1,
+    Aggregation = 2,
+    MessageIdMultisig = 5,
+}
+
+pub trait InterchainSecurityModule: Send + Sync {
+    fn module_type(&self) -> ModuleType;
+
+    /// Succeeds when `metadata` proves `message` may be delivered.
+    fn verify(&self, metadata: &[u8], message: &HyperlaneMessage) -> CCIHSResult<()>;
+}
+
+fn rejected(reason: String) -> CCIHSError {
+    CCIHSError::InvalidAttestation(reason)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// `threshold` of `validators` must have signed a checkpoint committing to
+/// the message ID.
+///
+/// Metadata: `origin_merkle_tree_hook: [u8; 32] | root: [u8; 32] | index: u32 | signatures`,
+/// with signatures ordered like the validators that made them.
+pub struct MessageIdMultisigIsm {
+    pub validators: Vec<EvmAddress>,
+    pub threshold: usize,
+}
+
+impl MessageIdMultisigIsm {
+    /// Offset of the signatures in the metadata.
+    const SIGNATURES_OFFSET: usize = 68;
+
+    pub fn new(validators: Vec<EvmAddress>, threshold: usize) -> Self {
+        Self { validators, threshold }
+    }
+
+    /// The digest validators sign for a checkpoint of the origin merkle tree
+    /// hook that includes `message_id`.
+    pub fn checkpoint_digest(
+        origin: u32,
+        merkle_tree_hook: &[u8; 32],
+        root: &[u8; 32],
+        index: u32,
+        message_id: &[u8; 32],
+    ) -> [u8; 32] {
+        let domain_hash = keccak::hashv(&[&origin.to_be_bytes(), merkle_tree_hook, b"HYPERLANE"]).to_bytes();
+        let checkpoint = keccak::hashv(&[&domain_hash, root, &index.to_be_bytes(), message_id]).to_bytes();
+        eth_signed_message_hash(&checkpoint)
+    }
+}
+
+impl InterchainSecurityModule for MessageIdMultisigIsm {
+    fn module_type(&self) -> ModuleType {
+        ModuleType::MessageIdMultisig
+    }
+
+    fn verify(&self, metadata: &[u8], message: &HyperlaneMessage) -> CCIHSResult<()> {
+        if self.threshold == 0 {
+            return Err(rejected("multisig threshold is zero".to_string()));
+        }
+        let signatures = metadata
+            .get(Self::SIGNATURES_OFFSET..)
+            .filter(|signatures| signatures.len() >= self.threshold * SIGNATURE_LENGTH)
+            .ok_or_else(|| rejected(format!("metadata does not hold {} signatures", self.threshold)))?;
+
+        let merkle_tree_hook: &[u8; 32] = metadata[..32].try_into().expect("checked length");
+        let root: &[u8; 32] = metadata[32..64].try_into().expect("checked length");
+        let index = u32::from_be_bytes(metadata[64..68].try_into().expect("checked length"));
+        let digest = Self::checkpoint_digest(message.origin, merkle_tree_hook, root, index, &message.id());
+
+        // Like the Hyperlane contracts: each signer must come later in the
+        // validator set than the previous one.
+        let mut validators = self.validators.iter();
+        for (position, signature) in signatures.chunks_exact(SIGNATURE_LENGTH).take(self.threshold).enumerate() {
+            let signature: &[u8; SIGNATURE_LENGTH] = signature.try_into().expect("chunk is a signature");
+            let signer = recover_address(&digest, signature)?;
+            if !validators.any(|validator| *validator == signer) {
+                return Err(rejected(format!(
+                    "signature {} is not from a validator, or is out of order",
+                    position
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Verifies each message with the module configured for its origin domain.
+/// The metadata is passed through unchanged.
+#[derive(Default)]
+pub struct RoutingIsm {
+    routes: HashMap<u32, Box<dyn InterchainSecurityModule>>,
+    default: Option<Box<dyn InterchainSecurityModule>>,
+}
+
+impl RoutingIsm {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn route(mut self, origin: u32, module: Box<dyn InterchainSecurityModule>) -> Self {
+        self.routes.insert(origin, module);
+        self
+    }
+
+    /// Module for origins without a route. Without one such messages are
+    /// rejected.
+    pub fn default_module(mut self, module: Box<dyn InterchainSecurityModule>) -> Self {
+        self.default = Some(module);
+        self
+    }
+}
+
+impl InterchainSecurityModule for RoutingIsm {
+    fn module_type(&self) -> ModuleType {
+        ModuleType::Routing
+    }
+
+    fn verify(&self, metadata: &[u8], message: &HyperlaneMessage) -> CCIHSResult<()> {
+        self.routes
+            .get(&message.origin)
+            .or(self.default.as_ref())
+            .ok_or_else(|| rejected(format!("no security module for origin domain {}", message.origin)))?
+            .verify(metadata, message)
+    }
+}
+
+/// At least `threshold` of `modules` must verify the message.
+///
+/// Metadata starts with a `start: u32 | end: u32` range per module, giving
+/// that module's metadata within the whole. A start of zero means no
+/// metadata for the module, which then counts as not verified. Every module
+/// given metadata must verify.
+pub struct AggregationIsm {
+    modules: Vec<Box<dyn InterchainSecurityModule>>,
+    threshold: usize,
+}
+
+impl AggregationIsm {
+    pub fn new(modules: Vec<Box<dyn InterchainSecurityModule>>, threshold: usize) -> Self {
+        Self { modules, threshold }
+    }
+
+    fn module_metadata<'a>(metadata: &'a [u8], index: usize) -> CCIHSResult<Option<&'a [u8]>> {
+        let read = |offset: usize| {
+            metadata
+                .get(offset..offset + 4)
+                .map(|bytes| u32::from_be_bytes(bytes.try_into().expect("four bytes")) as usize)
+                .ok_or_else(|| rejected(format!("metadata has no range for module {}", index)))
+        };
+        let start = read(index * 8)?;
+        if start == 0 {
+            return Ok(None);
+        }
+        let end = read(index * 8 + 4)?;
+        metadata
+            .get(start..end)
+            .map(Some)
+            .ok_or_else(|| rejected(format!("metadata range of module {} is out of bounds", index)))
+    }
+}
+
+impl InterchainSecurityModule for AggregationIsm {
+    fn module_type(&self) -> ModuleType {
+        ModuleType::Aggregation
+    }
+
+    fn verify(&self, metadata: &[u8], message: &HyperlaneMessage) -> CCIHSResult<()> {
+        if self.threshold == 0 {
+            return Err(rejected("aggregation threshold is zero".to_string()));
+        }
+        let mut verified = 0;
+        for (index, module) in self.modules.iter().enumerate() {
+            if let Some(module_metadata) = Self::module_metadata(metadata, index)? {
+                module.verify(module_metadata, message)?;
+                verified += 1;
+            }
+        }
+        if verified < self.threshold {
+            return Err(rejected(format!(
+                "{} of {} required modules verified the message",
+                verified, self.threshold
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utility::ecdsa::testing::{address, secret_key, sign};
+
+    fn message_from(origin: u32) -> HyperlaneMessage {
+        HyperlaneMessage {
+            version: 3,
+            nonce: 4,
+            origin,
+            sender: [1; 32],
+            destination: 1_399_811_149,
+            recipient: [2; 32],
+            body: b"hello".to_vec(),
+        }
+    }
+
+    fn multisig_metadata(message: &HyperlaneMessage, keys: &[&libsecp256k1::SecretKey]) -> Vec<u8> {
+        let (hook, root, index) = ([7; 32], [8; 32], 4u32);
+        let digest = MessageIdMultisigIsm::checkpoint_digest(message.origin, &hook, &root, index, &message.id());
+        let mut metadata = [&hook[..], &root, &index.to_be_bytes()].concat();
+        for key in keys {
+            metadata.extend_from_slice(&sign(key, &digest));
+        }
+        metadata
+    }
+
+    #[test]
+    fn routes_to_multisig_per_origin() {
+        let keys: Vec<_> = (1..=3).map(secret_key).collect();
+        let multisig = MessageIdMultisigIsm::new(keys.iter().map(address).collect(), 2);
+        let ism = RoutingIsm::new().route(1, Box::new(multisig));
+
+        let message = message_from(1);
+        ism.verify(&multisig_metadata(&message, &[&keys[0], &keys[2]]), &message).unwrap();
+        assert!(ism.verify(&multisig_metadata(&message, &[&keys[2], &keys[0]]), &message).is_err());
+        assert!(ism.verify(&multisig_metadata(&message, &[&keys[0], &secret_key(9)]), &message).is_err());
+
+        let unrouted = message_from(10);
+        assert!(ism.verify(&multisig_metadata(&unrouted, &[&keys[0], &keys[1]]), &unrouted).is_err());
+
+        let aggregation = AggregationIsm::new(
+            vec![Box::new(MessageIdMultisigIsm::new(keys.iter().map(address).collect(), 1))],
+            1,
+        );
+        let inner = multisig_metadata(&message, &[&keys[1]]);
+        let mut metadata = 8u32.to_be_bytes().to_vec();
+        metadata.extend_from_slice(&(8 + inner.len() as u32).to_be_bytes());
+        metadata.extend_from_slice(&inner);
+        aggregation.verify(&metadata, &message).unwrap();
+        assert!(aggregation.verify(&[0; 8], &message).is_err());
+    }
+}
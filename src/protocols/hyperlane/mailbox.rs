@@ -0,0 +1,55 @@
+// protocols/hyperlane/mailbox.rs
+
+use anchor_lang::AnchorSerialize;
+use solana_program::pubkey::Pubkey;
+
+/// Borsh variant index of `InboxProcess` in the Sealevel mailbox's
+/// instruction enum.
+const INBOX_PROCESS: u8 = 1;
+/// Borsh variant index of `OutboxDispatch`.
+const OUTBOX_DISPATCH: u8 = 4;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Instructions of the Hyperlane mailbox program on Solana that the adapter
+/// asks the caller to submit.
+pub enum MailboxInstruction {
+    /// Dispatches `message_body` to `recipient` on `destination_domain`.
+    OutboxDispatch {
+        sender: Pubkey,
+        destination_domain: u32,
+        recipient: [u8; 32],
+        message_body: Vec<u8>,
+    },
+    /// Delivers an inbound message with the metadata its ISM checks.
+    InboxProcess {
+        metadata: Vec<u8>,
+        message: Vec<u8>,
+    },
+}
+
+impl MailboxInstruction {
+    /// Borsh encoded instruction data for the mailbox program.
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        match self {
+            MailboxInstruction::OutboxDispatch {
+                sender,
+                destination_domain,
+                recipient,
+                message_body,
+            } => {
+                data.push(OUTBOX_DISPATCH);
+                data.extend_from_slice(sender.as_ref());
+                data.extend_from_slice(&destination_domain.to_le_bytes());
+                data.extend_from_slice(recipient);
+                message_body.serialize(&mut data).expect("writing to a Vec does not fail");
+            }
+            MailboxInstruction::InboxProcess { metadata, message } => {
+                data.push(INBOX_PROCESS);
+                metadata.serialize(&mut data).expect("writing to a Vec does not fail");
+                message.serialize(&mut data).expect("writing to a Vec does not fail");
+            }
+        }
+        data
+    }
+}
@@ -0,0 +1,77 @@
+// protocols/hyperlane/message.rs
+
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use solana_program::keccak;
+use crate::utility::encoding::{read_bytes32, read_u32};
+use std::io;
+use std::io::Read;
+
+/// Version of the message format dispatched by the mailbox.
+pub const HYPERLANE_MESSAGE_VERSION: u8 = 3;
+
+#[derive(Clone, Debug, PartialEq)]
+/// A message dispatched by a Hyperlane mailbox. Encoded packed, integers big
+/// endian and no length prefix on the body:
+/// `version: u8 | nonce: u32 | origin: u32 | sender | destination: u32 | recipient | body`
+pub struct HyperlaneMessage {
+    pub version: u8,
+    /// Dispatch count of the origin mailbox.
+    pub nonce: u32,
+    pub origin: u32,
+    pub sender: [u8; 32],
+    pub destination: u32,
+    pub recipient: [u8; 32],
+    pub body: Vec<u8>,
+}
+
+impl HyperlaneMessage {
+    /// The message ID: keccak256 of the encoded message. Mailboxes record
+    /// delivered messages by ID and validators sign checkpoints over it.
+    pub fn id(&self) -> [u8; 32] {
+        let mut encoded = Vec::new();
+        self.serialize(&mut encoded).expect("writing to a Vec does not fail");
+        keccak::hash(&encoded).to_bytes()
+    }
+}
+
+impl AnchorSerialize for HyperlaneMessage {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&[self.version])?;
+        writer.write_all(&self.nonce.to_be_bytes())?;
+        writer.write_all(&self.origin.to_be_bytes())?;
+        writer.write_all(&self.sender)?;
+        writer.write_all(&self.destination.to_be_bytes())?;
+        writer.write_all(&self.recipient)?;
+        writer.write_all(&self.body)
+    }
+}
+
+impl AnchorDeserialize for HyperlaneMessage {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        Self::deserialize_reader(buf)
+    }
+
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut version = [0u8; 1];
+        reader.read_exact(&mut version)?;
+        if version[0] != HYPERLANE_MESSAGE_VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "unsupported message version"));
+        }
+        let nonce = read_u32(reader)?;
+        let origin = read_u32(reader)?;
+        let sender = read_bytes32(reader)?;
+        let destination = read_u32(reader)?;
+        let recipient = read_bytes32(reader)?;
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)?;
+        Ok(Self {
+            version: version[0],
+            nonce,
+            origin,
+            sender,
+            destination,
+            recipient,
+            body,
+        })
+    }
+}
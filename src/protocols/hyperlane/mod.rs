@@ -0,0 +1,11 @@
+mod adapter;
+mod domain;
+mod ism;
+mod mailbox;
+mod message;
+
+pub use adapter::HyperlaneAdapter;
+pub use domain::{chain_for_domain, domain_for_chain, SOLANA_MAINNET_DOMAIN, SOLANA_TESTNET_DOMAIN};
+pub use ism::{AggregationIsm, InterchainSecurityModule, MessageIdMultisigIsm, ModuleType, RoutingIsm};
+pub use mailbox::MailboxInstruction;
+pub use message::{HyperlaneMessage, HYPERLANE_MESSAGE_VERSION};
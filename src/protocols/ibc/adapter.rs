@@ -0,0 +1,435 @@
+// protocols/ibc/adapter.rs
+
+use super::{
+    acknowledgement_path, commitment_path, receipt_path, Acknowledgement, CustomPacketData, FungibleTokenPacketData, Height, InterchainAccountPacketData, LightClient,
+    Packet, Timeout,
+};
+use crate::config::{ChainConfig, IbcChannel, IbcSettings};
+use crate::protocols::{ProtocolAdapter, VerifiedInbox};
+use crate::types::{ChainId, CrossChainAddress, CrossChainMessage, MessageId, MessageType, CCIHSResult};
+use crate::utility::{default_clock, Clock};
+use crate::CCIHSError;
+use parking_lot::Mutex;
+use solana_program::hash::hash as sha256;
+use solana_program::keccak;
+use solana_program::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Packet data format of a channel, by channel version.
+pub enum PacketFormat {
+    /// `ics20-1`: fungible token transfers.
+    Ics20,
+    /// `ics27-1`: interchain account transactions.
+    Ics27,
+    /// CCIHS's own general messages.
+    Custom,
+}
+
+impl PacketFormat {
+    pub fn from_version(version: &str) -> Self {
+        match version {
+            "ics20-1" => PacketFormat::Ics20,
+            "ics27-1" => PacketFormat::Ics27,
+            _ => PacketFormat::Custom,
+        }
+    }
+}
+
+/// Sends and receives IBC packets to Cosmos chains over configured channels.
+///
+/// Like the other off-chain adapters it does not talk to the chain. Sent
+/// packets are queued for a relayer to pick up with
+/// [`take_outbound`](Self::take_outbound) and stay in flight until
+/// [`handle_acknowledgement`](Self::handle_acknowledgement) or
+/// [`handle_timeout`](Self::handle_timeout). Received packets are checked
+/// against the counterparty's packet commitment through the
+/// [`LightClient`].
+pub struct IbcAdapter {
+    settings: IbcSettings,
+    light_client: Box<dyn LightClient>,
+    clock: Arc<dyn Clock>,
+    next_sequence_send: Mutex<HashMap<ChainId, u64>>,
+    /// Sent packets awaiting an acknowledgement or timeout, by counterparty
+    /// and sequence.
+    in_flight: Mutex<HashMap<(ChainId, u64), Packet>>,
+    /// Counterparty and sequence of every packet received.
+    received: Mutex<HashSet<(ChainId, u64)>>,
+    outbox: Mutex<Vec<Packet>>,
+    inbox: VerifiedInbox,
+}
+
+impl IbcAdapter {
+    pub fn new(settings: IbcSettings, light_client: Box<dyn LightClient>) -> Self {
+        Self::with_clock(settings, light_client, default_clock())
+    }
+
+    pub fn with_clock(settings: IbcSettings, light_client: Box<dyn LightClient>, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            settings,
+            light_client,
+            clock,
+            next_sequence_send: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            received: Mutex::new(HashSet::new()),
+            outbox: Mutex::new(Vec::new()),
+            inbox: VerifiedInbox::new(),
+        }
+    }
+
+    pub fn settings(&self) -> &IbcSettings {
+        &self.settings
+    }
+
+    /// Packets sent since the last call.
+    pub fn take_outbound(&self) -> Vec<Packet> {
+        std::mem::take(&mut *self.outbox.lock())
+    }
+
+    /// Sent packets still awaiting an acknowledgement or timeout.
+    pub fn in_flight(&self) -> Vec<Packet> {
+        self.in_flight.lock().values().cloned().collect()
+    }
+
+    fn channel(&self, chain: ChainId) -> CCIHSResult<&IbcChannel> {
+        if !self.settings.supported_chains.contains(&chain) {
+            return Err(CCIHSError::InvalidChainId);
+        }
+        self.settings.channels.get(&chain).ok_or(CCIHSError::InvalidChainId)
+    }
+
+    /// The counterparty whose channel `packet` travels on, as seen from the
+    /// sending (`outbound`) or receiving end.
+    fn counterparty(&self, packet: &Packet, outbound: bool) -> CCIHSResult<ChainId> {
+        let (port, channel, counterparty_port, counterparty_channel) = match outbound {
+            true => (&packet.source_port, &packet.source_channel, &packet.destination_port, &packet.destination_channel),
+            false => (&packet.destination_port, &packet.destination_channel, &packet.source_port, &packet.source_channel),
+        };
+        self.settings
+            .channels
+            .iter()
+            .find(|(chain, end)| {
+                self.settings.supported_chains.contains(chain)
+                    && end.port_id == *port
+                    && end.channel_id == *channel
+                    && end.counterparty_port_id == *counterparty_port
+                    && end.counterparty_channel_id == *counterparty_channel
+            })
+            .map(|(chain, _)| *chain)
+            .ok_or_else(|| CCIHSError::ProtocolError(format!("ibc: no channel {}/{}", port, channel)))
+    }
+
+    fn packet_data(&self, format: PacketFormat, message: &CrossChainMessage) -> CCIHSResult<Vec<u8>> {
+        match (format, &message.message_type) {
+            (PacketFormat::Ics20, MessageType::TokenTransfer) => {
+                if message.amount == 0 {
+                    return Err(CCIHSError::MissingAmount);
+                }
+                let denom = message.token_address.ok_or(CCIHSError::UnsupportedToken)?;
+                let memo = String::from_utf8(message.payload.clone())
+                    .map_err(|_| CCIHSError::ProtocolError("ibc: ICS-20 memo must be UTF-8".to_string()))?;
+                Ok(FungibleTokenPacketData {
+                    denom: denom.to_string(),
+                    amount: message.amount.to_string(),
+                    sender: message.sender.to_string(),
+                    receiver: message.recipient.to_string(),
+                    memo,
+                }
+                .encode())
+            }
+            (PacketFormat::Ics27 | PacketFormat::Custom, MessageType::General) if message.payload.is_empty() => {
+                Err(CCIHSError::EmptyPayload)
+            }
+            (PacketFormat::Ics27, MessageType::General) => {
+                Ok(InterchainAccountPacketData::execute_tx(message.payload.clone()).encode())
+            }
+            (PacketFormat::Custom, MessageType::General) => Ok(CustomPacketData {
+                sender: message.sender.to_string(),
+                receiver: message.recipient.to_string(),
+                payload: message.payload.clone(),
+            }
+            .encode()),
+            _ => Err(CCIHSError::UnsupportedOperation),
+        }
+    }
+
+    /// Turns received packet data into a message, or the reason to
+    /// acknowledge it with an error.
+    fn receive_data(&self, chain: ChainId, packet: &Packet) -> Result<CrossChainMessage, String> {
+        let nonce = u32::try_from(packet.sequence).map_err(|_| "sequence does not fit a CCIHS nonce".to_string())?;
+        let channel = self.channel(chain).map_err(|e| e.to_string())?;
+        let address = |chain: ChainId, text: &str| {
+            CrossChainAddress::parse(chain, text).map_err(|_| format!("invalid address `{}`", text))
+        };
+
+        let (message_type, payload, amount, token_address, sender, receiver) =
+            match PacketFormat::from_version(&channel.version) {
+                PacketFormat::Ics20 => {
+                    let data = FungibleTokenPacketData::decode(&packet.data).map_err(|e| e.to_string())?;
+                    let amount = data.amount.parse::<u64>().map_err(|_| format!("invalid amount `{}`", data.amount))?;
+                    // A token returning to Solana is denominated by its mint
+                    // under the path it left through.
+                    let prefix = format!("{}/{}/", packet.source_port, packet.source_channel);
+                    let mint = data.denom.strip_prefix(&prefix).and_then(|mint| mint.parse::<Pubkey>().ok());
+                    (MessageType::TokenTransfer, data.memo.into_bytes(), amount, mint, data.sender, data.receiver)
+                }
+                PacketFormat::Ics27 => return Err("Solana is not an interchain account host".to_string()),
+                PacketFormat::Custom => {
+                    let data = CustomPacketData::decode(&packet.data).map_err(|e| e.to_string())?;
+                    (MessageType::General, data.payload, 0, None, data.sender, data.receiver)
+                }
+            };
+
+        Ok(CrossChainMessage {
+            message_type,
+            payload,
+            amount,
+            token_address,
+            sender: address(chain, &sender)?,
+            recipient: address(ChainId::SOLANA, &receiver)?,
+            source_chain: chain,
+            destination_chain: ChainId::SOLANA,
+            nonce,
+            timestamp: self.clock.unix_timestamp().map_err(|e| e.to_string())?,
+            consistency_level: 0,
+            expires_at: None,
+            execute_after: None,
+            // Sequences count per channel, so the channel end the packet
+            // left through stands in for the emitter.
+            message_id: Some(MessageId::compute(
+                chain,
+                &keccak::hash(format!("{}/{}", packet.source_port, packet.source_channel).as_bytes()).to_bytes(),
+                packet.sequence,
+                &packet.data,
+            )),
+        })
+    }
+
+    /// Receives a packet relayed from a counterparty after checking its
+    /// commitment at `proof_height`. Returns the acknowledgement to write:
+    /// packets that verify but cannot be delivered are acknowledged with an
+    /// error rather than rejected, so the sender can refund.
+    ///
+    /// Solana has no IBC height, so only timestamp timeouts apply here.
+    pub fn handle_recv_packet(&self, packet: &Packet, proof: &[u8], proof_height: Height) -> CCIHSResult<Acknowledgement> {
+        let chain = self.counterparty(packet, false)?;
+        let now = self.clock.unix_timestamp()?.saturating_mul(NANOS_PER_SECOND);
+        if packet.timeout.timestamp.map_or(false, |timeout| now >= timeout) {
+            return Err(CCIHSError::MessageExpired);
+        }
+        let path = commitment_path(&packet.source_port, &packet.source_channel, packet.sequence);
+        self.light_client.verify_membership(chain, proof_height, &path, &packet.commitment(), proof)?;
+        if !self.received.lock().insert((chain, packet.sequence)) {
+            return Err(CCIHSError::MessageAlreadyProcessed);
+        }
+
+        match self.receive_data(chain, packet) {
+            Ok(message) => {
+                self.inbox.push(message)?;
+                Ok(Acknowledgement::success())
+            }
+            Err(reason) => Ok(Acknowledgement::Error(reason)),
+        }
+    }
+
+    /// Completes a sent packet with the acknowledgement the counterparty
+    /// wrote, checked at `proof_height`. An error acknowledgement means the
+    /// packet was not delivered and should be refunded.
+    pub fn handle_acknowledgement(
+        &self,
+        packet: &Packet,
+        acknowledgement: &[u8],
+        proof: &[u8],
+        proof_height: Height,
+    ) -> CCIHSResult<Acknowledgement> {
+        let chain = self.counterparty(packet, true)?;
+        let mut in_flight = self.in_flight.lock();
+        if in_flight.get(&(chain, packet.sequence)) != Some(packet) {
+            return Err(CCIHSError::ProtocolError(format!("ibc: packet {} is not in flight", packet.sequence)));
+        }
+        let path = acknowledgement_path(&packet.destination_port, &packet.destination_channel, packet.sequence);
+        let commitment = sha256(acknowledgement).to_bytes();
+        self.light_client.verify_membership(chain, proof_height, &path, &commitment, proof)?;
+
+        let acknowledgement = Acknowledgement::decode(acknowledgement)?;
+        in_flight.remove(&(chain, packet.sequence));
+        Ok(acknowledgement)
+    }
+
+    /// Completes a sent packet that timed out: the counterparty's latest
+    /// known state is past the timeout and holds no receipt for it. The
+    /// packet should be refunded.
+    pub fn handle_timeout(&self, packet: &Packet, proof: &[u8]) -> CCIHSResult<()> {
+        let chain = self.counterparty(packet, true)?;
+        let mut in_flight = self.in_flight.lock();
+        if in_flight.get(&(chain, packet.sequence)) != Some(packet) {
+            return Err(CCIHSError::ProtocolError(format!("ibc: packet {} is not in flight", packet.sequence)));
+        }
+        let (height, timestamp) = self.light_client.latest_consensus(chain)?;
+        if !packet.timeout.has_expired(height, timestamp) {
+            return Err(CCIHSError::ProtocolError(format!("ibc: packet {} has not timed out", packet.sequence)));
+        }
+        let path = receipt_path(&packet.destination_port, &packet.destination_channel, packet.sequence);
+        self.light_client.verify_non_membership(chain, height, &path, proof)?;
+
+        in_flight.remove(&(chain, packet.sequence));
+        Ok(())
+    }
+}
+
+impl ProtocolAdapter for IbcAdapter {
+    /// Sends `message` as a packet on the channel to its destination, in the
+    /// channel's packet format. The packet times out after the configured
+    /// offsets, or when the message expires if that is sooner.
+    fn send_message(&self, message: &CrossChainMessage, _source_config: &ChainConfig, _destination_config: &ChainConfig) -> CCIHSResult<()> {
+        let chain = message.destination_chain;
+        let channel = self.channel(chain)?;
+        let data = self.packet_data(PacketFormat::from_version(&channel.version), message)?;
+
+        let (latest, _) = self.light_client.latest_consensus(chain)?;
+        let now = self.clock.unix_timestamp()?;
+        let deadline = match (self.settings.timeout_secs, message.expires_at) {
+            (0, expires_at) => expires_at,
+            (secs, Some(expires_at)) => Some(expires_at.min(now.saturating_add(secs))),
+            (secs, None) => Some(now.saturating_add(secs)),
+        };
+        let timeout = Timeout {
+            height: (self.settings.timeout_height_offset > 0).then(|| Height {
+                revision_number: latest.revision_number,
+                revision_height: latest.revision_height.saturating_add(self.settings.timeout_height_offset),
+            }),
+            timestamp: deadline.map(|deadline| deadline.saturating_mul(NANOS_PER_SECOND)),
+        };
+
+        let mut next_sequence = self.next_sequence_send.lock();
+        let sequence = next_sequence.entry(chain).or_insert(1);
+        let packet = Packet {
+            sequence: *sequence,
+            source_port: channel.port_id.clone(),
+            source_channel: channel.channel_id.clone(),
+            destination_port: channel.counterparty_port_id.clone(),
+            destination_channel: channel.counterparty_channel_id.clone(),
+            data,
+            timeout,
+        };
+        *sequence += 1;
+
+        self.in_flight.lock().insert((chain, packet.sequence), packet.clone());
+        self.outbox.lock().push(packet);
+        Ok(())
+    }
+
+    fn receive_message(&self, source_config: &ChainConfig) -> CCIHSResult<CrossChainMessage> {
+        self.inbox.pop(source_config.chain_id)
+    }
+
+    fn verify_message(&self, message: &CrossChainMessage, _source_config: &ChainConfig, _destination_config: &ChainConfig) -> CCIHSResult<bool> {
+        self.inbox.is_verified(message)
+    }
+
+    fn supported_chains(&self) -> Vec<ChainId> {
+        self.settings.supported_chains.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::protocols::ibc::LocalLightClient;
+    use crate::utility::TestClock;
+
+    const OSMOSIS: ChainId = ChainId::OSMOSIS;
+
+    fn settings() -> IbcSettings {
+        let mut settings = IbcSettings::new();
+        settings.supported_chains = [ChainId::SOLANA, OSMOSIS].into_iter().collect();
+        settings.channels.insert(
+            OSMOSIS,
+            IbcChannel {
+                port_id: "ccihs".to_string(),
+                channel_id: "channel-0".to_string(),
+                counterparty_port_id: "wasm.osmo1ccihs".to_string(),
+                counterparty_channel_id: "channel-42".to_string(),
+                version: "ccihs-1".to_string(),
+            },
+        );
+        settings
+    }
+
+    #[test]
+    fn test_sends_receives_and_acknowledges_packets() {
+        let light_client = Arc::new(LocalLightClient::new());
+        light_client.update(OSMOSIS, Height { revision_number: 1, revision_height: 100 }, 0);
+        let adapter = IbcAdapter::with_clock(settings(), Box::new(light_client.clone()), Arc::new(TestClock::new(1_000)));
+        let solana = ChainConfig::new(ChainId::SOLANA, "http://localhost:8899".to_string());
+        let osmosis = ChainConfig::new(OSMOSIS, "http://localhost:26657".to_string());
+        let sender = Pubkey::new_unique();
+        let osmo_address = CrossChainAddress::Cosmos { hrp: "osmo".to_string(), bytes: vec![7; 20] };
+
+        // Send: sequence 1, timeouts from the light client and the clock.
+        let message = CrossChainMessage {
+            message_type: MessageType::General,
+            payload: b"ping".to_vec(),
+            amount: 0,
+            token_address: None,
+            sender: CrossChainAddress::Solana(sender),
+            recipient: CrossChainAddress::parse(OSMOSIS, &osmo_address.to_string()).unwrap(),
+            source_chain: ChainId::SOLANA,
+            destination_chain: OSMOSIS,
+            nonce: 0,
+            timestamp: 1_000,
+            consistency_level: 0,
+            expires_at: None,
+            execute_after: None,
+            message_id: None,
+        };
+        adapter.send_message(&message, &solana, &osmosis).unwrap();
+        let sent = adapter.take_outbound().remove(0);
+        assert_eq!(sent.sequence, 1);
+        assert_eq!(sent.timeout.height, Some(Height { revision_number: 1, revision_height: 1_100 }));
+        assert_eq!(sent.timeout.timestamp, Some(1_600 * NANOS_PER_SECOND));
+
+        // Acknowledge: only once the counterparty has committed the ack.
+        let ack = Acknowledgement::success().encode();
+        let proof_height = Height { revision_number: 1, revision_height: 101 };
+        assert!(adapter.handle_acknowledgement(&sent, &ack, &[], proof_height).is_err());
+        light_client.update(OSMOSIS, proof_height, 0);
+        light_client.store(OSMOSIS, &acknowledgement_path("wasm.osmo1ccihs", "channel-42", 1), sha256(&ack).to_bytes());
+        assert!(adapter.handle_acknowledgement(&sent, &ack, &[], proof_height).unwrap().is_success());
+        assert!(adapter.in_flight().is_empty());
+
+        // Receive: the reply is delivered once, and only with a commitment.
+        let reply = Packet {
+            sequence: 5,
+            source_port: "wasm.osmo1ccihs".to_string(),
+            source_channel: "channel-42".to_string(),
+            destination_port: "ccihs".to_string(),
+            destination_channel: "channel-0".to_string(),
+            data: CustomPacketData {
+                sender: osmo_address.to_string(),
+                receiver: sender.to_string(),
+                payload: b"pong".to_vec(),
+            }
+            .encode(),
+            timeout: Timeout { height: None, timestamp: Some(2_000 * NANOS_PER_SECOND) },
+        };
+        assert!(adapter.handle_recv_packet(&reply, &[], proof_height).is_err());
+        light_client.store(OSMOSIS, &commitment_path("wasm.osmo1ccihs", "channel-42", 5), reply.commitment());
+        assert!(adapter.handle_recv_packet(&reply, &[], proof_height).unwrap().is_success());
+        assert!(adapter.handle_recv_packet(&reply, &[], proof_height).is_err());
+
+        let received = adapter.receive_message(&osmosis).unwrap();
+        assert_eq!(received.payload, b"pong");
+        assert_eq!(received.sender, osmo_address);
+        assert_eq!(received.nonce, 5);
+
+        // Only the received message verifies, not any message on the route.
+        assert!(adapter.verify_message(&received, &osmosis, &solana).unwrap());
+        let mut forged = received.clone();
+        forged.payload = b"forged".to_vec();
+        assert!(!adapter.verify_message(&forged, &osmosis, &solana).unwrap());
+    }
+}
@@ -0,0 +1,133 @@
+// protocols/ibc/light_client.rs
+
+use super::Height;
+use crate::types::ChainId;
+use crate::{CCIHSError, CCIHSResult};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// ICS-24 path of a packet commitment.
+pub fn commitment_path(port: &str, channel: &str, sequence: u64) -> String {
+    format!("commitments/ports/{}/channels/{}/sequences/{}", port, channel, sequence)
+}
+
+/// ICS-24 path of a packet acknowledgement.
+pub fn acknowledgement_path(port: &str, channel: &str, sequence: u64) -> String {
+    format!("acks/ports/{}/channels/{}/sequences/{}", port, channel, sequence)
+}
+
+/// ICS-24 path of a packet receipt.
+pub fn receipt_path(port: &str, channel: &str, sequence: u64) -> String {
+    format!("receipts/ports/{}/channels/{}/sequences/{}", port, channel, sequence)
+}
+
+/// Verifies counterparty state against consensus states the client has
+/// accepted, in the manner of an ICS-02 client.
+pub trait LightClient: Send + Sync {
+    /// Latest verified height of `chain` and its timestamp in unix
+    /// nanoseconds.
+    fn latest_consensus(&self, chain: ChainId) -> CCIHSResult<(Height, u64)>;
+
+    /// Proves `value` is stored at `path` on `chain` at `height`.
+    fn verify_membership(&self, chain: ChainId, height: Height, path: &str, value: &[u8; 32], proof: &[u8]) -> CCIHSResult<()>;
+
+    /// Proves nothing is stored at `path` on `chain` at `height`.
+    fn verify_non_membership(&self, chain: ChainId, height: Height, path: &str, proof: &[u8]) -> CCIHSResult<()>;
+}
+
+/// Lets one client, e.g. a [`LocalLightClient`] a test keeps updating, be
+/// shared.
+impl<T: LightClient + ?Sized> LightClient for Arc<T> {
+    fn latest_consensus(&self, chain: ChainId) -> CCIHSResult<(Height, u64)> {
+        (**self).latest_consensus(chain)
+    }
+
+    fn verify_membership(&self, chain: ChainId, height: Height, path: &str, value: &[u8; 32], proof: &[u8]) -> CCIHSResult<()> {
+        (**self).verify_membership(chain, height, path, value, proof)
+    }
+
+    fn verify_non_membership(&self, chain: ChainId, height: Height, path: &str, proof: &[u8]) -> CCIHSResult<()> {
+        (**self).verify_non_membership(chain, height, path, proof)
+    }
+}
+
+#[derive(Default)]
+struct LocalChainState {
+    latest: (Height, u64),
+    /// Value at each path and the height it was written at.
+    store: HashMap<String, (Height, [u8; 32])>,
+}
+
+/// Light client stub for tests and local networks. It trusts whatever
+/// counterparty state it is told about and ignores proofs.
+#[derive(Default)]
+pub struct LocalLightClient {
+    chains: Mutex<HashMap<ChainId, LocalChainState>>,
+}
+
+impl LocalLightClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts a consensus state of `chain`.
+    pub fn update(&self, chain: ChainId, height: Height, timestamp: u64) {
+        self.chains.lock().entry(chain).or_default().latest = (height, timestamp);
+    }
+
+    /// Records `value` written at `path` on `chain` at the latest height.
+    pub fn store(&self, chain: ChainId, path: &str, value: [u8; 32]) {
+        let mut chains = self.chains.lock();
+        let state = chains.entry(chain).or_default();
+        let height = state.latest.0;
+        state.store.insert(path.to_string(), (height, value));
+    }
+
+    /// Records `path` being deleted on `chain`.
+    pub fn delete(&self, chain: ChainId, path: &str) {
+        if let Some(state) = self.chains.lock().get_mut(&chain) {
+            state.store.remove(path);
+        }
+    }
+}
+
+impl LightClient for LocalLightClient {
+    fn latest_consensus(&self, chain: ChainId) -> CCIHSResult<(Height, u64)> {
+        self.chains
+            .lock()
+            .get(&chain)
+            .map(|state| state.latest)
+            .ok_or_else(|| CCIHSError::ProtocolError(format!("ibc: no light client for {}", chain)))
+    }
+
+    fn verify_membership(&self, chain: ChainId, height: Height, path: &str, value: &[u8; 32], _proof: &[u8]) -> CCIHSResult<()> {
+        let chains = self.chains.lock();
+        let state = chains
+            .get(&chain)
+            .ok_or_else(|| CCIHSError::ProtocolError(format!("ibc: no light client for {}", chain)))?;
+        if height > state.latest.0 {
+            return Err(CCIHSError::InvalidAttestation(format!("ibc: height {:?} is not verified yet", height)));
+        }
+        match state.store.get(path) {
+            Some((written, stored)) if *written <= height && stored == value => Ok(()),
+            _ => Err(CCIHSError::InvalidAttestation(format!("ibc: `{}` does not hold the expected value", path))),
+        }
+    }
+
+    fn verify_non_membership(&self, chain: ChainId, height: Height, path: &str, _proof: &[u8]) -> CCIHSResult<()> {
+        let chains = self.chains.lock();
+        let state = chains
+            .get(&chain)
+            .ok_or_else(|| CCIHSError::ProtocolError(format!("ibc: no light client for {}", chain)))?;
+        if height > state.latest.0 {
+            return Err(CCIHSError::InvalidAttestation(format!("ibc: height {:?} is not verified yet", height)));
+        }
+        match state.store.get(path) {
+            Some((written, _)) if *written <= height => {
+                Err(CCIHSError::InvalidAttestation(format!("ibc: `{}` is set", path)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+mod adapter;
+mod light_client;
+mod packet;
+
+pub use adapter::{IbcAdapter, PacketFormat};
+pub use light_client::{acknowledgement_path, commitment_path, receipt_path, LightClient, LocalLightClient};
+pub use packet::{
+    Acknowledgement, CustomPacketData, FungibleTokenPacketData, Height, InterchainAccountPacketData, Packet, Timeout,
+};
@@ -0,0 +1,231 @@
+// protocols/ibc/packet.rs
+
+use crate::utility::encoding::{from_base64, to_base64};
+use crate::{CCIHSError, CCIHSResult};
+use serde_json::{json, Value};
+use solana_program::hash::hash as sha256;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// ICS-02 height. Heights compare by revision first.
+pub struct Height {
+    pub revision_number: u64,
+    pub revision_height: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// When a packet may no longer be received. Either part may be unset (zero
+/// on the wire), but not both.
+pub struct Timeout {
+    /// Counterparty height from which the packet times out.
+    pub height: Option<Height>,
+    /// Counterparty time from which the packet times out, in unix
+    /// nanoseconds.
+    pub timestamp: Option<u64>,
+}
+
+impl Timeout {
+    /// Whether a chain at `height` and `timestamp` (unix nanoseconds) is
+    /// past the timeout.
+    pub fn has_expired(&self, height: Height, timestamp: u64) -> bool {
+        self.height.map_or(false, |timeout| height >= timeout)
+            || self.timestamp.map_or(false, |timeout| timestamp >= timeout)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// An ICS-04 packet.
+pub struct Packet {
+    /// Per channel send sequence, starting at 1.
+    pub sequence: u64,
+    pub source_port: String,
+    pub source_channel: String,
+    pub destination_port: String,
+    pub destination_channel: String,
+    pub data: Vec<u8>,
+    pub timeout: Timeout,
+}
+
+impl Packet {
+    /// The ICS-04 packet commitment:
+    /// `sha256(timeout_timestamp | timeout_revision_number | timeout_revision_height | sha256(data))`,
+    /// integers big endian.
+    pub fn commitment(&self) -> [u8; 32] {
+        let height = self.timeout.height.unwrap_or_default();
+        let mut preimage = Vec::with_capacity(56);
+        preimage.extend_from_slice(&self.timeout.timestamp.unwrap_or(0).to_be_bytes());
+        preimage.extend_from_slice(&height.revision_number.to_be_bytes());
+        preimage.extend_from_slice(&height.revision_height.to_be_bytes());
+        preimage.extend_from_slice(&sha256(&self.data).to_bytes());
+        sha256(&preimage).to_bytes()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// ICS-20 `FungibleTokenPacketData`.
+pub struct FungibleTokenPacketData {
+    pub denom: String,
+    /// Decimal string, so amounts beyond 64 bits fit.
+    pub amount: String,
+    pub sender: String,
+    pub receiver: String,
+    /// Free text, often JSON for middleware. Omitted from the encoding when
+    /// empty.
+    pub memo: String,
+}
+
+impl FungibleTokenPacketData {
+    /// JSON with sorted keys, as ICS-20 modules encode it.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut value = json!({
+            "amount": self.amount,
+            "denom": self.denom,
+            "receiver": self.receiver,
+            "sender": self.sender,
+        });
+        if !self.memo.is_empty() {
+            value["memo"] = json!(self.memo);
+        }
+        value.to_string().into_bytes()
+    }
+
+    pub fn decode(bytes: &[u8]) -> CCIHSResult<Self> {
+        let value = parse_json(bytes)?;
+        Ok(Self {
+            denom: string_field(&value, "denom")?,
+            amount: string_field(&value, "amount")?,
+            sender: string_field(&value, "sender")?,
+            receiver: string_field(&value, "receiver")?,
+            memo: optional_string_field(&value, "memo")?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// ICS-27 `InterchainAccountPacketData`.
+pub struct InterchainAccountPacketData {
+    /// `TYPE_EXECUTE_TX` is the only type hosts execute.
+    pub packet_type: String,
+    /// Protobuf encoded `CosmosTx`.
+    pub data: Vec<u8>,
+    pub memo: String,
+}
+
+impl InterchainAccountPacketData {
+    pub const EXECUTE_TX: &'static str = "TYPE_EXECUTE_TX";
+
+    pub fn execute_tx(data: Vec<u8>) -> Self {
+        Self {
+            packet_type: Self::EXECUTE_TX.to_string(),
+            data,
+            memo: String::new(),
+        }
+    }
+
+    /// Proto3 JSON: sorted keys, `data` in base64.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut value = json!({
+            "data": to_base64(&self.data),
+            "type": self.packet_type,
+        });
+        if !self.memo.is_empty() {
+            value["memo"] = json!(self.memo);
+        }
+        value.to_string().into_bytes()
+    }
+
+    pub fn decode(bytes: &[u8]) -> CCIHSResult<Self> {
+        let value = parse_json(bytes)?;
+        Ok(Self {
+            packet_type: string_field(&value, "type")?,
+            data: from_base64(&string_field(&value, "data")?)?,
+            memo: optional_string_field(&value, "memo")?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// Packet data of CCIHS's own port: a general message with its sender and
+/// receiver, which plain IBC packets do not carry.
+pub struct CustomPacketData {
+    pub sender: String,
+    pub receiver: String,
+    pub payload: Vec<u8>,
+}
+
+impl CustomPacketData {
+    /// JSON with sorted keys and the payload in base64.
+    pub fn encode(&self) -> Vec<u8> {
+        json!({
+            "payload": to_base64(&self.payload),
+            "receiver": self.receiver,
+            "sender": self.sender,
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    pub fn decode(bytes: &[u8]) -> CCIHSResult<Self> {
+        let value = parse_json(bytes)?;
+        Ok(Self {
+            sender: string_field(&value, "sender")?,
+            receiver: string_field(&value, "receiver")?,
+            payload: from_base64(&string_field(&value, "payload")?)?,
+        })
+    }
+}
+
+fn parse_json(bytes: &[u8]) -> CCIHSResult<Value> {
+    serde_json::from_slice(bytes).map_err(|e| CCIHSError::DeserializationError(e.to_string()))
+}
+
+fn string_field(value: &Value, name: &str) -> CCIHSResult<String> {
+    value
+        .get(name)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| CCIHSError::DeserializationError(format!("packet data has no string `{}`", name)))
+}
+
+fn optional_string_field(value: &Value, name: &str) -> CCIHSResult<String> {
+    match value.get(name) {
+        None => Ok(String::new()),
+        Some(_) => string_field(value, name),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// ICS-04 acknowledgement in its JSON encoding, `{"result": base64}` or
+/// `{"error": message}`.
+pub enum Acknowledgement {
+    Result(Vec<u8>),
+    Error(String),
+}
+
+impl Acknowledgement {
+    /// The `{"result":"AQ=="}` acknowledgement ICS-20 and ICS-27 write on
+    /// success.
+    pub fn success() -> Self {
+        Acknowledgement::Result(vec![1])
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, Acknowledgement::Result(_))
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let value = match self {
+            Acknowledgement::Result(result) => json!({ "result": to_base64(result) }),
+            Acknowledgement::Error(error) => json!({ "error": error }),
+        };
+        value.to_string().into_bytes()
+    }
+
+    pub fn decode(bytes: &[u8]) -> CCIHSResult<Self> {
+        let value = parse_json(bytes)?;
+        match (value.get("result").and_then(Value::as_str), value.get("error").and_then(Value::as_str)) {
+            (Some(result), None) => Ok(Acknowledgement::Result(from_base64(result)?)),
+            (None, Some(error)) => Ok(Acknowledgement::Error(error.to_string())),
+            _ => Err(CCIHSError::DeserializationError("acknowledgement is neither a result nor an error".to_string())),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+// protocols/inbox.rs
+
+use crate::types::{ChainId, CrossChainMessage, CCIHSResult};
+use crate::CCIHSError;
+use anchor_lang::AnchorSerialize;
+use parking_lot::Mutex;
+use solana_program::keccak;
+use std::collections::{HashSet, VecDeque};
+
+/// Received messages of an off-chain adapter (CCTP, Hyperlane, Axelar,
+/// IBC), waiting for `receive_message`.
+///
+/// These adapters verify a message (its attestation, ISM metadata, operator
+/// signatures or light client proof) when it is handed to them, and only
+/// then push it here. `verify_message` cannot repeat that check without the
+/// proof, so it asks [`is_verified`](Self::is_verified) instead: a message
+/// is verified if the adapter pushed exactly that message, whatever its
+/// route.
+pub struct VerifiedInbox {
+    pending: Mutex<VecDeque<CrossChainMessage>>,
+    /// Hashes of every message pushed so far, received or not.
+    verified: Mutex<HashSet<[u8; 32]>>,
+}
+
+impl VerifiedInbox {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(VecDeque::new()),
+            verified: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Queues a message the adapter has verified.
+    pub fn push(&self, message: CrossChainMessage) -> CCIHSResult<()> {
+        self.verified.lock().insert(message_hash(&message)?);
+        self.pending.lock().push_back(message);
+        Ok(())
+    }
+
+    /// The oldest queued message from `source_chain`.
+    pub fn pop(&self, source_chain: ChainId) -> CCIHSResult<CrossChainMessage> {
+        let mut pending = self.pending.lock();
+        let position = pending
+            .iter()
+            .position(|message| message.source_chain == source_chain)
+            .ok_or(CCIHSError::NoPendingMessage)?;
+        Ok(pending.remove(position).expect("position is in range"))
+    }
+
+    /// Whether `message`, every field of it, was verified and pushed.
+    pub fn is_verified(&self, message: &CrossChainMessage) -> CCIHSResult<bool> {
+        Ok(self.verified.lock().contains(&message_hash(message)?))
+    }
+}
+
+impl Default for VerifiedInbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn message_hash(message: &CrossChainMessage) -> CCIHSResult<[u8; 32]> {
+    let encoded = message.try_to_vec().map_err(|e| CCIHSError::SerializationError(e.to_string()))?;
+    Ok(keccak::hash(&encoded).to_bytes())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::types::{CrossChainAddress, MessageType};
+    use solana_program::pubkey::Pubkey;
+
+    fn message(source_chain: ChainId, payload: &[u8]) -> CrossChainMessage {
+        CrossChainMessage {
+            message_type: MessageType::General,
+            payload: payload.to_vec(),
+            amount: 0,
+            token_address: None,
+            sender: CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(1)),
+            recipient: CrossChainAddress::Solana(Pubkey::new_from_array([2; 32])),
+            source_chain,
+            destination_chain: ChainId::SOLANA,
+            nonce: 1,
+            timestamp: 0,
+            consistency_level: 0,
+            expires_at: None,
+            execute_after: None,
+            message_id: None,
+        }
+    }
+
+    #[test]
+    fn test_only_pushed_messages_are_verified() {
+        let inbox = VerifiedInbox::new();
+        let received = message(ChainId::ETHEREUM, b"hello");
+        inbox.push(received.clone()).unwrap();
+
+        assert!(matches!(inbox.pop(ChainId::SOLANA), Err(CCIHSError::NoPendingMessage)));
+        assert_eq!(inbox.pop(ChainId::ETHEREUM).unwrap(), received);
+        assert!(inbox.is_verified(&received).unwrap());
+
+        let mut forged = received.clone();
+        forged.payload = b"goodbye".to_vec();
+        assert!(!inbox.is_verified(&forged).unwrap());
+        assert!(!inbox.is_verified(&message(ChainId::ETHEREUM, b"other")).unwrap());
+    }
+}
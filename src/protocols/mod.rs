@@ -1,4 +1,6 @@
 use crate::types::{CrossChainMessage, ChainId, CCIHSResult};
+use crate::config::ChainConfig;
+use std::sync::Arc;
 
 // pub trait ProtocolAdapter {
 //     fn send_message(&self, message: &CrossChainMessage) -> CCIHSResult<()>;
@@ -13,9 +15,56 @@ pub trait ProtocolAdapter: Send + Sync {
     fn receive_message(&self, source_config: &ChainConfig) -> CCIHSResult<CrossChainMessage>;
     fn verify_message(&self, message: &CrossChainMessage, source_config: &ChainConfig, destination_config: &ChainConfig) -> CCIHSResult<bool>;
     fn supported_chains(&self) -> Vec<ChainId>;
+
+    /// Sends every message under the same batch ID (Wormhole's nonce). The
+    /// default just sends them one by one; `batch_id` is already set as each
+    /// message's nonce.
+    ///
+    /// Not atomic: when a send fails, the members before it are already in
+    /// flight. The receiving side holds members until the whole batch has
+    /// arrived, so an incomplete batch is never executed.
+    fn send_batch(&self, _batch_id: u32, messages: &[CrossChainMessage], source_config: &ChainConfig, destination_config: &ChainConfig) -> CCIHSResult<()> {
+        for message in messages {
+            self.send_message(message, source_config, destination_config)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lets one adapter, e.g. a [`FakeAdapter`] a test keeps feeding, be
+/// shared.
+impl<T: ProtocolAdapter + ?Sized> ProtocolAdapter for Arc<T> {
+    fn send_message(&self, message: &CrossChainMessage, source_config: &ChainConfig, destination_config: &ChainConfig) -> CCIHSResult<()> {
+        (**self).send_message(message, source_config, destination_config)
+    }
+
+    fn receive_message(&self, source_config: &ChainConfig) -> CCIHSResult<CrossChainMessage> {
+        (**self).receive_message(source_config)
+    }
+
+    fn verify_message(&self, message: &CrossChainMessage, source_config: &ChainConfig, destination_config: &ChainConfig) -> CCIHSResult<bool> {
+        (**self).verify_message(message, source_config, destination_config)
+    }
+
+    fn supported_chains(&self) -> Vec<ChainId> {
+        (**self).supported_chains()
+    }
+
+    fn send_batch(&self, batch_id: u32, messages: &[CrossChainMessage], source_config: &ChainConfig, destination_config: &ChainConfig) -> CCIHSResult<()> {
+        (**self).send_batch(batch_id, messages, source_config, destination_config)
+    }
 }
 
+mod inbox;
+pub use inbox::VerifiedInbox;
+mod fake;
+pub use fake::FakeAdapter;
+
 pub mod wormhole;
+pub mod cctp;
+pub mod hyperlane;
+pub mod axelar;
+pub mod ibc;
 //pub use wormhole::*;
 
 
@@ -24,23 +24,23 @@ use crate::types::{ ChainId, CrossChainAddress, CCIHSResult, MessageStatus, Hook
 use crate::protocols::wormhole::message::*;
 use crate::error::CCIHSError;
 use crate::hooks::HookManager;
-use super::config::WormholeConfig;
+use crate::config::{ProtocolConfigTrait, WormholeSettings};
 use super::instructions::*;
 
 pub struct WormholeAdapter {
-    pub config: WormholeConfig, //I still need to setup WormholeConfig
+    pub config: WormholeSettings,
     hook_manager: HookManager,
     foreign_emitters: ForeignEmitter,
     received: Received,
 }
 
 impl WormholeAdapter {
-    pub fn new(config: WormholeConfig, hook_manager: HookManager) -> Self {
+    pub fn new(config: WormholeSettings, hook_manager: HookManager) -> Self {
         Self { config, hook_manager, foreign_emitters: BTreeMap::new(), received: Received::default() }
     }
 
     pub fn initialize(&self, ctx: Context<Initialize>) -> Result<()> {
-        initialize_handler(ctx, self.config.relayer_fee, self.config.relayer_fee_precision)//TODO: Check to know if you need to add the args to WormholeConfig
+        initialize_handler(ctx, self.config.relayer_fee, self.config.relayer_fee_precision)
     }
 
     pub fn register_emitter(&self, ctx: Context<RegisterEmitter>, chain: u16, address: [u8; 32]) -> Result<()> {
@@ -48,13 +48,50 @@ impl WormholeAdapter {
     }
 
     pub fn send_message(&self, ctx: Context<SendMessage>, message: &CrossChainMessage) -> Result<()> {
-        send_message_handler(ctx, message)//TODO: Check to know if you need to add the args to WormholeConfig
+        send_message_handler(ctx, message.payload.clone(), message.expires_at, message.execute_after)//TODO: Check to know if you need to add the args to WormholeConfig
     }
 
     pub fn receive_message(&self, ctx: Context<ReceiveMessage>, vaa_hash: [u8; 32]) -> Result<()> {
         receive_message_handler(ctx, vaa_hash)//TODO: Check to know if you need to add the args to WormholeConfig
     }
 
+    pub fn initialize_message_state(&self, ctx: Context<InitializeMessageState>) -> Result<()> {
+        initialize_message_state_handler(ctx)
+    }
+
+    pub fn update_message_state(&self, ctx: Context<UpdateMessageState>, nonce: u64) -> Result<()> {
+        update_message_state_handler(ctx, nonce)
+    }
+
+    pub fn register_receiver(&self, ctx: Context<RegisterReceiver>, program_id: Pubkey, enabled: bool) -> Result<()> {
+        register_receiver_handler(ctx, program_id, enabled)
+    }
+
+    pub fn receive_and_deliver_message<'info>(
+        &self,
+        ctx: Context<'_, '_, '_, 'info, ReceiveAndDeliverMessage<'info>>,
+        vaa_hash: [u8; 32],
+    ) -> Result<()> {
+        receive_and_deliver_message_handler(ctx, vaa_hash)
+    }
+
+    pub fn receive_message_chunk(&self, ctx: Context<ReceiveMessageChunk>, vaa_hash: [u8; 32], transfer_id: [u8; 32]) -> Result<()> {
+        receive_message_chunk_handler(ctx, vaa_hash, transfer_id)
+    }
+
+    pub fn close_reassembly_buffer(&self, ctx: Context<CloseReassemblyBuffer>, emitter_chain: u16, transfer_id: [u8; 32]) -> Result<()> {
+        close_reassembly_buffer_handler(ctx, emitter_chain, transfer_id)
+    }
+
+    pub fn deliver_reassembled_message<'info>(
+        &self,
+        ctx: Context<'_, '_, '_, 'info, DeliverReassembledMessage<'info>>,
+        emitter_chain: u16,
+        transfer_id: [u8; 32],
+    ) -> Result<()> {
+        deliver_reassembled_message_handler(ctx, emitter_chain, transfer_id)
+    }
+
     fn serialize_message(&self, message: &CrossChainMessage) -> Result<Vec<u8>> {
         wormhole_io::serialize(message)
         .map_err(|e| CCIHSError::SerializationError(e.to_string()))
@@ -299,7 +336,7 @@ impl ProtocolAdapter for WormholeAdapter {
         Ok(true)
     }
     fn supported_chains(&self) -> Vec<ChainId> {
-        self.config.supported_chains()
+        self.config.supported_chains().iter().cloned().collect()
     }
 }
 
@@ -0,0 +1,755 @@
+// protocols/wormhole/client.rs
+
+//! Off-chain builders for the instructions of the CCIHS Wormhole program.
+//! Every builder derives the PDAs its instruction needs and returns an
+//! [`Instruction`] whose accounts are in the order of the instruction's
+//! `#[derive(Accounts)]` struct, ready to be put in a transaction.
+
+use super::instructions::{SEED_PREFIX_BRIDGED, SEED_PREFIX_SENT, SEED_PREFIX_TMP};
+use super::state::{
+    ForeignEmitter, ForeignTokenEmitter, GeneralMessageConfig, ReassemblyBuffer, Received,
+    RedeemerConfig, RegisteredReceiver, SenderConfig, WormholeEmitter, SEED_PREFIX_DELIVERY_AUTHORITY,
+};
+use super::WormholeCrossChainMessage;
+use crate::config::WormholeSettings;
+use crate::state::{derive_foreign_state_address, derive_state_address};
+use crate::types::{ChainId, MessageId};
+use crate::{CCIHSError, CCIHSResult};
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::message::Message;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::AnchorSerialize;
+use anchor_spl::associated_token::get_associated_token_address;
+use wormhole_anchor_sdk::{token_bridge, wormhole};
+
+/// Anchor instruction discriminator: the first 8 bytes of
+/// `sha256("global:<name>")`.
+pub fn instruction_discriminator(name: &str) -> [u8; 8] {
+    let preimage = format!("global:{name}");
+    let hash = anchor_lang::solana_program::hash::hash(preimage.as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash.to_bytes()[..8]);
+    discriminator
+}
+
+fn instruction_data<A: AnchorSerialize>(name: &str, args: A) -> CCIHSResult<Vec<u8>> {
+    let mut data = instruction_discriminator(name).to_vec();
+    args.serialize(&mut data)
+        .map_err(|e| CCIHSError::SerializationError(e.to_string()))?;
+    Ok(data)
+}
+
+/// Serializes `instructions` as a transaction paid by `payer` with every
+/// signature left zeroed, for signing elsewhere. `recent_blockhash` may be a
+/// durable nonce.
+pub fn unsigned_transaction(instructions: &[Instruction], payer: &Pubkey, recent_blockhash: Hash) -> Vec<u8> {
+    let message = Message::new_with_blockhash(instructions, Some(payer), &recent_blockhash);
+    let signature_count = message.header.num_required_signatures as usize;
+
+    let mut transaction = Vec::new();
+    // Signature count as a compact-u16.
+    let mut count = signature_count;
+    loop {
+        let byte = (count & 0x7f) as u8;
+        count >>= 7;
+        if count == 0 {
+            transaction.push(byte);
+            break;
+        }
+        transaction.push(byte | 0x80);
+    }
+    transaction.resize(transaction.len() + signature_count * 64, 0);
+    transaction.extend_from_slice(&message.serialize());
+    transaction
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// What a builder needs to know about a VAA that was already posted to the
+/// Wormhole core bridge.
+pub struct PostedVaaInfo {
+    pub vaa_hash: [u8; 32],
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+}
+
+impl PostedVaaInfo {
+    /// The [`MessageId`] the program records in the `received` account when
+    /// it redeems this VAA. `payload` is the payload of the cross-chain
+    /// message carried by the VAA, not the VAA payload itself.
+    pub fn message_id(&self, payload: &[u8]) -> MessageId {
+        MessageId::compute(ChainId(self.emitter_chain), &self.emitter_address, self.sequence, payload)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Origin of a token bridged to Solana as a Token Bridge wrapped mint.
+pub struct WrappedToken {
+    pub token_chain: u16,
+    pub token_address: [u8; 32],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Builds instructions for one deployment of the CCIHS Wormhole program.
+pub struct InstructionBuilder {
+    pub program_id: Pubkey,
+    pub core_bridge_program: Pubkey,
+    pub token_bridge_program: Pubkey,
+}
+
+impl InstructionBuilder {
+    pub fn new(program_id: Pubkey, core_bridge_program: Pubkey, token_bridge_program: Pubkey) -> Self {
+        Self {
+            program_id,
+            core_bridge_program,
+            token_bridge_program,
+        }
+    }
+
+    /// Uses the bridge programs of `settings`. The token bridge program must
+    /// be set since [`initialize`](Self::initialize) needs it.
+    pub fn from_settings(program_id: Pubkey, settings: &WormholeSettings) -> CCIHSResult<Self> {
+        let token_bridge_program = settings.token_bridge_program.ok_or_else(|| {
+            CCIHSError::InvalidProtocolSettings("wormhole: token_bridge_program is not set".to_string())
+        })?;
+        Ok(Self::new(program_id, settings.core_bridge_program, token_bridge_program))
+    }
+
+    fn program_address(&self, seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &self.program_id).0
+    }
+
+    fn core_bridge_address(&self, seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &self.core_bridge_program).0
+    }
+
+    fn token_bridge_address(&self, seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &self.token_bridge_program).0
+    }
+
+    // PDAs of this program.
+
+    pub fn general_message_config(&self) -> Pubkey {
+        self.program_address(&[GeneralMessageConfig::SEED_PREFIX])
+    }
+
+    pub fn sender_config(&self) -> Pubkey {
+        self.program_address(&[SenderConfig::SEED_PREFIX])
+    }
+
+    pub fn redeemer_config(&self) -> Pubkey {
+        self.program_address(&[RedeemerConfig::SEED_PREFIX])
+    }
+
+    pub fn emitter(&self) -> Pubkey {
+        self.program_address(&[WormholeEmitter::SEED_PREFIX])
+    }
+
+    pub fn foreign_emitter(&self, chain: u16) -> Pubkey {
+        self.program_address(&[ForeignEmitter::SEED_PREFIX, &chain.to_le_bytes()])
+    }
+
+    pub fn foreign_token_emitter(&self, chain: u16) -> Pubkey {
+        self.program_address(&[ForeignTokenEmitter::SEED_PREFIX, &chain.to_le_bytes()])
+    }
+
+    pub fn received(&self, emitter_chain: u16, sequence: u64) -> Pubkey {
+        self.program_address(&[Received::SEED_PREFIX, &emitter_chain.to_le_bytes(), &sequence.to_le_bytes()])
+    }
+
+    /// Message account of a message sent with `sequence` by this program's
+    /// emitter.
+    pub fn sent_message(&self, sequence: u64) -> Pubkey {
+        self.program_address(&[SEED_PREFIX_SENT, &sequence.to_le_bytes()])
+    }
+
+    /// Message account of a token transfer posted with `sequence` by the
+    /// Token Bridge emitter.
+    pub fn bridged_message(&self, sequence: u64) -> Pubkey {
+        self.program_address(&[SEED_PREFIX_BRIDGED, &sequence.to_le_bytes()])
+    }
+
+    pub fn tmp_token_account(&self, mint: &Pubkey) -> Pubkey {
+        self.program_address(&[SEED_PREFIX_TMP, mint.as_ref()])
+    }
+
+    pub fn reassembly_buffer(&self, emitter_chain: u16, transfer_id: &[u8; 32]) -> Pubkey {
+        self.program_address(&[ReassemblyBuffer::SEED_PREFIX, &emitter_chain.to_le_bytes(), transfer_id])
+    }
+
+    pub fn sender_state(&self, sender: &Pubkey) -> Pubkey {
+        derive_state_address(&self.program_id, sender).0
+    }
+
+    pub fn foreign_sender_state(&self, chain: u16, sender: &[u8; 32]) -> Pubkey {
+        derive_foreign_state_address(&self.program_id, chain, sender).0
+    }
+
+    pub fn registered_receiver(&self, receiver_program: &Pubkey) -> Pubkey {
+        self.program_address(&[RegisteredReceiver::SEED_PREFIX, receiver_program.as_ref()])
+    }
+
+    pub fn delivery_authority(&self) -> Pubkey {
+        self.program_address(&[SEED_PREFIX_DELIVERY_AUTHORITY])
+    }
+
+    // PDAs of the Wormhole core bridge.
+
+    pub fn wormhole_bridge(&self) -> Pubkey {
+        self.core_bridge_address(&[wormhole::BridgeData::SEED_PREFIX])
+    }
+
+    pub fn wormhole_fee_collector(&self) -> Pubkey {
+        self.core_bridge_address(&[wormhole::FeeCollector::SEED_PREFIX])
+    }
+
+    /// Sequence tracker of this program's emitter.
+    pub fn sequence(&self) -> Pubkey {
+        self.core_bridge_address(&[wormhole::SequenceTracker::SEED_PREFIX, self.emitter().as_ref()])
+    }
+
+    pub fn posted_vaa(&self, vaa_hash: &[u8; 32]) -> Pubkey {
+        self.core_bridge_address(&[wormhole::SEED_PREFIX_POSTED_VAA, vaa_hash])
+    }
+
+    // PDAs of the Wormhole token bridge.
+
+    pub fn token_bridge_config(&self) -> Pubkey {
+        self.token_bridge_address(&[token_bridge::Config::SEED_PREFIX])
+    }
+
+    pub fn token_bridge_authority_signer(&self) -> Pubkey {
+        self.token_bridge_address(&[token_bridge::SEED_PREFIX_AUTHORITY_SIGNER])
+    }
+
+    pub fn token_bridge_custody_signer(&self) -> Pubkey {
+        self.token_bridge_address(&[token_bridge::SEED_PREFIX_CUSTODY_SIGNER])
+    }
+
+    pub fn token_bridge_mint_authority(&self) -> Pubkey {
+        self.token_bridge_address(&[token_bridge::SEED_PREFIX_MINT_AUTHORITY])
+    }
+
+    pub fn token_bridge_emitter(&self) -> Pubkey {
+        self.token_bridge_address(&[token_bridge::SEED_PREFIX_EMITTER])
+    }
+
+    pub fn token_bridge_sequence(&self) -> Pubkey {
+        self.core_bridge_address(&[wormhole::SequenceTracker::SEED_PREFIX, self.token_bridge_emitter().as_ref()])
+    }
+
+    /// Token Bridge custody account holding the bridged balance of a native
+    /// mint.
+    pub fn custody(&self, mint: &Pubkey) -> Pubkey {
+        self.token_bridge_address(&[mint.as_ref()])
+    }
+
+    pub fn wrapped_mint(&self, token: &WrappedToken) -> Pubkey {
+        self.token_bridge_address(&[
+            token_bridge::WrappedMint::SEED_PREFIX,
+            &token.token_chain.to_be_bytes(),
+            &token.token_address,
+        ])
+    }
+
+    pub fn wrapped_meta(&self, mint: &Pubkey) -> Pubkey {
+        self.token_bridge_address(&[token_bridge::WrappedMeta::SEED_PREFIX, mint.as_ref()])
+    }
+
+    /// Registration of the Token Bridge of another chain.
+    pub fn token_bridge_endpoint(&self, chain: u16, address: &[u8; 32]) -> Pubkey {
+        self.token_bridge_address(&[&chain.to_be_bytes(), address])
+    }
+
+    /// Claim account the Token Bridge creates when it redeems a transfer, so
+    /// the same transfer cannot be redeemed twice.
+    pub fn claim(&self, vaa: &PostedVaaInfo) -> Pubkey {
+        self.token_bridge_address(&[
+            &vaa.emitter_address,
+            &vaa.emitter_chain.to_be_bytes(),
+            &vaa.sequence.to_be_bytes(),
+        ])
+    }
+
+    // Instructions.
+
+    pub fn initialize(&self, owner: &Pubkey, relayer_fee: u64, relayer_fee_precision: u32) -> CCIHSResult<Instruction> {
+        let accounts = vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(self.general_message_config(), false),
+            AccountMeta::new(self.sender_config(), false),
+            AccountMeta::new(self.redeemer_config(), false),
+            AccountMeta::new_readonly(self.core_bridge_program, false),
+            AccountMeta::new_readonly(self.token_bridge_program, false),
+            AccountMeta::new_readonly(self.wormhole_bridge(), false),
+            AccountMeta::new_readonly(self.token_bridge_config(), false),
+            AccountMeta::new_readonly(self.token_bridge_authority_signer(), false),
+            AccountMeta::new_readonly(self.token_bridge_custody_signer(), false),
+            AccountMeta::new_readonly(self.token_bridge_mint_authority(), false),
+            AccountMeta::new_readonly(self.token_bridge_emitter(), false),
+            AccountMeta::new(self.wormhole_fee_collector(), false),
+            AccountMeta::new(self.emitter(), false),
+            AccountMeta::new(self.sequence(), false),
+            AccountMeta::new_readonly(self.token_bridge_sequence(), false),
+            AccountMeta::new(self.sent_message(wormhole::INITIAL_SEQUENCE), false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        self.instruction("initialize", accounts, (relayer_fee, relayer_fee_precision))
+    }
+
+    pub fn register_emitter(&self, owner: &Pubkey, chain: u16, address: [u8; 32]) -> CCIHSResult<Instruction> {
+        let accounts = vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(self.general_message_config(), false),
+            AccountMeta::new(self.foreign_emitter(chain), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        self.instruction("register_emitter", accounts, (chain, address))
+    }
+
+    /// `token_bridge_emitter` is the address of the Token Bridge on `chain`.
+    pub fn register_foreign_token_emitter(&self, owner: &Pubkey, chain: u16, token_bridge_emitter: [u8; 32]) -> CCIHSResult<Instruction> {
+        let accounts = vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(self.sender_config(), false),
+            AccountMeta::new(self.foreign_token_emitter(chain), false),
+            AccountMeta::new_readonly(self.token_bridge_endpoint(chain, &token_bridge_emitter), false),
+            AccountMeta::new_readonly(self.token_bridge_program, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        self.instruction("register_foreign_token_emitter", accounts, (chain, token_bridge_emitter))
+    }
+
+    pub fn register_receiver(&self, owner: &Pubkey, receiver_program: Pubkey, enabled: bool) -> CCIHSResult<Instruction> {
+        let accounts = vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(self.general_message_config(), false),
+            AccountMeta::new(self.registered_receiver(&receiver_program), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        self.instruction("register_receiver", accounts, (receiver_program, enabled))
+    }
+
+    pub fn update_relayer_fee(&self, owner: &Pubkey, relayer_fee: u32, relayer_fee_precision: u32) -> CCIHSResult<Instruction> {
+        // The program does not check the owner's signature, but only the
+        // owner has a reason to send this, so it is marked as a signer.
+        let accounts = vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(self.redeemer_config(), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        self.instruction("update_relayer_fee", accounts, (relayer_fee, relayer_fee_precision))
+    }
+
+    pub fn initialize_message_state(&self, payer: &Pubkey) -> CCIHSResult<Instruction> {
+        let accounts = vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(self.sender_state(payer), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        self.instruction("initialize_message_state", accounts, ())
+    }
+
+    pub fn update_message_state(&self, sender: &Pubkey, nonce: u64) -> CCIHSResult<Instruction> {
+        let accounts = vec![
+            AccountMeta::new_readonly(*sender, true),
+            AccountMeta::new(self.sender_state(sender), false),
+        ];
+        self.instruction("update_message_state", accounts, nonce)
+    }
+
+    /// `sequence` is the next value of this program's sequence tracker, which
+    /// names the message account. Read it from the chain right before
+    /// sending.
+    pub fn send_message(
+        &self,
+        payer: &Pubkey,
+        sequence: u64,
+        message: Vec<u8>,
+        expires_at: Option<u64>,
+        execute_after: Option<u64>,
+    ) -> CCIHSResult<Instruction> {
+        let accounts = vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(self.general_message_config(), false),
+            AccountMeta::new_readonly(self.core_bridge_program, false),
+            AccountMeta::new(self.wormhole_bridge(), false),
+            AccountMeta::new(self.wormhole_fee_collector(), false),
+            AccountMeta::new_readonly(self.emitter(), false),
+            AccountMeta::new(self.sequence(), false),
+            AccountMeta::new(self.sent_message(sequence), false),
+            AccountMeta::new(self.sender_state(payer), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ];
+        self.instruction("send_message", accounts, (message, expires_at, execute_after))
+    }
+
+    pub fn receive_message(&self, payer: &Pubkey, vaa: &PostedVaaInfo) -> CCIHSResult<Instruction> {
+        let accounts = vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(self.general_message_config(), false),
+            AccountMeta::new_readonly(self.core_bridge_program, false),
+            AccountMeta::new_readonly(self.posted_vaa(&vaa.vaa_hash), false),
+            AccountMeta::new_readonly(self.foreign_emitter(vaa.emitter_chain), false),
+            AccountMeta::new(self.received(vaa.emitter_chain, vaa.sequence), false),
+            AccountMeta::new(self.foreign_sender_state(vaa.emitter_chain, &vaa.emitter_address), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        self.instruction("receive_message", accounts, vaa.vaa_hash)
+    }
+
+    pub fn receive_message_chunk(&self, payer: &Pubkey, vaa: &PostedVaaInfo, transfer_id: [u8; 32]) -> CCIHSResult<Instruction> {
+        let accounts = vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(self.general_message_config(), false),
+            AccountMeta::new_readonly(self.core_bridge_program, false),
+            AccountMeta::new_readonly(self.posted_vaa(&vaa.vaa_hash), false),
+            AccountMeta::new_readonly(self.foreign_emitter(vaa.emitter_chain), false),
+            AccountMeta::new(self.reassembly_buffer(vaa.emitter_chain, &transfer_id), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        self.instruction("receive_message_chunk", accounts, (vaa.vaa_hash, transfer_id))
+    }
+
+    /// `payer` must be the payer of the transfer's first fragment.
+    pub fn close_reassembly_buffer(&self, payer: &Pubkey, emitter_chain: u16, transfer_id: [u8; 32]) -> CCIHSResult<Instruction> {
+        let accounts = vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(self.reassembly_buffer(emitter_chain, &transfer_id), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        self.instruction("close_reassembly_buffer", accounts, (emitter_chain, transfer_id))
+    }
+
+    /// `receiver_accounts` are appended after the program's own accounts and
+    /// passed on to the receiver program.
+    pub fn deliver_reassembled_message(
+        &self,
+        payer: &Pubkey,
+        emitter_chain: u16,
+        transfer_id: [u8; 32],
+        receiver_program: &Pubkey,
+        receiver_accounts: Vec<AccountMeta>,
+    ) -> CCIHSResult<Instruction> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*payer, true),
+            AccountMeta::new_readonly(self.general_message_config(), false),
+            AccountMeta::new(self.reassembly_buffer(emitter_chain, &transfer_id), false),
+            AccountMeta::new_readonly(self.registered_receiver(receiver_program), false),
+            AccountMeta::new_readonly(*receiver_program, false),
+            AccountMeta::new_readonly(self.delivery_authority(), false),
+        ];
+        accounts.extend(receiver_accounts);
+        self.instruction("deliver_reassembled_message", accounts, (emitter_chain, transfer_id))
+    }
+
+    /// `receiver_accounts` are appended after the program's own accounts and
+    /// passed on to the receiver program.
+    pub fn receive_and_deliver_message(
+        &self,
+        payer: &Pubkey,
+        vaa: &PostedVaaInfo,
+        receiver_program: &Pubkey,
+        receiver_accounts: Vec<AccountMeta>,
+    ) -> CCIHSResult<Instruction> {
+        let mut accounts = vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(self.general_message_config(), false),
+            AccountMeta::new_readonly(self.core_bridge_program, false),
+            AccountMeta::new_readonly(self.posted_vaa(&vaa.vaa_hash), false),
+            AccountMeta::new_readonly(self.foreign_emitter(vaa.emitter_chain), false),
+            AccountMeta::new(self.received(vaa.emitter_chain, vaa.sequence), false),
+            AccountMeta::new_readonly(self.registered_receiver(receiver_program), false),
+            AccountMeta::new_readonly(*receiver_program, false),
+            AccountMeta::new_readonly(self.delivery_authority(), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        accounts.extend(receiver_accounts);
+        self.instruction("receive_and_deliver_message", accounts, vaa.vaa_hash)
+    }
+
+    /// `token_bridge_sequence` is the next value of the Token Bridge emitter's
+    /// sequence tracker, which names the message account.
+    #[allow(clippy::too_many_arguments)]
+    pub fn send_native_tokens_with_payload(
+        &self,
+        payer: &Pubkey,
+        mint: &Pubkey,
+        token_bridge_sequence: u64,
+        batch_id: u32,
+        amount: u64,
+        recipient_address: [u8; 32],
+        recipient_chain: u16,
+        message: WormholeCrossChainMessage,
+        content: Vec<u8>,
+        expires_at: Option<u64>,
+        execute_after: Option<u64>,
+    ) -> CCIHSResult<Instruction> {
+        let accounts = vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(self.sender_config(), false),
+            AccountMeta::new_readonly(self.foreign_token_emitter(recipient_chain), false),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(get_associated_token_address(payer, mint), false),
+            AccountMeta::new(self.tmp_token_account(mint), false),
+            AccountMeta::new_readonly(self.core_bridge_program, false),
+            AccountMeta::new_readonly(self.token_bridge_program, false),
+            AccountMeta::new_readonly(self.token_bridge_config(), false),
+            AccountMeta::new(self.custody(mint), false),
+            AccountMeta::new_readonly(self.token_bridge_authority_signer(), false),
+            AccountMeta::new_readonly(self.token_bridge_custody_signer(), false),
+            AccountMeta::new(self.wormhole_bridge(), false),
+            AccountMeta::new(self.bridged_message(token_bridge_sequence), false),
+            AccountMeta::new(self.token_bridge_emitter(), false),
+            AccountMeta::new(self.token_bridge_sequence(), false),
+            AccountMeta::new(self.wormhole_fee_collector(), false),
+            AccountMeta::new(self.sender_state(payer), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(anchor_spl::token::ID, false),
+            AccountMeta::new_readonly(anchor_spl::associated_token::ID, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ];
+        self.instruction(
+            "send_native_tokens_with_payload",
+            accounts,
+            (batch_id, amount, recipient_address, recipient_chain, message, content, expires_at, execute_after),
+        )
+    }
+
+    /// `token_bridge_sequence` is the next value of the Token Bridge emitter's
+    /// sequence tracker, which names the message account.
+    #[allow(clippy::too_many_arguments)]
+    pub fn send_wrapped_tokens_with_payload(
+        &self,
+        payer: &Pubkey,
+        token: &WrappedToken,
+        token_bridge_sequence: u64,
+        batch_id: u32,
+        amount: u64,
+        recipient_address: [u8; 32],
+        recipient_chain: u16,
+    ) -> CCIHSResult<Instruction> {
+        let wrapped_mint = self.wrapped_mint(token);
+        let accounts = vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(self.sender_config(), false),
+            AccountMeta::new_readonly(self.foreign_token_emitter(recipient_chain), false),
+            AccountMeta::new(wrapped_mint, false),
+            AccountMeta::new(get_associated_token_address(payer, &wrapped_mint), false),
+            AccountMeta::new(self.tmp_token_account(&wrapped_mint), false),
+            AccountMeta::new_readonly(self.core_bridge_program, false),
+            AccountMeta::new_readonly(self.token_bridge_program, false),
+            AccountMeta::new_readonly(self.wrapped_meta(&wrapped_mint), false),
+            AccountMeta::new(self.token_bridge_config(), false),
+            AccountMeta::new_readonly(self.token_bridge_authority_signer(), false),
+            AccountMeta::new(self.wormhole_bridge(), false),
+            AccountMeta::new(self.bridged_message(token_bridge_sequence), false),
+            AccountMeta::new(self.token_bridge_emitter(), false),
+            AccountMeta::new(self.token_bridge_sequence(), false),
+            AccountMeta::new(self.wormhole_fee_collector(), false),
+            AccountMeta::new(self.sender_state(payer), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(anchor_spl::token::ID, false),
+            AccountMeta::new_readonly(anchor_spl::associated_token::ID, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ];
+        self.instruction(
+            "send_wrapped_tokens_with_payload",
+            accounts,
+            (batch_id, amount, recipient_address, recipient_chain),
+        )
+    }
+
+    /// `vaa` is the posted token transfer; its emitter is the Token Bridge of
+    /// the sending chain.
+    pub fn redeem_native_transfer_with_payload(
+        &self,
+        payer: &Pubkey,
+        recipient: &Pubkey,
+        mint: &Pubkey,
+        vaa: &PostedVaaInfo,
+    ) -> CCIHSResult<Instruction> {
+        let accounts = vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(get_associated_token_address(payer, mint), false),
+            AccountMeta::new_readonly(self.redeemer_config(), false),
+            AccountMeta::new_readonly(self.foreign_token_emitter(vaa.emitter_chain), false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(get_associated_token_address(recipient, mint), false),
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new(self.tmp_token_account(mint), false),
+            AccountMeta::new_readonly(self.core_bridge_program, false),
+            AccountMeta::new_readonly(self.token_bridge_program, false),
+            AccountMeta::new_readonly(self.token_bridge_config(), false),
+            AccountMeta::new_readonly(self.posted_vaa(&vaa.vaa_hash), false),
+            AccountMeta::new(self.claim(vaa), false),
+            AccountMeta::new_readonly(self.token_bridge_endpoint(vaa.emitter_chain, &vaa.emitter_address), false),
+            AccountMeta::new(self.custody(mint), false),
+            AccountMeta::new_readonly(self.token_bridge_custody_signer(), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(anchor_spl::token::ID, false),
+            AccountMeta::new_readonly(anchor_spl::associated_token::ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ];
+        self.instruction("redeem_native_transfer_with_payload", accounts, vaa.vaa_hash)
+    }
+
+    /// `vaa` is the posted token transfer; its emitter is the Token Bridge of
+    /// the sending chain.
+    pub fn redeem_wrapped_transfer_with_payload(
+        &self,
+        payer: &Pubkey,
+        recipient: &Pubkey,
+        token: &WrappedToken,
+        vaa: &PostedVaaInfo,
+    ) -> CCIHSResult<Instruction> {
+        let wrapped_mint = self.wrapped_mint(token);
+        let accounts = vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(get_associated_token_address(payer, &wrapped_mint), false),
+            AccountMeta::new_readonly(self.redeemer_config(), false),
+            AccountMeta::new_readonly(self.foreign_token_emitter(vaa.emitter_chain), false),
+            AccountMeta::new(wrapped_mint, false),
+            AccountMeta::new(get_associated_token_address(recipient, &wrapped_mint), false),
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new(self.tmp_token_account(&wrapped_mint), false),
+            AccountMeta::new_readonly(self.core_bridge_program, false),
+            AccountMeta::new_readonly(self.token_bridge_program, false),
+            AccountMeta::new_readonly(self.wrapped_meta(&wrapped_mint), false),
+            AccountMeta::new_readonly(self.token_bridge_config(), false),
+            AccountMeta::new_readonly(self.posted_vaa(&vaa.vaa_hash), false),
+            AccountMeta::new(self.claim(vaa), false),
+            AccountMeta::new_readonly(self.token_bridge_endpoint(vaa.emitter_chain, &vaa.emitter_address), false),
+            AccountMeta::new_readonly(self.token_bridge_mint_authority(), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(anchor_spl::token::ID, false),
+            AccountMeta::new_readonly(anchor_spl::associated_token::ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ];
+        self.instruction("redeem_wrapped_transfer_with_payload", accounts, vaa.vaa_hash)
+    }
+
+    fn instruction<A: AnchorSerialize>(&self, name: &str, accounts: Vec<AccountMeta>, args: A) -> CCIHSResult<Instruction> {
+        Ok(Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: instruction_data(name, args)?,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    fn builder() -> InstructionBuilder {
+        InstructionBuilder::new(Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique())
+    }
+
+    fn pda(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(seeds, program_id).0
+    }
+
+    #[test]
+    fn test_send_message_accounts_follow_the_accounts_struct() {
+        let builder = builder();
+        let payer = Pubkey::new_unique();
+        let ix = builder.send_message(&payer, 7, b"hello".to_vec(), None, None).unwrap();
+
+        let emitter = pda(&[b"emitter"], &builder.program_id);
+        let expected = vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(pda(&[b"general_message_config"], &builder.program_id), false),
+            AccountMeta::new_readonly(builder.core_bridge_program, false),
+            AccountMeta::new(pda(&[b"Bridge"], &builder.core_bridge_program), false),
+            AccountMeta::new(pda(&[b"fee_collector"], &builder.core_bridge_program), false),
+            AccountMeta::new_readonly(emitter, false),
+            AccountMeta::new(pda(&[b"Sequence", emitter.as_ref()], &builder.core_bridge_program), false),
+            AccountMeta::new(pda(&[b"sent", &7u64.to_le_bytes()], &builder.program_id), false),
+            AccountMeta::new(pda(&[b"cross_chain_state", payer.as_ref()], &builder.program_id), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ];
+        assert_eq!(ix.program_id, builder.program_id);
+        assert_eq!(ix.accounts, expected);
+        assert_eq!(ix.data[..8], instruction_discriminator("send_message"));
+    }
+
+    #[test]
+    fn test_receive_message_derives_accounts_from_the_vaa() {
+        let builder = builder();
+        let payer = Pubkey::new_unique();
+        let vaa = PostedVaaInfo {
+            vaa_hash: [3; 32],
+            emitter_chain: 2,
+            emitter_address: [9; 32],
+            sequence: 42,
+        };
+        let ix = builder.receive_message(&payer, &vaa).unwrap();
+
+        let program_id = builder.program_id;
+        let expected = vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(pda(&[b"general_message_config"], &program_id), false),
+            AccountMeta::new_readonly(builder.core_bridge_program, false),
+            AccountMeta::new_readonly(pda(&[b"PostedVAA", &[3; 32]], &builder.core_bridge_program), false),
+            AccountMeta::new_readonly(pda(&[b"foreign_emitter", &2u16.to_le_bytes()], &program_id), false),
+            AccountMeta::new(pda(&[b"received", &2u16.to_le_bytes(), &42u64.to_le_bytes()], &program_id), false),
+            AccountMeta::new(pda(&[b"cross_chain_state", &2u16.to_le_bytes(), &[9; 32]], &program_id), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ];
+        assert_eq!(ix.accounts, expected);
+        assert_eq!(ix.data, [&instruction_discriminator("receive_message")[..], &[3; 32]].concat());
+    }
+
+    #[test]
+    fn test_redeem_derives_token_bridge_accounts() {
+        let builder = builder();
+        let (payer, recipient, mint) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let vaa = PostedVaaInfo {
+            vaa_hash: [1; 32],
+            emitter_chain: 2,
+            emitter_address: [5; 32],
+            sequence: 11,
+        };
+        let ix = builder.redeem_native_transfer_with_payload(&payer, &recipient, &mint, &vaa).unwrap();
+
+        let token_bridge = builder.token_bridge_program;
+        assert_eq!(ix.accounts.len(), 20);
+        assert_eq!(ix.accounts[1].pubkey, get_associated_token_address(&payer, &mint));
+        assert_eq!(ix.accounts[7], AccountMeta::new(pda(&[b"tmp", mint.as_ref()], &builder.program_id), false));
+        assert_eq!(
+            ix.accounts[12],
+            AccountMeta::new(pda(&[&[5; 32], &2u16.to_be_bytes(), &11u64.to_be_bytes()], &token_bridge), false)
+        );
+        assert_eq!(ix.accounts[13].pubkey, pda(&[&2u16.to_be_bytes(), &[5; 32]], &token_bridge));
+        assert_eq!(ix.accounts[14], AccountMeta::new(pda(&[mint.as_ref()], &token_bridge), false));
+    }
+
+    #[test]
+    fn test_token_sends_record_sender_state() {
+        let builder = builder();
+        let payer = Pubkey::new_unique();
+        let token = WrappedToken {
+            token_chain: 2,
+            token_address: [4; 32],
+        };
+        let ix = builder.send_wrapped_tokens_with_payload(&payer, &token, 3, 0, 10, [1; 32], 2).unwrap();
+
+        assert_eq!(ix.accounts.len(), 22);
+        assert_eq!(
+            ix.accounts[16],
+            AccountMeta::new(pda(&[b"cross_chain_state", payer.as_ref()], &builder.program_id), false)
+        );
+        assert_eq!(ix.accounts[17], AccountMeta::new_readonly(system_program::ID, false));
+    }
+}
@@ -0,0 +1,267 @@
+// protocols/wormhole/decode.rs
+
+//! Decodes raw account data of the CCIHS Wormhole program outside the
+//! program, e.g. data fetched with `getAccountInfo`. The account type is
+//! detected from its Anchor discriminator.
+
+use super::state::{
+    ForeignEmitter, ForeignTokenEmitter, GeneralMessageConfig, ReassemblyBuffer, Received, RedeemerConfig,
+    RegisteredReceiver, SenderConfig, WormholeEmitter,
+};
+use crate::state::CrossChainMessageState;
+use crate::utility::encoding::to_hex;
+use crate::{CCIHSError, CCIHSResult};
+use anchor_lang::{AccountDeserialize, Discriminator};
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// A decoded account of the CCIHS Wormhole program.
+pub enum DecodedAccount {
+    GeneralMessageConfig(GeneralMessageConfig),
+    SenderConfig(SenderConfig),
+    RedeemerConfig(RedeemerConfig),
+    ForeignEmitter(ForeignEmitter),
+    ForeignTokenEmitter(ForeignTokenEmitter),
+    Received(Received),
+    WormholeEmitter(WormholeEmitter),
+    ReassemblyBuffer(ReassemblyBuffer),
+    RegisteredReceiver(RegisteredReceiver),
+    CrossChainMessageState(CrossChainMessageState),
+}
+
+fn deserialize<T: AccountDeserialize>(data: &[u8]) -> CCIHSResult<T> {
+    let mut data = data;
+    T::try_deserialize(&mut data).map_err(|e| CCIHSError::DeserializationError(e.to_string()))
+}
+
+impl DecodedAccount {
+    /// Decodes `data`, discriminator included.
+    pub fn decode(data: &[u8]) -> CCIHSResult<Self> {
+        let discriminator: [u8; 8] = data
+            .get(..8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| CCIHSError::DeserializationError("account data is shorter than a discriminator".to_string()))?;
+
+        if discriminator == GeneralMessageConfig::DISCRIMINATOR {
+            deserialize(data).map(DecodedAccount::GeneralMessageConfig)
+        } else if discriminator == SenderConfig::DISCRIMINATOR {
+            deserialize(data).map(DecodedAccount::SenderConfig)
+        } else if discriminator == RedeemerConfig::DISCRIMINATOR {
+            deserialize(data).map(DecodedAccount::RedeemerConfig)
+        } else if discriminator == ForeignEmitter::DISCRIMINATOR {
+            deserialize(data).map(DecodedAccount::ForeignEmitter)
+        } else if discriminator == ForeignTokenEmitter::DISCRIMINATOR {
+            deserialize(data).map(DecodedAccount::ForeignTokenEmitter)
+        } else if discriminator == Received::DISCRIMINATOR {
+            deserialize(data).map(DecodedAccount::Received)
+        } else if discriminator == WormholeEmitter::DISCRIMINATOR {
+            deserialize(data).map(DecodedAccount::WormholeEmitter)
+        } else if discriminator == ReassemblyBuffer::DISCRIMINATOR {
+            deserialize(data).map(DecodedAccount::ReassemblyBuffer)
+        } else if discriminator == RegisteredReceiver::DISCRIMINATOR {
+            deserialize(data).map(DecodedAccount::RegisteredReceiver)
+        } else if discriminator == CrossChainMessageState::DISCRIMINATOR {
+            deserialize(data).map(DecodedAccount::CrossChainMessageState)
+        } else {
+            Err(CCIHSError::DeserializationError(format!(
+                "unknown account discriminator {}",
+                to_hex(&discriminator)
+            )))
+        }
+    }
+
+    pub fn account_type(&self) -> &'static str {
+        match self {
+            DecodedAccount::GeneralMessageConfig(_) => "GeneralMessageConfig",
+            DecodedAccount::SenderConfig(_) => "SenderConfig",
+            DecodedAccount::RedeemerConfig(_) => "RedeemerConfig",
+            DecodedAccount::ForeignEmitter(_) => "ForeignEmitter",
+            DecodedAccount::ForeignTokenEmitter(_) => "ForeignTokenEmitter",
+            DecodedAccount::Received(_) => "Received",
+            DecodedAccount::WormholeEmitter(_) => "WormholeEmitter",
+            DecodedAccount::ReassemblyBuffer(_) => "ReassemblyBuffer",
+            DecodedAccount::RegisteredReceiver(_) => "RegisteredReceiver",
+            DecodedAccount::CrossChainMessageState(_) => "CrossChainMessageState",
+        }
+    }
+
+    /// Fields of the account in declaration order. Pubkeys are base58,
+    /// byte arrays hex.
+    pub fn fields(&self) -> Vec<(&'static str, Value)> {
+        match self {
+            DecodedAccount::GeneralMessageConfig(config) => vec![
+                ("owner", json!(config.owner.to_string())),
+                ("wormhole.bridge", json!(config.wormhole.bridge.to_string())),
+                ("wormhole.fee_collector", json!(config.wormhole.fee_collector.to_string())),
+                ("wormhole.sequence", json!(config.wormhole.sequence.to_string())),
+                ("batch_id", json!(config.batch_id)),
+                ("finality", json!(config.finality)),
+            ],
+            DecodedAccount::SenderConfig(config) => vec![
+                ("owner", json!(config.owner.to_string())),
+                ("bump", json!(config.bump)),
+                ("token_bridge.config", json!(config.token_bridge.config.to_string())),
+                ("token_bridge.authority_signer", json!(config.token_bridge.authority_signer.to_string())),
+                ("token_bridge.custody_signer", json!(config.token_bridge.custody_signer.to_string())),
+                ("token_bridge.emitter", json!(config.token_bridge.emitter.to_string())),
+                ("token_bridge.sequence", json!(config.token_bridge.sequence.to_string())),
+                ("token_bridge.wormhole_bridge", json!(config.token_bridge.wormhole_bridge.to_string())),
+                ("token_bridge.wormhole_fee_collector", json!(config.token_bridge.wormhole_fee_collector.to_string())),
+                ("finality", json!(config.finality)),
+            ],
+            DecodedAccount::RedeemerConfig(config) => vec![
+                ("owner", json!(config.owner.to_string())),
+                ("bump", json!(config.bump)),
+                ("token_bridge.config", json!(config.token_bridge.config.to_string())),
+                ("token_bridge.custody_signer", json!(config.token_bridge.custody_signer.to_string())),
+                ("token_bridge.mint_authority", json!(config.token_bridge.mint_authority.to_string())),
+                ("relayer_fee", json!(config.relayer_fee)),
+                ("relayer_fee_precision", json!(config.relayer_fee_precision)),
+            ],
+            DecodedAccount::ForeignEmitter(emitter) => vec![
+                ("chain", json!(emitter.chain)),
+                ("address", json!(to_hex(&emitter.address))),
+            ],
+            DecodedAccount::ForeignTokenEmitter(emitter) => vec![
+                ("chain", json!(emitter.chain)),
+                ("address", json!(to_hex(&emitter.address))),
+                ("token_bridge_foreign_endpoint", json!(emitter.token_bridge_foreign_endpoint.to_string())),
+            ],
+            DecodedAccount::Received(received) => {
+                let mut fields = vec![
+                    ("batch_id", json!(received.batch_id)),
+                    ("wormhole_message_hash", json!(to_hex(&received.wormhole_message_hash))),
+                    ("message_id", json!(to_hex(&received.message_id))),
+                    ("message", json!(to_hex(&received.message))),
+                ];
+                if let Ok(text) = std::str::from_utf8(&received.message) {
+                    fields.push(("message_utf8", json!(text)));
+                }
+                fields
+            }
+            DecodedAccount::WormholeEmitter(emitter) => vec![("bump", json!(emitter.bump))],
+            // The payload itself can be several kilobytes; only its length
+            // is shown.
+            DecodedAccount::ReassemblyBuffer(buffer) => vec![
+                ("transfer_id", json!(to_hex(&buffer.transfer_id))),
+                ("emitter_chain", json!(buffer.emitter_chain)),
+                ("emitter_address", json!(to_hex(&buffer.emitter_address))),
+                ("total_chunks", json!(buffer.total_chunks)),
+                ("chunk_size", json!(buffer.chunk_size)),
+                ("total_length", json!(buffer.total_length)),
+                ("checksum", json!(to_hex(&buffer.checksum))),
+                ("received_chunks", json!(buffer.received_count())),
+                ("complete", json!(buffer.complete)),
+                ("payer", json!(buffer.payer.to_string())),
+                (
+                    "recipient",
+                    json!(buffer.envelope.as_ref().and_then(|envelope| envelope.recipient.as_deref()).map(to_hex)),
+                ),
+                ("sequence", json!(buffer.sequence.to_string())),
+                ("delivered", json!(buffer.delivered)),
+                ("data_length", json!(buffer.data.len())),
+            ],
+            DecodedAccount::RegisteredReceiver(receiver) => vec![
+                ("program_id", json!(receiver.program_id.to_string())),
+                ("enabled", json!(receiver.enabled)),
+            ],
+            DecodedAccount::CrossChainMessageState(state) => vec![
+                ("last_nonce", json!(state.last_nonce.to_string())),
+                ("message_count", json!(state.message_count.to_string())),
+                ("last_message_timestamp", json!(state.last_message_timestamp)),
+                ("bump", json!(state.bump)),
+            ],
+        }
+    }
+
+    /// `{"type": ..., "fields": {...}}`. Nested fields keep their dotted
+    /// names.
+    pub fn to_json(&self) -> Value {
+        let fields: Map<String, Value> = self
+            .fields()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        json!({
+            "type": self.account_type(),
+            "fields": fields,
+        })
+    }
+}
+
+impl fmt::Display for DecodedAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.account_type())?;
+        for (name, value) in self.fields() {
+            match value {
+                Value::String(text) => writeln!(f, "  {name}: {text}")?,
+                other => writeln!(f, "  {name}: {other}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::AccountSerialize;
+
+    fn serialize<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_decodes_by_discriminator() {
+        let emitter = ForeignEmitter {
+            chain: 2,
+            address: [0xab; 32],
+        };
+        let mut data = serialize(&emitter);
+
+        let decoded = DecodedAccount::decode(&data).unwrap();
+        assert_eq!(decoded.account_type(), "ForeignEmitter");
+        assert_eq!(decoded.to_json()["fields"]["chain"], json!(2));
+        assert_eq!(decoded.to_json()["fields"]["address"], json!("ab".repeat(32)));
+        assert!(decoded.to_string().contains("  chain: 2"));
+
+        data[0] ^= 0xff;
+        assert!(DecodedAccount::decode(&data).is_err());
+    }
+
+    #[test]
+    fn test_decodes_delivery_and_state_accounts() {
+        let program_id = Pubkey::new_unique();
+        let receiver = RegisteredReceiver { program_id, enabled: true };
+        let decoded = DecodedAccount::decode(&serialize(&receiver)).unwrap();
+        assert_eq!(decoded.account_type(), "RegisteredReceiver");
+        assert_eq!(decoded.to_json()["fields"]["program_id"], json!(program_id.to_string()));
+        assert_eq!(decoded.to_json()["fields"]["enabled"], json!(true));
+
+        let buffer = ReassemblyBuffer {
+            emitter_chain: 2,
+            total_chunks: 3,
+            received_chunks: 0b101,
+            data: vec![0; 300],
+            ..Default::default()
+        };
+        let decoded = DecodedAccount::decode(&serialize(&buffer)).unwrap();
+        assert_eq!(decoded.account_type(), "ReassemblyBuffer");
+        assert_eq!(decoded.to_json()["fields"]["received_chunks"], json!(2));
+        assert_eq!(decoded.to_json()["fields"]["data_length"], json!(300));
+
+        let state = CrossChainMessageState {
+            last_nonce: 7,
+            message_count: 3,
+            last_message_timestamp: 1_700_000_000,
+            bump: 254,
+        };
+        let decoded = DecodedAccount::decode(&serialize(&state)).unwrap();
+        assert_eq!(decoded.account_type(), "CrossChainMessageState");
+        assert_eq!(decoded.to_json()["fields"]["last_nonce"], json!("7"));
+        assert_eq!(decoded.to_json()["fields"]["bump"], json!(254));
+    }
+}
@@ -118,4 +118,47 @@ pub enum WormholeError {
     /// Specified Token Bridge mint authority PDA is wrong.
     InvalidTokenBridgeMintAuthority,
 
+    #[msg("InvalidChunk")]
+    /// Deserialized chunk does not belong to the reassembly buffer or its
+    /// data does not line up with its header.
+    InvalidChunk,
+
+    #[msg("ChunkAlreadyReceived")]
+    /// Chunk with the same index was already written to the reassembly buffer.
+    ChunkAlreadyReceived,
+
+    #[msg("ChunkChecksumMismatch")]
+    /// Reassembled payload does not match the checksum shared by its chunks.
+    ChunkChecksumMismatch,
+
+    #[msg("TransferAlreadyReassembled")]
+    /// Every chunk of this transfer has already been received.
+    TransferAlreadyReassembled,
+
+    #[msg("TransferNotReassembled")]
+    /// Reassembly buffer is still missing chunks.
+    TransferNotReassembled,
+
+    #[msg("InvalidReceiver")]
+    /// Message recipient is not a program ID, or disagrees with the receiver
+    /// program passed in.
+    InvalidReceiver,
+
+    #[msg("ReceiverDisabled")]
+    /// Receiver program is registered but delivery to it is paused.
+    ReceiverDisabled,
+
+    #[msg("MessageExpired")]
+    /// Message's execution window closed before it was received.
+    MessageExpired,
+
+    #[msg("MessageNotYetExecutable")]
+    /// Message asked for delayed execution and its `execute_after` time has
+    /// not been reached.
+    MessageNotYetExecutable,
+
+    #[msg("MessageAlreadyDelivered")]
+    /// Reassembled payload was already delivered to its receiver program.
+    MessageAlreadyDelivered,
+
 }
@@ -0,0 +1,335 @@
+// protocols/wormhole/evm.rs
+
+//! The EVM side of Wormhole: calldata for the core bridge and token bridge
+//! contracts, and decoding of the events they emit.
+
+use super::{MessageType as WormholeMessageType, WormholeCrossChainMessage};
+use crate::relayer::TokenTransfer;
+use crate::types::{ChainId, CrossChainMessage, MessageType};
+use crate::utility::abi::{self, encode_call, ParamType, Token};
+use crate::utility::ecdsa::EvmAddress;
+use crate::{CCIHSError, CCIHSResult};
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::AnchorSerialize;
+
+pub const PUBLISH_MESSAGE: &str = "publishMessage(uint32,bytes,uint8)";
+pub const TRANSFER_TOKENS_WITH_PAYLOAD: &str =
+    "transferTokensWithPayload(address,uint256,uint16,bytes32,uint32,bytes)";
+pub const COMPLETE_TRANSFER_WITH_PAYLOAD: &str = "completeTransferWithPayload(bytes)";
+pub const LOG_MESSAGE_PUBLISHED: &str = "LogMessagePublished(address,uint64,uint32,bytes,uint8)";
+pub const TRANSFER_REDEEMED: &str = "TransferRedeemed(uint16,bytes32,uint64)";
+
+/// `topics[0]` of an event with the given signature.
+pub fn event_topic(signature: &str) -> [u8; 32] {
+    keccak::hash(signature.as_bytes()).to_bytes()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A call to a Wormhole EVM contract.
+pub enum EvmCall {
+    /// Core bridge. Pay the bridge's `messageFee` as value.
+    PublishMessage {
+        nonce: u32,
+        payload: Vec<u8>,
+        consistency_level: u8,
+    },
+    /// Token bridge. The bridge must be approved for `amount` of `token`.
+    TransferTokensWithPayload {
+        token: EvmAddress,
+        amount: u128,
+        recipient_chain: u16,
+        recipient: [u8; 32],
+        nonce: u32,
+        payload: Vec<u8>,
+    },
+    /// Token bridge. Must be sent by the transfer's recipient contract.
+    CompleteTransferWithPayload { encoded_vm: Vec<u8> },
+}
+
+/// `message` in the envelope the CCIHS program on Solana receives and
+/// redeems, naming the end recipient.
+fn envelope(message: &CrossChainMessage) -> CCIHSResult<Vec<u8>> {
+    WormholeCrossChainMessage {
+        message_type: match message.message_type {
+            MessageType::General => WormholeMessageType::General,
+            MessageType::TokenTransfer => WormholeMessageType::TokenTransfer,
+        },
+        payload: message.payload.clone(),
+        amount: message.amount,
+        token_address: message.token_address,
+        recipient: Some(message.recipient.to_bytes32().to_vec()),
+        destination_chain: Some(message.destination_chain),
+        nonce: message.nonce,
+        timestamp: message.timestamp,
+        expires_at: message.expires_at,
+        execute_after: message.execute_after,
+    }
+    .try_to_vec()
+    .map_err(|e| CCIHSError::SerializationError(e.to_string()))
+}
+
+impl EvmCall {
+    /// The call that sends `message` from an EVM chain to the CCIHS program
+    /// on Solana, with the payload wrapped in a [`WormholeCrossChainMessage`].
+    /// Token transfers go through the token bridge and need the EVM `token`
+    /// being sent; they are addressed to `redeemer`, the program's
+    /// [`redeemer_config`](super::InstructionBuilder::redeemer_config), which
+    /// pays out to `message.recipient` on redemption. Other messages are
+    /// published on the core bridge.
+    ///
+    /// The call must come from the contract registered with the program:
+    /// with `register_foreign_token_emitter` for token transfers, which the
+    /// token bridge records as `msg.sender`, and with `register_emitter`
+    /// for messages, whose emitter is `msg.sender`. The program and the
+    /// [relayer](crate::relayer) reject anything else, so the same calldata
+    /// sent from an EOA produces VAAs that can never be redeemed.
+    pub fn from_message(message: &CrossChainMessage, token: Option<EvmAddress>, redeemer: &Pubkey) -> CCIHSResult<Self> {
+        match message.message_type {
+            MessageType::General => Ok(EvmCall::PublishMessage {
+                nonce: message.nonce,
+                payload: envelope(message)?,
+                consistency_level: message.consistency_level,
+            }),
+            MessageType::TokenTransfer => {
+                if message.amount == 0 {
+                    return Err(CCIHSError::MissingAmount);
+                }
+                if message.destination_chain != ChainId::SOLANA {
+                    return Err(CCIHSError::InvalidChainId);
+                }
+                Ok(EvmCall::TransferTokensWithPayload {
+                    token: token.ok_or(CCIHSError::UnsupportedToken)?,
+                    amount: message.amount as u128,
+                    recipient_chain: message.destination_chain.0,
+                    recipient: redeemer.to_bytes(),
+                    nonce: message.nonce,
+                    payload: envelope(message)?,
+                })
+            }
+        }
+    }
+
+    pub fn calldata(&self) -> Vec<u8> {
+        match self {
+            EvmCall::PublishMessage { nonce, payload, consistency_level } => encode_call(
+                PUBLISH_MESSAGE,
+                &[
+                    Token::Uint(*nonce as u128),
+                    Token::Bytes(payload.clone()),
+                    Token::Uint(*consistency_level as u128),
+                ],
+            ),
+            EvmCall::TransferTokensWithPayload { token, amount, recipient_chain, recipient, nonce, payload } => {
+                encode_call(
+                    TRANSFER_TOKENS_WITH_PAYLOAD,
+                    &[
+                        Token::Address(*token),
+                        Token::Uint(*amount),
+                        Token::Uint(*recipient_chain as u128),
+                        Token::FixedBytes32(*recipient),
+                        Token::Uint(*nonce as u128),
+                        Token::Bytes(payload.clone()),
+                    ],
+                )
+            }
+            EvmCall::CompleteTransferWithPayload { encoded_vm } => {
+                encode_call(COMPLETE_TRANSFER_WITH_PAYLOAD, &[Token::Bytes(encoded_vm.clone())])
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// An EVM event log.
+pub struct EvmLog {
+    /// Contract that emitted the log.
+    pub address: EvmAddress,
+    pub topics: Vec<[u8; 32]>,
+    pub data: Vec<u8>,
+}
+
+impl EvmLog {
+    fn is_event(&self, signature: &str) -> bool {
+        self.topics.first() == Some(&event_topic(signature))
+    }
+
+    fn topic(&self, index: usize) -> CCIHSResult<&[u8; 32]> {
+        self.topics
+            .get(index)
+            .ok_or_else(|| CCIHSError::DeserializationError(format!("log has no topic {}", index)))
+    }
+}
+
+fn uint(token: &Token) -> u128 {
+    match token {
+        Token::Uint(value) => *value,
+        _ => unreachable!("decoded as ParamType::Uint"),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// `LogMessagePublished` of the core bridge.
+pub struct MessagePublished {
+    pub core_bridge: EvmAddress,
+    /// The emitter of the resulting VAA.
+    pub sender: EvmAddress,
+    pub sequence: u64,
+    pub nonce: u32,
+    pub payload: Vec<u8>,
+    pub consistency_level: u8,
+}
+
+impl MessagePublished {
+    /// `None` if `log` is another event.
+    pub fn decode(log: &EvmLog) -> CCIHSResult<Option<Self>> {
+        if !log.is_event(LOG_MESSAGE_PUBLISHED) {
+            return Ok(None);
+        }
+        let mut sender = [0u8; 20];
+        sender.copy_from_slice(&log.topic(1)?[12..]);
+        let tokens = abi::decode(&[ParamType::Uint, ParamType::Uint, ParamType::Bytes, ParamType::Uint], &log.data)?;
+        let out_of_range = || CCIHSError::DeserializationError("LogMessagePublished value out of range".to_string());
+        Ok(Some(Self {
+            core_bridge: log.address,
+            sender,
+            sequence: u64::try_from(uint(&tokens[0])).map_err(|_| out_of_range())?,
+            nonce: u32::try_from(uint(&tokens[1])).map_err(|_| out_of_range())?,
+            payload: match &tokens[2] {
+                Token::Bytes(payload) => payload.clone(),
+                _ => unreachable!("decoded as ParamType::Bytes"),
+            },
+            consistency_level: u8::try_from(uint(&tokens[3])).map_err(|_| out_of_range())?,
+        }))
+    }
+
+    /// The transfer, if this message was published by the token bridge for
+    /// `transferTokensWithPayload`.
+    pub fn token_transfer(&self) -> Option<TokenTransfer> {
+        TokenTransfer::parse(&self.payload).ok()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// `TransferRedeemed` of the token bridge, emitted by
+/// `completeTransferWithPayload`.
+pub struct TransferRedeemed {
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+}
+
+impl TransferRedeemed {
+    /// `None` if `log` is another event.
+    pub fn decode(log: &EvmLog) -> CCIHSResult<Option<Self>> {
+        if !log.is_event(TRANSFER_REDEEMED) {
+            return Ok(None);
+        }
+        let chain = log.topic(1)?;
+        let sequence = log.topic(3)?;
+        Ok(Some(Self {
+            emitter_chain: u16::from_be_bytes([chain[30], chain[31]]),
+            emitter_address: *log.topic(2)?,
+            sequence: u64::from_be_bytes(sequence[24..].try_into().expect("8 bytes")),
+        }))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::types::CrossChainAddress;
+    use crate::utility::encoding::to_hex;
+    use anchor_lang::AnchorDeserialize;
+
+    #[test]
+    fn test_wraps_messages_for_the_solana_program() {
+        let user = Pubkey::new_unique();
+        let redeemer = Pubkey::new_unique();
+        let mut message = CrossChainMessage {
+            message_type: MessageType::TokenTransfer,
+            payload: b"hello".to_vec(),
+            amount: 1_000,
+            token_address: None,
+            sender: CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(0x22)),
+            recipient: CrossChainAddress::Solana(user),
+            source_chain: ChainId::ETHEREUM,
+            destination_chain: ChainId::SOLANA,
+            nonce: 7,
+            timestamp: 1_700_000_000,
+            consistency_level: 1,
+            expires_at: Some(1_700_003_600),
+            execute_after: None,
+            message_id: None,
+        };
+
+        let call = EvmCall::from_message(&message, Some([0x33; 20]), &redeemer).unwrap();
+        let payload = match &call {
+            EvmCall::TransferTokensWithPayload { recipient, payload, .. } => {
+                assert_eq!(*recipient, redeemer.to_bytes());
+                payload.clone()
+            }
+            other => panic!("unexpected call {:?}", other),
+        };
+        let envelope = WormholeCrossChainMessage::try_from_slice(&payload).unwrap();
+        assert_eq!(envelope.payload, b"hello");
+        assert_eq!(envelope.amount, 1_000);
+        assert_eq!(envelope.recipient, Some(user.to_bytes().to_vec()));
+        assert_eq!(envelope.expires_at, Some(1_700_003_600));
+
+        message.message_type = MessageType::General;
+        match EvmCall::from_message(&message, None, &redeemer).unwrap() {
+            EvmCall::PublishMessage { payload, .. } => {
+                let envelope = WormholeCrossChainMessage::try_from_slice(&payload).unwrap();
+                assert_eq!(envelope.message_type, WormholeMessageType::General);
+                assert_eq!(envelope.payload, b"hello");
+            }
+            other => panic!("unexpected call {:?}", other),
+        }
+
+        message.message_type = MessageType::TokenTransfer;
+        message.destination_chain = ChainId::ETHEREUM;
+        assert!(EvmCall::from_message(&message, Some([0x33; 20]), &redeemer).is_err());
+    }
+
+    #[test]
+    fn test_matches_known_encodings() {
+        let call = EvmCall::PublishMessage {
+            nonce: 1,
+            payload: vec![0xab, 0xcd],
+            consistency_level: 15,
+        };
+        assert_eq!(
+            to_hex(&call.calldata()),
+            [
+                "b19a437e".to_string(),
+                format!("{:064x}", 1),
+                format!("{:064x}", 0x60),
+                format!("{:064x}", 15),
+                format!("{:064x}", 2),
+                format!("abcd{}", "0".repeat(60)),
+            ]
+            .concat()
+        );
+        let transfer = EvmCall::CompleteTransferWithPayload { encoded_vm: vec![1] };
+        assert_eq!(to_hex(&transfer.calldata()[..4]), "c3f511c1");
+        assert_eq!(
+            to_hex(&event_topic(LOG_MESSAGE_PUBLISHED)),
+            "6eb224fb001ed210e379b335e35efe88672a8ce935d981a6896b27ffdf52a3b2"
+        );
+
+        let mut sender = [0u8; 32];
+        sender[12..].copy_from_slice(&[0x22; 20]);
+        let log = EvmLog {
+            address: [0x11; 20],
+            topics: vec![event_topic(LOG_MESSAGE_PUBLISHED), sender],
+            data: abi::encode(&[Token::Uint(42), Token::Uint(7), Token::Bytes(b"hi".to_vec()), Token::Uint(1)]),
+        };
+        let published = MessagePublished::decode(&log).unwrap().unwrap();
+        assert_eq!(published.sender, [0x22; 20]);
+        assert_eq!(published.sequence, 42);
+        assert_eq!(published.payload, b"hi");
+        assert!(published.token_transfer().is_none());
+        assert_eq!(TransferRedeemed::decode(&log).unwrap(), None);
+    }
+}
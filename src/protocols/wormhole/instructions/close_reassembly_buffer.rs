@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::protocols::wormhole::state::ReassemblyBuffer;
+use crate::protocols::wormhole::WormholeError;
+
+    /// This instruction releases the rent of a completely reassembled
+    /// [ReassemblyBuffer] to the payer who created it, once the payload has
+    /// been read. The payload is dropped and the account shrunk to its
+    /// header, which stays marked complete so fragments of the transfer
+    /// cannot be redeemed into a new buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `emitter_chain` - Chain the fragments were sent from
+    /// * `transfer_id`   - Transfer ID shared by all fragments of the payload
+    pub fn close_reassembly_buffer_handler(
+        ctx: Context<CloseReassemblyBuffer>,
+        _emitter_chain: u16,
+        _transfer_id: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.reassembly_buffer.data = Vec::new();
+
+        // Done.
+        Ok(())
+    }
+
+#[derive(Accounts)]
+#[instruction(emitter_chain: u16, transfer_id: [u8; 32])]
+pub struct CloseReassemblyBuffer<'info> {
+    #[account(mut)]
+    /// Payer of the first fragment. Receives the released rent.
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = payer,
+        constraint = reassembly_buffer.complete @ WormholeError::TransferNotReassembled,
+        seeds = [
+            ReassemblyBuffer::SEED_PREFIX,
+            &emitter_chain.to_le_bytes()[..],
+            &transfer_id
+        ],
+        bump,
+        realloc = ReassemblyBuffer::CLOSED_SIZE,
+        realloc::payer = payer,
+        realloc::zero = false
+    )]
+    /// Completely reassembled buffer.
+    pub reassembly_buffer: Box<Account<'info, ReassemblyBuffer>>,
+
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use crate::protocols::wormhole::state::{
+    GeneralMessageConfig, ReassemblyBuffer, RegisteredReceiver, SEED_PREFIX_DELIVERY_AUTHORITY,
+};
+use crate::protocols::wormhole::WormholeError;
+use crate::types::{ChainId, MessageId};
+use super::{receiver_instruction_discriminator, DeliveredMessage};
+
+    /// This instruction delivers the payload of a completely reassembled
+    /// [ReassemblyBuffer] to the program named as the recipient of its
+    /// fragments, the way [receive_and_deliver_message_handler](super::receive_and_deliver_message_handler)
+    /// delivers a single message. The recipient must be a 32 byte program ID
+    /// registered with [register_receiver_handler](super::register_receiver_handler).
+    ///
+    /// The receiver's `receive_ccihs_message` instruction is invoked with a
+    /// [DeliveredMessage] whose `sequence` and `vaa_hash` are those of the
+    /// fragment that completed the payload, and these accounts, in order:
+    /// 0. `[signer]` delivery authority PDA of this program
+    /// 1. the `reassembly_buffer` account (read-only)
+    /// 2. every remaining account passed to this instruction, as passed
+    ///
+    /// A payload is delivered once. If the receiver fails, the whole
+    /// transaction fails and the payload can be delivered again. Close the
+    /// buffer with [close_reassembly_buffer_handler](super::close_reassembly_buffer_handler)
+    /// afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `emitter_chain` - Chain the fragments were sent from
+    /// * `transfer_id`   - Transfer ID shared by all fragments of the payload
+    pub fn deliver_reassembled_message_handler<'info>(
+        ctx: Context<'_, '_, '_, 'info, DeliverReassembledMessage<'info>>,
+        _emitter_chain: u16,
+        _transfer_id: [u8; 32],
+    ) -> Result<()> {
+        let buffer = &mut ctx.accounts.reassembly_buffer;
+        require!(!buffer.delivered, WormholeError::MessageAlreadyDelivered);
+        let message = buffer.message().ok_or(WormholeError::TransferNotReassembled)?;
+
+        // Only execute messages inside their execution window.
+        message.check_execution_window(Clock::get()?.unix_timestamp as u64)?;
+
+        let receiver = &ctx.accounts.registered_receiver;
+        receiver.check_recipient(message.recipient.as_deref())?;
+
+        let message_id = MessageId::compute(
+            ChainId(buffer.emitter_chain),
+            &buffer.emitter_address,
+            buffer.sequence,
+            &message.payload,
+        )
+        .to_bytes();
+
+        let delivered = DeliveredMessage {
+            emitter_chain: buffer.emitter_chain,
+            emitter_address: buffer.emitter_address,
+            sequence: buffer.sequence,
+            vaa_hash: buffer.vaa_hash,
+            message_id,
+            message,
+        };
+
+        // Marked before the receiver runs, so it cannot deliver the payload
+        // again from within the CPI.
+        buffer.delivered = true;
+        buffer.exit(&crate::ID)?;
+
+        let mut data = receiver_instruction_discriminator().to_vec();
+        delivered.serialize(&mut data)?;
+
+        let mut account_metas = vec![
+            AccountMeta::new_readonly(ctx.accounts.delivery_authority.key(), true),
+            AccountMeta::new_readonly(ctx.accounts.reassembly_buffer.key(), false),
+        ];
+        let mut account_infos = vec![
+            ctx.accounts.delivery_authority.to_account_info(),
+            ctx.accounts.reassembly_buffer.to_account_info(),
+        ];
+        for account in ctx.remaining_accounts {
+            account_metas.push(if account.is_writable {
+                AccountMeta::new(account.key(), account.is_signer)
+            } else {
+                AccountMeta::new_readonly(account.key(), account.is_signer)
+            });
+            account_infos.push(account.clone());
+        }
+        account_infos.push(ctx.accounts.receiver_program.to_account_info());
+
+        invoke_signed(
+            &Instruction {
+                program_id: receiver.program_id,
+                accounts: account_metas,
+                data,
+            },
+            &account_infos,
+            &[&[SEED_PREFIX_DELIVERY_AUTHORITY, &[ctx.bumps.delivery_authority]]],
+        )?;
+
+        // Done.
+        Ok(())
+    }
+
+#[derive(Accounts)]
+#[instruction(emitter_chain: u16, transfer_id: [u8; 32])]
+pub struct DeliverReassembledMessage<'info> {
+    /// Pays for the transaction. Anyone can deliver a reassembled payload.
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [GeneralMessageConfig::SEED_PREFIX],
+        bump,
+    )]
+    /// Config account. Read-only.
+    pub general_message_config: Account<'info, GeneralMessageConfig>,
+
+    #[account(
+        mut,
+        constraint = reassembly_buffer.complete @ WormholeError::TransferNotReassembled,
+        seeds = [
+            ReassemblyBuffer::SEED_PREFIX,
+            &emitter_chain.to_le_bytes()[..],
+            &transfer_id
+        ],
+        bump,
+    )]
+    /// Completely reassembled buffer. Marked delivered.
+    pub reassembly_buffer: Box<Account<'info, ReassemblyBuffer>>,
+
+    #[account(
+        seeds = [
+            RegisteredReceiver::SEED_PREFIX,
+            receiver_program.key().as_ref()
+        ],
+        bump,
+    )]
+    /// Registration of the receiver program. Read-only.
+    pub registered_receiver: Account<'info, RegisteredReceiver>,
+
+    #[account(executable)]
+    /// CHECK: Receiver program. Must match `registered_receiver`, which is
+    /// derived from its key.
+    pub receiver_program: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [SEED_PREFIX_DELIVERY_AUTHORITY],
+        bump,
+    )]
+    /// CHECK: Delivery authority PDA. Holds no data; only signs the delivery
+    /// CPI so receivers can tell it came from this program.
+    pub delivery_authority: UncheckedAccount<'info>,
+}
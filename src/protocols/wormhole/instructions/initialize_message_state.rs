@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::{CrossChainMessageState, CrossChainMessageStateTrait};
+
+    /// This instruction creates the [CrossChainMessageState] account of the
+    /// signing sender. The account is a PDA derived from the sender's key (see
+    /// [derive_state_address](crate::state::derive_state_address)) and is
+    /// updated every time the sender posts a message or sends tokens. The send
+    /// instructions create it on first use, so calling this up front is
+    /// optional: it lets the sender pay for the account ahead of time.
+    pub fn initialize_message_state_handler(ctx: Context<InitializeMessageState>) -> Result<()> {
+        let state = &mut ctx.accounts.sender_state;
+        **state = CrossChainMessageState::new();
+        state.bump = ctx.bumps.sender_state;
+
+        // Done.
+        Ok(())
+    }
+
+#[derive(Accounts)]
+pub struct InitializeMessageState<'info> {
+    #[account(mut)]
+    /// Sender whose messages are tracked. Pays for the state account.
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            CrossChainMessageState::SEED_PREFIX,
+            payer.key().as_ref()
+        ],
+        bump,
+        space = CrossChainMessageState::MAXIMUM_SIZE
+    )]
+    /// Sender's message state account. Cannot be initialized twice.
+    pub sender_state: Account<'info, CrossChainMessageState>,
+
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
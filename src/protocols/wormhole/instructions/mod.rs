@@ -8,6 +8,13 @@ mod update_relayer_fee;
 mod register_foreign_token_emitter;
 mod send_native_tokens_with_payload;
 mod send_wrapped_tokens_with_payload;
+mod receive_message_chunk;
+mod close_reassembly_buffer;
+mod initialize_message_state;
+mod update_message_state;
+mod register_receiver;
+mod receive_and_deliver_message;
+mod deliver_reassembled_message;
 
 pub use initialize::*;
 pub use register_emitter::*;
@@ -20,4 +27,11 @@ pub use update_relayer_fee::*;
 pub use register_foreign_token_emitter::*;
 pub use send_native_tokens_with_payload::*;
 pub use send_wrapped_tokens_with_payload::*;
+pub use receive_message_chunk::*;
+pub use close_reassembly_buffer::*;
+pub use initialize_message_state::*;
+pub use update_message_state::*;
+pub use register_receiver::*;
+pub use receive_and_deliver_message::*;
+pub use deliver_reassembled_message::*;
 
@@ -0,0 +1,226 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use wormhole_anchor_sdk::wormhole;
+use crate::protocols::wormhole::state::{
+    ForeignEmitter, GeneralMessageConfig, Received, RegisteredReceiver, SEED_PREFIX_DELIVERY_AUTHORITY,
+};
+use crate::protocols::wormhole::{WormholeCrossChainMessage, WormholeError};
+use crate::types::{ChainId, MessageId};
+use crate::MAX_PAYLOAD_SIZE;
+
+/// Name of the instruction every receiver program must expose. Receivers
+/// written with Anchor get it by declaring
+/// `pub fn receive_ccihs_message(ctx, message: DeliveredMessage)`.
+pub const RECEIVER_INSTRUCTION_NAME: &str = "receive_ccihs_message";
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+/// Argument of the receiver program's `receive_ccihs_message` instruction.
+pub struct DeliveredMessage {
+    /// Wormhole chain ID of the sending chain.
+    pub emitter_chain: u16,
+    /// Emitter (sending contract) on the sending chain.
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+    /// Keccak256 hash of the verified Wormhole message.
+    pub vaa_hash: [u8; 32],
+    /// Canonical [`MessageId`] of the message, also stored in the `received`
+    /// account.
+    pub message_id: [u8; 32],
+    pub message: WormholeCrossChainMessage,
+}
+
+/// Anchor instruction discriminator of `receive_ccihs_message`.
+pub fn receiver_instruction_discriminator() -> [u8; 8] {
+    crate::protocols::wormhole::instruction_discriminator(RECEIVER_INSTRUCTION_NAME)
+}
+
+    /// This instruction does what [receive_message_handler](super::receive_message_handler)
+    /// does and then delivers the message to the program named as its
+    /// recipient. The recipient must be a 32 byte program ID registered with
+    /// [register_receiver_handler](super::register_receiver_handler).
+    ///
+    /// The receiver's `receive_ccihs_message` instruction is invoked with a
+    /// [DeliveredMessage] and these accounts, in order:
+    /// 0. `[signer]` delivery authority PDA of this program
+    /// 1. the `received` account (read-only)
+    /// 2. every remaining account passed to this instruction, as passed
+    ///
+    /// If the receiver fails, the whole transaction fails and the message can
+    /// be redeemed again.
+    ///
+    /// # Arguments
+    ///
+    /// * `vaa_hash` - Keccak256 hash of verified Wormhole message
+    pub fn receive_and_deliver_message_handler<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReceiveAndDeliverMessage<'info>>,
+        vaa_hash: [u8; 32],
+    ) -> Result<()> {
+        let posted_message = &ctx.accounts.posted;
+        let message = posted_message.data();
+
+        // Only execute messages inside their execution window.
+        message.check_execution_window(Clock::get()?.unix_timestamp as u64)?;
+
+        require!(
+            message.payload.len() <= MAX_PAYLOAD_SIZE,
+            WormholeError::InvalidMessage
+        );
+
+        let receiver = &ctx.accounts.registered_receiver;
+        receiver.check_recipient(message.recipient.as_deref())?;
+
+        let message_id = MessageId::compute(
+            ChainId(posted_message.emitter_chain()),
+            posted_message.emitter_address(),
+            posted_message.sequence(),
+            &message.payload,
+        )
+        .to_bytes();
+
+        // Save batch ID, keccak256 hash, message ID and message payload.
+        let received = &mut ctx.accounts.received;
+        received.batch_id = posted_message.batch_id();
+        received.wormhole_message_hash = vaa_hash;
+        received.message_id = message_id;
+        received.message = message.payload.clone();
+        received.exit(&crate::ID)?;
+
+        let delivered = DeliveredMessage {
+            emitter_chain: posted_message.emitter_chain(),
+            emitter_address: *posted_message.emitter_address(),
+            sequence: posted_message.sequence(),
+            vaa_hash,
+            message_id,
+            message: message.clone(),
+        };
+
+        let mut data = receiver_instruction_discriminator().to_vec();
+        delivered.serialize(&mut data)?;
+
+        let mut account_metas = vec![
+            AccountMeta::new_readonly(ctx.accounts.delivery_authority.key(), true),
+            AccountMeta::new_readonly(ctx.accounts.received.key(), false),
+        ];
+        let mut account_infos = vec![
+            ctx.accounts.delivery_authority.to_account_info(),
+            ctx.accounts.received.to_account_info(),
+        ];
+        for account in ctx.remaining_accounts {
+            account_metas.push(if account.is_writable {
+                AccountMeta::new(account.key(), account.is_signer)
+            } else {
+                AccountMeta::new_readonly(account.key(), account.is_signer)
+            });
+            account_infos.push(account.clone());
+        }
+        account_infos.push(ctx.accounts.receiver_program.to_account_info());
+
+        invoke_signed(
+            &Instruction {
+                program_id: receiver.program_id,
+                accounts: account_metas,
+                data,
+            },
+            &account_infos,
+            &[&[SEED_PREFIX_DELIVERY_AUTHORITY, &[ctx.bumps.delivery_authority]]],
+        )?;
+
+        // Done.
+        Ok(())
+    }
+
+#[derive(Accounts)]
+#[instruction(vaa_hash: [u8; 32])]
+pub struct ReceiveAndDeliverMessage<'info> {
+    #[account(mut)]
+    /// Payer will initialize the [Received] account.
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [GeneralMessageConfig::SEED_PREFIX],
+        bump,
+    )]
+    /// Config account. Read-only.
+    pub general_message_config: Account<'info, GeneralMessageConfig>,
+
+    // Wormhole program.
+    pub wormhole_program: Program<'info, wormhole::program::Wormhole>,
+
+    #[account(
+        seeds = [
+            wormhole::SEED_PREFIX_POSTED_VAA,
+            &vaa_hash
+        ],
+        bump,
+        seeds::program = wormhole_program
+    )]
+    /// Verified Wormhole message account. Read-only.
+    pub posted: Account<'info, wormhole::PostedVaa<WormholeCrossChainMessage>>,
+
+    #[account(
+        seeds = [
+            ForeignEmitter::SEED_PREFIX,
+            &posted.emitter_chain().to_le_bytes()[..]
+        ],
+        bump,
+        constraint = foreign_emitter.verify(posted.emitter_address()) @ WormholeError::InvalidForeignEmitter
+    )]
+    /// Foreign emitter account. Read-only.
+    pub foreign_emitter: Account<'info, ForeignEmitter>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            Received::SEED_PREFIX,
+            &posted.emitter_chain().to_le_bytes()[..],
+            &posted.sequence().to_le_bytes()[..]
+        ],
+        bump,
+        space = Received::MAXIMUM_SIZE
+    )]
+    /// Received account. Prevents the message from being delivered twice.
+    pub received: Account<'info, Received>,
+
+    #[account(
+        seeds = [
+            RegisteredReceiver::SEED_PREFIX,
+            receiver_program.key().as_ref()
+        ],
+        bump,
+    )]
+    /// Registration of the receiver program. Read-only.
+    pub registered_receiver: Account<'info, RegisteredReceiver>,
+
+    #[account(executable)]
+    /// CHECK: Receiver program. Must match `registered_receiver`, which is
+    /// derived from its key.
+    pub receiver_program: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [SEED_PREFIX_DELIVERY_AUTHORITY],
+        bump,
+    )]
+    /// CHECK: Delivery authority PDA. Holds no data; only signs the delivery
+    /// CPI so receivers can tell it came from this program.
+    pub delivery_authority: UncheckedAccount<'info>,
+
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_receiver_instruction_discriminator() {
+        // sha256("global:receive_ccihs_message")[..8], what Anchor generates
+        // for a receiver's `receive_ccihs_message` instruction.
+        assert_eq!(
+            receiver_instruction_discriminator(),
+            [82, 15, 22, 65, 240, 186, 244, 43]
+        );
+    }
+}
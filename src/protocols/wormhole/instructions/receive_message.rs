@@ -1,13 +1,14 @@
 use anchor_lang::prelude::*;
 use solana_program::entrypoint::HEAP_LENGTH;
 use wormhole_anchor_sdk::{wormhole, token_bridge};
-use crate::types::CCIHSResult;
+use crate::types::{CCIHSResult, ChainId, MessageId};
 use crate::utility::error::CCIHSError;
 use crate::protocols::wormhole::state::{ForeignEmitter, Received};
 use crate::wormhole::GeneralMessageConfig;
 use crate::protocols::wormhole::state::MESSAGE_MAX_LENGTH;
 use crate::protocols::wormhole::WormholeError;
 use crate::MAX_PAYLOAD_SIZE;
+use crate::state::{CrossChainMessageState, CrossChainMessageStateTrait};
 
 
     /// This instruction reads a posted verified Wormhole message and verifies
@@ -22,6 +23,11 @@ use crate::MAX_PAYLOAD_SIZE;
     pub fn receive_message_handler(ctx: Context<ReceiveMessage>, vaa_hash: [u8; 32]) -> Result<()> {
         let posted_message = &ctx.accounts.posted;
 
+        // Only execute messages inside their execution window.
+        posted_message
+            .data()
+            .check_execution_window(Clock::get()?.unix_timestamp as u64)?;
+
         if let message = &posted_message.data().payload {
         //if let message = &posted_message.payload {
         // CrossChainMessage payload cannot be larger than the maximum size allowed.
@@ -34,8 +40,26 @@ use crate::MAX_PAYLOAD_SIZE;
             let received = &mut ctx.accounts.received;
             received.batch_id = posted_message.batch_id();
             received.wormhole_message_hash = vaa_hash;
+            received.message_id = MessageId::compute(
+                ChainId(posted_message.emitter_chain()),
+                posted_message.emitter_address(),
+                posted_message.sequence(),
+                message,
+            )
+            .to_bytes();
             received.message = message.clone();
 
+            // Record the message in the foreign sender's state account, using
+            // the emitter's sequence as the nonce.
+            let sender_state = &mut ctx.accounts.sender_state;
+            if sender_state.message_count == 0 {
+                sender_state.bump = ctx.bumps.sender_state;
+            }
+            sender_state.update_with_message(
+                posted_message.sequence(),
+                Clock::get()?.unix_timestamp,
+            );
+
             // Done
             Ok(())
         } else {
@@ -104,6 +128,22 @@ pub struct ReceiveMessage<'info> {
     /// replay with the same sequence.
     pub received: Account<'info, Received>,
 
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [
+            CrossChainMessageState::SEED_PREFIX,
+            &posted.emitter_chain().to_le_bytes()[..],
+            &posted.emitter_address()[..]
+        ],
+        bump,
+        space = CrossChainMessageState::MAXIMUM_SIZE
+    )]
+    /// Message state of the foreign sender (the emitter). Created on the
+    /// first message received from it, see
+    /// [derive_foreign_state_address](crate::state::derive_foreign_state_address).
+    pub sender_state: Account<'info, CrossChainMessageState>,
+
     /// System program.
     pub system_program: Program<'info, System>,
 }
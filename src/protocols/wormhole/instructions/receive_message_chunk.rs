@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use wormhole_anchor_sdk::wormhole;
+use crate::types::{MessageChunk, payload_checksum};
+use crate::protocols::wormhole::state::{ForeignEmitter, GeneralMessageConfig, ReassemblyBuffer};
+use crate::protocols::wormhole::{WormholeCrossChainMessage, WormholeError};
+
+    /// This instruction reads a posted verified Wormhole message whose payload
+    /// is one fragment of a chunked payload (payload ID == 2), as sent by
+    /// `CCIHSCore::send_chunked_message`, and writes it into the
+    /// [ReassemblyBuffer] for its transfer. Fragments can be redeemed in any
+    /// order, but every one must name the recipient and execution window of
+    /// the first. Once the last missing fragment is written, the checksum of
+    /// the reassembled payload is verified and the buffer is marked complete.
+    /// Deliver the payload to its receiver program with
+    /// [deliver_reassembled_message_handler](super::deliver_reassembled_message_handler),
+    /// or read it from the buffer off-chain, then release the buffer's rent
+    /// with [close_reassembly_buffer_handler](super::close_reassembly_buffer_handler).
+    ///
+    /// See [MessageChunk] for deserialization implementation.
+    ///
+    /// # Arguments
+    ///
+    /// * `vaa_hash`    - Keccak256 hash of verified Wormhole message
+    /// * `transfer_id` - Transfer ID shared by all fragments of the payload
+    pub fn receive_message_chunk_handler(
+        ctx: Context<ReceiveMessageChunk>,
+        vaa_hash: [u8; 32],
+        transfer_id: [u8; 32],
+    ) -> Result<()> {
+        let posted_message = &ctx.accounts.posted;
+        let envelope = posted_message.data();
+        let chunk = MessageChunk::try_from_slice(&envelope.payload)
+            .map_err(|_| WormholeError::InvalidChunk)?;
+        let chunk = &chunk;
+
+        require!(
+            chunk.transfer_id == transfer_id && chunk.is_well_formed(),
+            WormholeError::InvalidChunk
+        );
+
+        let emitter_chain = posted_message.emitter_chain();
+        let emitter_address = posted_message.emitter_address();
+        let buffer = &mut ctx.accounts.reassembly_buffer;
+        require!(!buffer.complete, WormholeError::TransferAlreadyReassembled);
+        require!(
+            buffer.accepts(emitter_chain, emitter_address, envelope, chunk),
+            WormholeError::InvalidChunk
+        );
+        require!(!buffer.has_chunk(chunk.index), WormholeError::ChunkAlreadyReceived);
+        if buffer.envelope.is_none() {
+            buffer.payer = ctx.accounts.payer.key();
+        }
+
+        if buffer.write_chunk(emitter_chain, emitter_address, envelope, chunk) {
+            require!(
+                payload_checksum(&buffer.data) == buffer.checksum,
+                WormholeError::ChunkChecksumMismatch
+            );
+            buffer.sequence = posted_message.sequence();
+            buffer.vaa_hash = vaa_hash;
+            buffer.complete = true;
+
+            msg!(
+                "ReceiveMessageChunk :: transfer {:?} reassembled ({} bytes)",
+                transfer_id,
+                buffer.total_length
+            );
+        }
+
+        // Done.
+        Ok(())
+    }
+
+#[derive(Accounts)]
+#[instruction(vaa_hash: [u8; 32], transfer_id: [u8; 32])]
+pub struct ReceiveMessageChunk<'info> {
+    #[account(mut)]
+    /// Payer will initialize the reassembly buffer on the first fragment.
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [GeneralMessageConfig::SEED_PREFIX],
+        bump,
+    )]
+    /// Config account. Read-only.
+    pub general_message_config: Account<'info, GeneralMessageConfig>,
+
+    // Wormhole program.
+    pub wormhole_program: Program<'info, wormhole::program::Wormhole>,
+
+    #[account(
+        seeds = [
+            wormhole::SEED_PREFIX_POSTED_VAA,
+            &vaa_hash
+        ],
+        bump,
+        seeds::program = wormhole_program
+    )]
+    /// Verified Wormhole message account carrying one fragment. Read-only.
+    pub posted: Account<'info, wormhole::PostedVaa<WormholeCrossChainMessage>>,
+
+    #[account(
+        seeds = [
+            ForeignEmitter::SEED_PREFIX,
+            &posted.emitter_chain().to_le_bytes()[..]
+        ],
+        bump,
+        constraint = foreign_emitter.verify(posted.emitter_address()) @ WormholeError::InvalidForeignEmitter
+    )]
+    /// Foreign emitter account. The posted message's `emitter_address` must
+    /// agree with the one we have registered for this message's `emitter_chain`
+    /// (chain ID). Read-only.
+    pub foreign_emitter: Account<'info, ForeignEmitter>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [
+            ReassemblyBuffer::SEED_PREFIX,
+            &posted.emitter_chain().to_le_bytes()[..],
+            &transfer_id
+        ],
+        bump,
+        space = ReassemblyBuffer::MAXIMUM_SIZE
+    )]
+    /// Reassembly buffer for this transfer. Created by the first fragment to
+    /// arrive, whichever index it has.
+    pub reassembly_buffer: Box<Account<'info, ReassemblyBuffer>>,
+
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
@@ -12,13 +12,7 @@ use anchor_spl::{
 use crate::protocols::wormhole::WormholeCrossChainMessage;
 use crate::protocols::wormhole::PostedWormholeCrossChainMessage;
 
-/// AKA `b"bridged"`.
-pub const SEED_PREFIX_BRIDGED: &[u8; 7] = b"bridged";
-/// AKA `b"tmp"`.
-pub const SEED_PREFIX_TMP: &[u8; 3] = b"tmp";
-
-/// AKA `b"general_message_config"`.
-pub const SEED_PREFIX_GENERAL_MESSAGE_CONFIG: &[u8; 18] = b"general_message_config";
+use super::SEED_PREFIX_TMP;
 
 pub fn redeem_native_transfer_with_payload_handler(
     ctx: Context<RedeemNativeTransferWithPayload>,
@@ -39,6 +33,11 @@ pub fn redeem_native_transfer_with_payload_handler(
     // Deserialize the VAA payload into WormholeCrossChainMessage
     let wormhole_message: WormholeCrossChainMessage = ctx.accounts.vaa.message().data();
 
+    // Only redeem transfers inside their execution window. Expired transfers
+    // are never redeemed: their tokens stay in Token Bridge custody, and
+    // nothing in CCIHS returns them to the sender.
+    wormhole_message.check_execution_window(Clock::get()?.unix_timestamp as u64)?;
+
     // Extract the recipient from the WormholeCrossChainMessage
     let recipient = match wormhole_message.recipient {
         Some(recipient_bytes) if recipient_bytes.len() == 32 => recipient_bytes,
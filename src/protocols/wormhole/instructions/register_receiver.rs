@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use crate::protocols::wormhole::state::{GeneralMessageConfig, RegisteredReceiver};
+use crate::protocols::wormhole::error::WormholeError;
+
+    /// This instruction registers a program that received messages can be
+    /// delivered to by CPI, or enables/disables an existing registration.
+    /// This instruction is owner-only.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`        - `RegisterReceiver` context
+    /// * `program_id` - Receiver program ID
+    /// * `enabled`    - Whether messages are delivered to the program
+    pub fn register_receiver_handler(
+        ctx: Context<RegisterReceiver>,
+        program_id: Pubkey,
+        enabled: bool,
+    ) -> Result<()> {
+        // CCIHS cannot deliver to itself.
+        require!(
+            program_id != Pubkey::default() && program_id != crate::ID,
+            WormholeError::InvalidReceiver
+        );
+
+        let receiver = &mut ctx.accounts.registered_receiver;
+        receiver.program_id = program_id;
+        receiver.enabled = enabled;
+
+        // Done.
+        Ok(())
+    }
+
+#[derive(Accounts)]
+#[instruction(program_id: Pubkey)]
+pub struct RegisterReceiver<'info> {
+    #[account(mut)]
+    /// Owner of the program set in the [`GeneralMessageConfig`] account.
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = owner,
+        seeds = [GeneralMessageConfig::SEED_PREFIX],
+        bump
+    )]
+    /// Config account. Read-only.
+    pub general_message_config: Account<'info, GeneralMessageConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [
+            RegisteredReceiver::SEED_PREFIX,
+            program_id.as_ref()
+        ],
+        bump,
+        space = RegisteredReceiver::MAXIMUM_SIZE
+    )]
+    /// Registered receiver account. Created on first registration and
+    /// overwritten afterwards.
+    pub registered_receiver: Account<'info, RegisteredReceiver>,
+
+    /// System program.
+    pub system_program: Program<'info, System>,
+}
@@ -1,10 +1,11 @@
 use anchor_lang::prelude::*;
 use wormhole_anchor_sdk::{wormhole, token_bridge};
-use crate::types::CCIHSResult;
+use crate::types::{CCIHSResult, ExecutionWindow};
 use crate::utility::error::CCIHSError;
 use crate::protocols::wormhole::state::{GeneralMessageConfig, WormholeEmitter};
 use crate::protocols::wormhole::error::WormholeError;
 use crate::protocols::wormhole::{MessageType, WormholeCrossChainMessage};
+use crate::state::{CrossChainMessageState, CrossChainMessageStateTrait};
 
  /// This instruction posts a Wormhole message of some arbitrary size
     /// in the form of bytes ([Vec<u8>]). The message is encoded as
@@ -18,8 +19,20 @@ use crate::protocols::wormhole::{MessageType, WormholeCrossChainMessage};
     ///
     /// # Arguments
     ///
-    /// * `message` - Arbitrary message to send out
-    pub fn send_message_handler(ctx: Context<SendMessage>, message: Vec<u8>) -> Result<()> {
+    /// * `message`       - Arbitrary message to send out
+    /// * `expires_at`    - Unix time after which the message must not be executed
+    /// * `execute_after` - Unix time before which the message must not be executed
+    pub fn send_message_handler(
+        ctx: Context<SendMessage>,
+        message: Vec<u8>,
+        expires_at: Option<u64>,
+        execute_after: Option<u64>,
+    ) -> Result<()> {
+        require!(
+            ExecutionWindow::new(expires_at, execute_after).is_valid(),
+            WormholeError::InvalidMessage
+        );
+
         // If Wormhole requires a fee before posting a message, we need to
         // transfer lamports to the fee collector. Otherwise
         // `wormhole::post_message` will fail.
@@ -72,10 +85,16 @@ use crate::protocols::wormhole::{MessageType, WormholeCrossChainMessage};
             destination_chain: None, // Set appropriate value or pass as parameter
             nonce: general_message_config.batch_id, // Set appropriate value or generate
             timestamp: Clock::get()?.unix_timestamp,
+            expires_at,
+            execute_after,
             //consistency_level: general_message_config.finality.try_into().unwrap(),
         };
 
 
+        // Sequence of the message about to be posted. Read before the CPI,
+        // which increments the sequence tracker.
+        let sequence = ctx.accounts.wormhole_sequence.next_value();
+
         wormhole::post_message(
             CpiContext::new_with_signer(
                 ctx.accounts.wormhole_program.to_account_info(),
@@ -104,6 +123,16 @@ use crate::protocols::wormhole::{MessageType, WormholeCrossChainMessage};
             general_message_config.finality.try_into().unwrap(),
         )?;
 
+        // Record the message in the sender's state account. The Wormhole
+        // sequence is used as the nonce since it increases with every message
+        // posted by this program's emitter.
+        let timestamp = ctx.accounts.clock.unix_timestamp;
+        let sender_state = &mut ctx.accounts.sender_state;
+        if sender_state.message_count == 0 {
+            sender_state.bump = ctx.bumps.sender_state;
+        }
+        sender_state.update_with_message(sequence, timestamp);
+
         // Done.
         Ok(())
     }
@@ -172,6 +201,21 @@ pub struct SendMessage<'info> {
     /// account be mutable.
     pub wormhole_message: UncheckedAccount<'info>,
 
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [
+            CrossChainMessageState::SEED_PREFIX,
+            payer.key().as_ref()
+        ],
+        bump,
+        space = CrossChainMessageState::MAXIMUM_SIZE
+    )]
+    /// Payer's message state account. Created on the payer's first message
+    /// unless [initialize_message_state_handler](super::initialize_message_state_handler)
+    /// already created it, so senders need no extra setup.
+    pub sender_state: Account<'info, CrossChainMessageState>,
+
     /// System program.
     pub system_program: Program<'info, System>,
 
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 use wormhole_anchor_sdk::{wormhole, token_bridge};
-use crate::types::CCIHSResult;
+use crate::types::{CCIHSResult, ExecutionWindow};
 use crate::utility::error::CCIHSError;
 use crate::wormhole::GeneralMessageConfig;
 use crate::wormhole::WormholeError;
@@ -10,11 +10,9 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount},
 };
 use crate::protocols::wormhole::WormholeCrossChainMessage;
+use crate::state::{CrossChainMessageState, CrossChainMessageStateTrait};
 
-/// AKA `b"bridged"`.
-pub const SEED_PREFIX_BRIDGED: &[u8; 7] = b"bridged";
-/// AKA `b"tmp"`.
-pub const SEED_PREFIX_TMP: &[u8; 3] = b"tmp";
+use super::{SEED_PREFIX_BRIDGED, SEED_PREFIX_TMP};
 
 pub fn send_native_tokens_with_payload(
     ctx: Context<SendNativeTokensWithPayload>,
@@ -24,7 +22,14 @@ pub fn send_native_tokens_with_payload(
     recipient_chain: u16,
     message: WormholeCrossChainMessage,
     content: Vec<u8>,
+    expires_at: Option<u64>,
+    execute_after: Option<u64>,
 ) -> Result<()> {
+    require!(
+        ExecutionWindow::new(expires_at, execute_after).is_valid(),
+        WormholeError::InvalidMessage
+    );
+
     // Token Bridge program truncates amounts to 8 decimals, so there will
     // be a residual amount if decimals of SPL is >8. We need to take into
     // account how much will actually be bridged.
@@ -101,9 +106,15 @@ let payload = WormholeCrossChainMessage {
     destination_chain: Some(recipient_chain),
     nonce: batch_id,
     timestamp: ctx.accounts.clock.unix_timestamp as u64,
+    expires_at,
+    execute_after,
     //consistency_level: ctx.accounts.wormhole_bridge.config.finality,
 }.try_to_vec()?;
 
+    // Sequence of the transfer message about to be posted. Read before the
+    // CPI, which increments the Token Bridge's sequence tracker.
+    let sequence = ctx.accounts.token_bridge_sequence.next_value();
+
     // Bridge native token with encoded payload.
     token_bridge::transfer_native_with_payload(
         CpiContext::new_with_signer(
@@ -148,6 +159,14 @@ let payload = WormholeCrossChainMessage {
         &ctx.program_id.key(),
     )?;
 
+    // Record the transfer in the sender's state account, using the Token
+    // Bridge sequence as the nonce.
+    let sender_state = &mut ctx.accounts.sender_state;
+    if sender_state.message_count == 0 {
+        sender_state.bump = ctx.bumps.sender_state;
+    }
+    sender_state.update_with_message(sequence, ctx.accounts.clock.unix_timestamp);
+
     // Finish instruction by closing tmp_token_account.
     anchor_spl::token::close_account(CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
@@ -303,6 +322,20 @@ pub struct SendNativeTokensWithPayload<'info> {
     /// Wormhole fee collector. Mutable.
     pub wormhole_fee_collector: Account<'info, wormhole::FeeCollector>,
 
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [
+            CrossChainMessageState::SEED_PREFIX,
+            payer.key().as_ref()
+        ],
+        bump,
+        space = CrossChainMessageState::MAXIMUM_SIZE
+    )]
+    /// Payer's message state account, created on the payer's first transfer
+    /// or message. Mutable.
+    pub sender_state: Box<Account<'info, CrossChainMessageState>>,
+
     /// System program.
     pub system_program: Program<'info, System>,
 
@@ -10,6 +10,8 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount},
 };
 use crate::protocols::wormhole::WormholeCrossChainMessage;
+use crate::state::{CrossChainMessageState, CrossChainMessageStateTrait};
+use super::{SEED_PREFIX_BRIDGED, SEED_PREFIX_TMP};
 
 
 #[derive(Accounts)]
@@ -155,6 +157,20 @@ pub struct SendWrappedTokensWithPayload<'info> {
     /// Wormhole fee collector. Mutable.
     pub wormhole_fee_collector: Account<'info, wormhole::FeeCollector>,
 
+    #[account(
+        init_if_needed,
+        payer = payer,
+        seeds = [
+            CrossChainMessageState::SEED_PREFIX,
+            payer.key().as_ref()
+        ],
+        bump,
+        space = CrossChainMessageState::MAXIMUM_SIZE
+    )]
+    /// Payer's message state account, created on the payer's first transfer
+    /// or message. Mutable.
+    pub sender_state: Box<Account<'info, CrossChainMessageState>>,
+
     /// System program.
     pub system_program: Program<'info, System>,
 
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::state::{CrossChainMessageState, CrossChainMessageStateTrait};
+
+    /// This instruction records a message sent by the signing sender outside
+    /// of [send_message_handler](super::send_message_handler), for example
+    /// through another bridge. `last_nonce` only moves forward, while
+    /// `message_count` and `last_message_timestamp` are updated on every call.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - Nonce of the message being recorded
+    pub fn update_message_state_handler(ctx: Context<UpdateMessageState>, nonce: u64) -> Result<()> {
+        let timestamp = Clock::get()?.unix_timestamp;
+        ctx.accounts.sender_state.update_with_message(nonce, timestamp);
+
+        // Done.
+        Ok(())
+    }
+
+#[derive(Accounts)]
+pub struct UpdateMessageState<'info> {
+    /// Sender whose messages are tracked.
+    pub sender: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            CrossChainMessageState::SEED_PREFIX,
+            sender.key().as_ref()
+        ],
+        bump = sender_state.bump,
+    )]
+    /// Sender's message state account, created by
+    /// [initialize_message_state_handler](super::initialize_message_state_handler).
+    pub sender_state: Account<'info, CrossChainMessageState>,
+}
@@ -1,4 +1,6 @@
 use super::{chain::ChainId, CCIHSResult};
+use crate::protocols::wormhole::WormholeError;
+use crate::types::ExecutionWindow;
 //use solana_program::pubkey::Pubkey;
 use anchor_lang::{prelude::Pubkey, AnchorDeserialize, AnchorSerialize};
 use std::io;
@@ -31,10 +33,16 @@ pub enum CrossChainPayload {
     Message { content: Vec<u8> },
 }
 
-#[cfg_attr(feature = "native", derive(BorshSerialize, BorshDeserialize))]
-#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
 #[derive(Clone, Debug, PartialEq)]
 //#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+/// Envelope of every message this program publishes and redeems.
+///
+/// Encoded as (all integers big endian, options as a `0`/`1` flag byte
+/// followed by the value when `1`):
+/// `message_type: u8 | payload length: u16 | payload | amount: u64 |
+/// token_address: Option<[u8; 32]> | recipient: Option<length: u16 | bytes> |
+/// destination_chain: Option<u16> | nonce: u32 | timestamp: u64 |
+/// expires_at: Option<u64> | execute_after: Option<u64>`.
 pub struct WormholeCrossChainMessage {
     pub message_type: MessageType,
     pub payload: Vec<u8>,
@@ -47,8 +55,14 @@ pub struct WormholeCrossChainMessage {
     pub nonce: u32,
     pub timestamp: u64,
     //pub consistency_level: u8,
+    /// Unix time after which the message must not be executed. `None`
+    /// never expires.
+    pub expires_at: Option<u64>,
+    /// Unix time before which the message must not be executed.
+    pub execute_after: Option<u64>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MessageType {
     General,
     TokenTransfer,
@@ -88,14 +102,27 @@ impl WormholeCrossChainMessage {
             //source_chain,
             destination_chain,
             nonce: 0, // This should be generated
-            timestamp: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
+            timestamp: 0, // Set by the pre-dispatch hook from the injected clock
            // consistency_level,
+            expires_at: None,
+            execute_after: None,
         }
     }
 
+    pub fn execution_window(&self) -> ExecutionWindow {
+        ExecutionWindow::new(self.expires_at, self.execute_after)
+    }
+
+    /// Checks that `now` lies in the message's execution window. Messages
+    /// executed too early can be redeemed again later; expired ones never.
+    pub fn check_execution_window(&self, now: u64) -> Result<()> {
+        let window = self.execution_window();
+        require!(!window.is_expired(now), WormholeError::MessageExpired);
+        require!(!window.is_scheduled_after(now), WormholeError::MessageNotYetExecutable);
+        Ok(())
+    }
+}
+
     
 // impl AnchorSerialize for CrossChainMessage {
 //     fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
@@ -150,86 +177,142 @@ impl WormholeCrossChainMessage {
 //     }
 // }
 
-    impl AnchorSerialize for WormholeCrossChainMessage {
-        fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
-            // Serialize message type
-            match self.message_type {
-                MessageType::General => 0u8.serialize(writer)?,
-                MessageType::TokenTransfer => 1u8.serialize(writer)?,
+impl AnchorSerialize for WormholeCrossChainMessage {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        if self.payload.len() > MAX_PAYLOAD_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("payload exceeds {MAX_PAYLOAD_LENGTH} bytes"),
+            ));
+        }
+        let message_type: u8 = match self.message_type {
+            MessageType::General => 0,
+            MessageType::TokenTransfer => 1,
+        };
+        writer.write_all(&[message_type])?;
+        writer.write_all(&(self.payload.len() as u16).to_be_bytes())?;
+        writer.write_all(&self.payload)?;
+        writer.write_all(&self.amount.to_be_bytes())?;
+        match &self.token_address {
+            Some(token) => {
+                writer.write_all(&[1])?;
+                writer.write_all(token.as_ref())?;
             }
-
-            // Serialize payload
-            if self.payload.len() > MAX_PAYLOAD_LENGTH {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!("payload exceeds {MAX_PAYLOAD_LENGTH} bytes"),
-                ));
+            None => writer.write_all(&[0])?,
+        }
+        match &self.recipient {
+            Some(recipient) => {
+                let length = u16::try_from(recipient.len())
+                    .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "recipient too long"))?;
+                writer.write_all(&[1])?;
+                writer.write_all(&length.to_be_bytes())?;
+                writer.write_all(recipient)?;
+            }
+            None => writer.write_all(&[0])?,
+        }
+        match self.destination_chain {
+            Some(chain) => {
+                writer.write_all(&[1])?;
+                writer.write_all(&chain.0.to_be_bytes())?;
             }
-            (self.payload.len() as u16).to_be_bytes().serialize(writer)?;
-            writer.write_all(&self.payload)?;
-
-            // Serialize other fields
-            self.amount.serialize(writer)?;
-            match &self.token_address {
-                Some(addr) => {
-                    1u8.serialize(writer)?; // Indicator for Some
-                    addr.serialize(writer)?;
+            None => writer.write_all(&[0])?,
+        }
+        writer.write_all(&self.nonce.to_be_bytes())?;
+        writer.write_all(&self.timestamp.to_be_bytes())?;
+        for value in [self.expires_at, self.execute_after] {
+            match value {
+                Some(value) => {
+                    writer.write_all(&[1])?;
+                    writer.write_all(&value.to_be_bytes())?;
                 }
-                None => 0u8.serialize(writer)?, // Indicator for None
+                None => writer.write_all(&[0])?,
             }
-            self.sender.serialize(writer)?;
-            self.recipient.serialize(writer)?;
-            self.source_chain.serialize(writer)?;
-            self.destination_chain.serialize(writer)?;
-            self.nonce.serialize(writer)?;
-            self.timestamp.serialize(writer)?;
-            self.consistency_level.serialize(writer)
         }
+        Ok(())
+    }
+}
+
+fn read_array<R: io::Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Reads the flag byte of an optional value.
+fn read_flag<R: io::Read>(reader: &mut R) -> io::Result<bool> {
+    match read_array::<R, 1>(reader)?[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidData, "invalid option flag")),
+    }
+}
+
+impl AnchorDeserialize for WormholeCrossChainMessage {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        Self::deserialize_reader(buf)
     }
 
-    impl AnchorDeserialize for WormholeCrossChainMessage {
-        fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
-            let message_type = match u8::deserialize(buf)? {
-                0 => MessageType::General,
-                1 => MessageType::TokenTransfer,
-                _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid message type")),
-            };
-
-            let payload_len = u16::deserialize(buf)? as usize;
-            if payload_len > MAX_PAYLOAD_LENGTH {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    format!("payload exceeds {MAX_PAYLOAD_LENGTH} bytes"),
-                ));
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        let message_type = match read_array::<R, 1>(reader)?[0] {
+            0 => MessageType::General,
+            1 => MessageType::TokenTransfer,
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid message type")),
+        };
+
+        let payload_len = u16::from_be_bytes(read_array(reader)?) as usize;
+        if payload_len > MAX_PAYLOAD_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("payload exceeds {MAX_PAYLOAD_LENGTH} bytes"),
+            ));
+        }
+        let mut payload = vec![0u8; payload_len];
+        reader.read_exact(&mut payload)?;
+
+        let amount = u64::from_be_bytes(read_array(reader)?);
+        let token_address = match read_flag(reader)? {
+            true => Some(Pubkey::new_from_array(read_array(reader)?)),
+            false => None,
+        };
+        let recipient = match read_flag(reader)? {
+            true => {
+                let length = u16::from_be_bytes(read_array(reader)?) as usize;
+                let mut recipient = vec![0u8; length];
+                reader.read_exact(&mut recipient)?;
+                Some(recipient)
             }
-            let mut payload = vec![0u8; payload_len];
-            buf.read_exact(&mut payload)?;
-
-            let amount = u64::deserialize(buf)?;
-            let token_address = match u8::deserialize(buf)? {
-                0 => None,
-                1 => Some(Pubkey::deserialize(buf)?),
-                _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid token address indicator")),
-            };
-
-            Ok(CrossChainMessage {
-                message_type,
-                payload,
-                amount,
-                token_address,
-                sender: CrossChainAddress::deserialize(buf)?,
-                recipient: CrossChainAddress::deserialize(buf)?,
-                source_chain: ChainId::deserialize(buf)?,
-                destination_chain: ChainId::deserialize(buf)?,
-                nonce: u32::deserialize(buf)?,
-                timestamp: u64::deserialize(buf)?,
-                consistency_level: u8::deserialize(buf)?,
+            false => None,
+        };
+        let destination_chain = match read_flag(reader)? {
+            true => Some(ChainId(u16::from_be_bytes(read_array(reader)?))),
+            false => None,
+        };
+        let nonce = u32::from_be_bytes(read_array(reader)?);
+        let timestamp = u64::from_be_bytes(read_array(reader)?);
+        let mut optional_u64 = || -> io::Result<Option<u64>> {
+            Ok(match read_flag(reader)? {
+                true => Some(u64::from_be_bytes(read_array(reader)?)),
+                false => None,
             })
-        }
+        };
+        let expires_at = optional_u64()?;
+        let execute_after = optional_u64()?;
+
+        Ok(WormholeCrossChainMessage {
+            message_type,
+            payload,
+            amount,
+            token_address,
+            recipient,
+            destination_chain,
+            nonce,
+            timestamp,
+            expires_at,
+            execute_after,
+        })
     }
 }
 
-
 pub type PostedWormholeCrossChainMessage = token_bridge::PostedTransferWith<WormholeCrossChainMessage>;
 
 #[cfg_attr(feature = "native", derive(BorshSerialize, BorshDeserialize))]
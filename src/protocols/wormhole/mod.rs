@@ -3,9 +3,25 @@ mod state;
 mod error;  
 mod instructions;
 mod message;
+mod client;
+mod decode;
+mod vaa;
+mod relayer;
+mod evm;
 
 pub use adapter::WormholeAdapter;
 pub use state::*;
 pub use error::WormholeError;
 pub use instructions::*;
-pub use message::{WormholeCrossChainMessage, MessageType, PostedWormholeCrossChainMessage};
\ No newline at end of file
+pub use message::{WormholeCrossChainMessage, MessageType, PostedWormholeCrossChainMessage};
+pub use decode::DecodedAccount;
+pub use client::{InstructionBuilder, PostedVaaInfo, WrappedToken, instruction_discriminator, unsigned_transaction};
+pub use vaa::{ParsedVaa, GuardianSignature};
+pub use relayer::{
+    DeliveryInstruction, DeliveryPrice, DeliveryRequest, DeliveryVaa, EvmExecutionInfo, MessageKey, VaaKey,
+    DELIVERY_INSTRUCTION_PAYLOAD_ID, VAA_KEY_TYPE,
+};
+pub use evm::{
+    event_topic, EvmCall, EvmLog, MessagePublished, TransferRedeemed, COMPLETE_TRANSFER_WITH_PAYLOAD,
+    LOG_MESSAGE_PUBLISHED, PUBLISH_MESSAGE, TRANSFER_REDEEMED, TRANSFER_TOKENS_WITH_PAYLOAD,
+};
\ No newline at end of file
@@ -0,0 +1,426 @@
+// protocols/wormhole/relayer.rs
+
+//! Delivery instructions in the wire format of Wormhole's generic relayer.
+//! A [`DeliveryInstruction`] names the target, the gas to forward and where
+//! to refund unused gas.
+//!
+//! Wormhole delivery providers only act on instructions emitted by a
+//! WormholeRelayer contract, and there is none on Solana, so this module
+//! only encodes, quotes and decodes: it cannot request a delivery from
+//! Solana. On the destination, [`DeliveryVaa`] decodes delivery VAAs.
+
+use super::ParsedVaa;
+use crate::utility::abi::{self, Token};
+use crate::{CCIHSError, CCIHSResult};
+
+/// First byte of an encoded [`DeliveryInstruction`].
+pub const DELIVERY_INSTRUCTION_PAYLOAD_ID: u8 = 1;
+/// Message key type of a [`VaaKey`].
+pub const VAA_KEY_TYPE: u8 = 1;
+/// `ExecutionInfoVersion.EVM_V0`.
+const EVM_EXECUTION_INFO_VERSION: u8 = 0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Points at another VAA the delivery provider must hand to the target
+/// along with the delivery.
+pub struct VaaKey {
+    pub chain: u16,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MessageKey {
+    Vaa(VaaKey),
+    /// A key type this crate does not interpret, kept as is.
+    Other { key_type: u8, data: Vec<u8> },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Execution parameters for an EVM target.
+pub struct EvmExecutionInfo {
+    pub gas_limit: u128,
+    /// Refunded per unit of gas left unused, in the target's native token.
+    pub target_chain_refund_per_gas_unused: u128,
+}
+
+impl EvmExecutionInfo {
+    /// ABI encoded `(uint8 version, uint256 gasLimit, uint256 refundPerGasUnused)`.
+    pub fn encode(&self) -> Vec<u8> {
+        abi::encode(&[
+            Token::Uint(EVM_EXECUTION_INFO_VERSION as u128),
+            Token::Uint(self.gas_limit),
+            Token::Uint(self.target_chain_refund_per_gas_unused),
+        ])
+    }
+
+    pub fn decode(bytes: &[u8]) -> CCIHSResult<Self> {
+        if bytes.len() != 96 {
+            return Err(CCIHSError::DeserializationError(format!(
+                "execution info is {} bytes, expected 96",
+                bytes.len()
+            )));
+        }
+        let words: Vec<u128> = bytes.chunks(32).map(word_to_u128).collect::<CCIHSResult<_>>()?;
+        if words[0] != EVM_EXECUTION_INFO_VERSION as u128 {
+            return Err(CCIHSError::DeserializationError(format!(
+                "unsupported execution info version {}",
+                words[0]
+            )));
+        }
+        Ok(Self {
+            gas_limit: words[1],
+            target_chain_refund_per_gas_unused: words[2],
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Payload of a delivery request VAA. Addresses are Wormhole 32 byte
+/// universal addresses; receiver values are in the target's native token.
+pub struct DeliveryInstruction {
+    pub target_chain: u16,
+    pub target_address: [u8; 32],
+    pub payload: Vec<u8>,
+    pub requested_receiver_value: u128,
+    pub extra_receiver_value: u128,
+    /// Encoded execution parameters, see [`EvmExecutionInfo`].
+    pub encoded_execution_info: Vec<u8>,
+    pub refund_chain: u16,
+    pub refund_address: [u8; 32],
+    pub refund_delivery_provider: [u8; 32],
+    pub source_delivery_provider: [u8; 32],
+    pub sender_address: [u8; 32],
+    pub message_keys: Vec<MessageKey>,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> CCIHSResult<&'a [u8]> {
+        let end = self.offset + length;
+        let bytes = self.bytes.get(self.offset..end).ok_or_else(|| {
+            CCIHSError::DeserializationError(format!("delivery instruction truncated at byte {}", self.offset))
+        })?;
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> CCIHSResult<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> CCIHSResult<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> CCIHSResult<u16> {
+        Ok(u16::from_be_bytes(self.array()?))
+    }
+
+    /// A `uint256` that must fit in 128 bits.
+    fn u256(&mut self) -> CCIHSResult<u128> {
+        word_to_u128(self.take(32)?)
+    }
+
+    /// Bytes with a `u32` length prefix.
+    fn bytes(&mut self) -> CCIHSResult<Vec<u8>> {
+        let length = u32::from_be_bytes(self.array()?) as usize;
+        Ok(self.take(length)?.to_vec())
+    }
+}
+
+fn word_to_u128(word: &[u8]) -> CCIHSResult<u128> {
+    if word[..16].iter().any(|&byte| byte != 0) {
+        return Err(CCIHSError::DeserializationError("uint256 value exceeds 128 bits".to_string()));
+    }
+    let mut low = [0u8; 16];
+    low.copy_from_slice(&word[16..]);
+    Ok(u128::from_be_bytes(low))
+}
+
+fn write_u256(output: &mut Vec<u8>, value: u128) {
+    output.extend_from_slice(&[0u8; 16]);
+    output.extend_from_slice(&value.to_be_bytes());
+}
+
+fn write_bytes(output: &mut Vec<u8>, bytes: &[u8]) -> CCIHSResult<()> {
+    let length = u32::try_from(bytes.len())
+        .map_err(|_| CCIHSError::SerializationError("field longer than u32::MAX bytes".to_string()))?;
+    output.extend_from_slice(&length.to_be_bytes());
+    output.extend_from_slice(bytes);
+    Ok(())
+}
+
+impl DeliveryInstruction {
+    pub fn encode(&self) -> CCIHSResult<Vec<u8>> {
+        let mut output = vec![DELIVERY_INSTRUCTION_PAYLOAD_ID];
+        output.extend_from_slice(&self.target_chain.to_be_bytes());
+        output.extend_from_slice(&self.target_address);
+        write_bytes(&mut output, &self.payload)?;
+        write_u256(&mut output, self.requested_receiver_value);
+        write_u256(&mut output, self.extra_receiver_value);
+        write_bytes(&mut output, &self.encoded_execution_info)?;
+        output.extend_from_slice(&self.refund_chain.to_be_bytes());
+        output.extend_from_slice(&self.refund_address);
+        output.extend_from_slice(&self.refund_delivery_provider);
+        output.extend_from_slice(&self.source_delivery_provider);
+        output.extend_from_slice(&self.sender_address);
+
+        let key_count = u8::try_from(self.message_keys.len())
+            .map_err(|_| CCIHSError::SerializationError("more than 255 message keys".to_string()))?;
+        output.push(key_count);
+        for key in &self.message_keys {
+            match key {
+                MessageKey::Vaa(vaa) => {
+                    output.push(VAA_KEY_TYPE);
+                    output.extend_from_slice(&vaa.chain.to_be_bytes());
+                    output.extend_from_slice(&vaa.emitter_address);
+                    output.extend_from_slice(&vaa.sequence.to_be_bytes());
+                }
+                MessageKey::Other { key_type, data } => {
+                    output.push(*key_type);
+                    write_bytes(&mut output, data)?;
+                }
+            }
+        }
+        Ok(output)
+    }
+
+    pub fn decode(bytes: &[u8]) -> CCIHSResult<Self> {
+        let mut reader = Reader { bytes, offset: 0 };
+        let payload_id = reader.u8()?;
+        if payload_id != DELIVERY_INSTRUCTION_PAYLOAD_ID {
+            return Err(CCIHSError::DeserializationError(format!(
+                "payload ID {} is not a delivery instruction",
+                payload_id
+            )));
+        }
+
+        let mut instruction = Self {
+            target_chain: reader.u16()?,
+            target_address: reader.array()?,
+            payload: reader.bytes()?,
+            requested_receiver_value: reader.u256()?,
+            extra_receiver_value: reader.u256()?,
+            encoded_execution_info: reader.bytes()?,
+            refund_chain: reader.u16()?,
+            refund_address: reader.array()?,
+            refund_delivery_provider: reader.array()?,
+            source_delivery_provider: reader.array()?,
+            sender_address: reader.array()?,
+            message_keys: Vec::new(),
+        };
+        let key_count = reader.u8()?;
+        for _ in 0..key_count {
+            let key = match reader.u8()? {
+                VAA_KEY_TYPE => MessageKey::Vaa(VaaKey {
+                    chain: reader.u16()?,
+                    emitter_address: reader.array()?,
+                    sequence: u64::from_be_bytes(reader.array()?),
+                }),
+                key_type => MessageKey::Other {
+                    key_type,
+                    data: reader.bytes()?,
+                },
+            };
+            instruction.message_keys.push(key);
+        }
+
+        if reader.offset != bytes.len() {
+            return Err(CCIHSError::DeserializationError(format!(
+                "{} trailing bytes after delivery instruction",
+                bytes.len() - reader.offset
+            )));
+        }
+        Ok(instruction)
+    }
+
+    pub fn execution_info(&self) -> CCIHSResult<EvmExecutionInfo> {
+        EvmExecutionInfo::decode(&self.encoded_execution_info)
+    }
+
+    pub fn vaa_keys(&self) -> impl Iterator<Item = &VaaKey> {
+        self.message_keys.iter().filter_map(|key| match key {
+            MessageKey::Vaa(vaa) => Some(vaa),
+            MessageKey::Other { .. } => None,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// What the sender asks of a delivery.
+pub struct DeliveryRequest {
+    pub target_chain: u16,
+    pub target_address: [u8; 32],
+    pub payload: Vec<u8>,
+    /// Gas forwarded to the target's `receiveWormholeMessages`.
+    pub gas_limit: u128,
+    /// Native tokens of the target chain sent along with the delivery.
+    pub receiver_value: u128,
+    /// Where unused gas is refunded.
+    pub refund_chain: u16,
+    pub refund_address: [u8; 32],
+}
+
+impl DeliveryRequest {
+    /// The instruction a WormholeRelayer would emit for this request.
+    /// `delivery_provider` is both the source and the refund provider;
+    /// `price` sets the refund rate for unused gas.
+    pub fn instruction(
+        &self,
+        sender_address: [u8; 32],
+        delivery_provider: [u8; 32],
+        price: &DeliveryPrice,
+        message_keys: Vec<MessageKey>,
+    ) -> DeliveryInstruction {
+        DeliveryInstruction {
+            target_chain: self.target_chain,
+            target_address: self.target_address,
+            payload: self.payload.clone(),
+            requested_receiver_value: self.receiver_value,
+            extra_receiver_value: 0,
+            encoded_execution_info: EvmExecutionInfo {
+                gas_limit: self.gas_limit,
+                target_chain_refund_per_gas_unused: price.gas_price as u128,
+            }
+            .encode(),
+            refund_chain: self.refund_chain,
+            refund_address: self.refund_address,
+            refund_delivery_provider: delivery_provider,
+            source_delivery_provider: delivery_provider,
+            sender_address,
+            message_keys,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Delivery provider pricing for one target chain.
+pub struct DeliveryPrice {
+    /// Flat fee per delivery, in lamports.
+    pub base_fee: u64,
+    /// Target gas price, in the smallest unit of the target's native token.
+    pub gas_price: u64,
+    /// Lamports per whole native token of the target chain.
+    pub native_price: u64,
+    /// Decimals of the target's native token.
+    pub native_decimals: u8,
+}
+
+impl Default for DeliveryPrice {
+    fn default() -> Self {
+        Self {
+            base_fee: 0,
+            gas_price: 0,
+            native_price: 0,
+            native_decimals: 18,
+        }
+    }
+}
+
+impl DeliveryPrice {
+    /// Lamports to pay for a delivery forwarding `gas_limit` gas and
+    /// `receiver_value` native tokens. Rounded up.
+    pub fn quote(&self, gas_limit: u128, receiver_value: u128) -> CCIHSResult<u64> {
+        let overflow = || CCIHSError::ProtocolError("delivery quote overflows".to_string());
+        let target_cost = gas_limit
+            .checked_mul(self.gas_price as u128)
+            .and_then(|gas_cost| gas_cost.checked_add(receiver_value))
+            .ok_or_else(overflow)?;
+        let unit = 10u128.checked_pow(self.native_decimals as u32).ok_or_else(overflow)?;
+        let lamports = target_cost
+            .checked_mul(self.native_price as u128)
+            .map(|scaled| (scaled + unit - 1) / unit)
+            .ok_or_else(overflow)?;
+        u64::try_from(lamports)
+            .ok()
+            .and_then(|lamports| lamports.checked_add(self.base_fee))
+            .ok_or_else(overflow)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A delivery request VAA as seen by the delivery provider or the target.
+pub struct DeliveryVaa {
+    pub vaa: ParsedVaa,
+    pub instruction: DeliveryInstruction,
+}
+
+impl DeliveryVaa {
+    /// Accepts a bare delivery instruction payload as well as one sent
+    /// through this program's `send_message`, which wraps it in a general
+    /// message envelope (type byte 0, then the length as a big endian u16).
+    pub fn parse(bytes: &[u8]) -> CCIHSResult<Self> {
+        let vaa = ParsedVaa::parse(bytes)?;
+        let instruction = match vaa.payload.first() {
+            Some(&DELIVERY_INSTRUCTION_PAYLOAD_ID) => DeliveryInstruction::decode(&vaa.payload)?,
+            _ => {
+                let length = vaa.payload
+                    .get(1..3)
+                    .map(|length| u16::from_be_bytes([length[0], length[1]]) as usize)
+                    .filter(|_| vaa.payload[0] == 0)
+                    .ok_or_else(|| CCIHSError::DeserializationError("VAA carries no delivery instruction".to_string()))?;
+                let inner = vaa.payload.get(3..3 + length).ok_or_else(|| {
+                    CCIHSError::DeserializationError("message envelope truncated".to_string())
+                })?;
+                DeliveryInstruction::decode(inner)?
+            }
+        };
+        Ok(Self { vaa, instruction })
+    }
+
+    /// The key under which other deliveries may reference this VAA.
+    pub fn key(&self) -> VaaKey {
+        VaaKey {
+            chain: self.vaa.emitter_chain,
+            emitter_address: self.vaa.emitter_address,
+            sequence: self.vaa.sequence,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delivery_instruction_round_trips() {
+        let price = DeliveryPrice {
+            base_fee: 5_000,
+            gas_price: 20_000_000_000,
+            native_price: 150_000_000_000,
+            native_decimals: 18,
+        };
+        let request = DeliveryRequest {
+            target_chain: 2,
+            target_address: [0xaa; 32],
+            payload: b"hello".to_vec(),
+            gas_limit: 250_000,
+            receiver_value: 0,
+            refund_chain: 1,
+            refund_address: [0xbb; 32],
+        };
+        let keys = vec![
+            MessageKey::Vaa(VaaKey { chain: 1, emitter_address: [0xcc; 32], sequence: 7 }),
+            MessageKey::Other { key_type: 2, data: vec![1, 2, 3] },
+        ];
+        let instruction = request.instruction([0xdd; 32], [0xee; 32], &price, keys);
+
+        let encoded = instruction.encode().unwrap();
+        assert_eq!(encoded[0], DELIVERY_INSTRUCTION_PAYLOAD_ID);
+        let decoded = DeliveryInstruction::decode(&encoded).unwrap();
+        assert_eq!(decoded, instruction);
+        assert_eq!(decoded.execution_info().unwrap().gas_limit, 250_000);
+        assert_eq!(decoded.vaa_keys().count(), 1);
+        assert!(DeliveryInstruction::decode(&encoded[..encoded.len() - 1]).is_err());
+
+        // 250k gas at 20 gwei is 0.005 ETH, 750M lamports at 150 SOL per ETH.
+        assert_eq!(price.quote(250_000, 0).unwrap(), 750_005_000);
+    }
+}
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
-use crate::config::protocol_config::ProtocolConfigTrait;
-use crate::types::{ChainId, ProtocolType};
-use std::collections::{HashSet, HashMap};
+use crate::config::WormholeSettings;
+use crate::types::ChainId;
+use std::collections::HashSet;
 use std::collections::BTreeMap;
 
 pub struct WormholeAddresses {
@@ -53,7 +53,6 @@ impl GeneralMessageConfig {
         wormhole_sequence: Pubkey,
         token_bridge: Option<Pubkey>,
         supported_chains: HashSet<ChainId>,
-        additional_params: HashMap<String, String>,
         foreign_emitters: BTreeMap<u16, Pubkey>,
     ) -> Self {
         Self {
@@ -84,25 +83,10 @@ impl GeneralMessageConfig {
         self.supported_chains.insert(chain_id);
     }
 
-    pub fn set_parameter(&mut self, key: &str, value: &str) {
-        self.additional_params.insert(key.to_string(), value.to_string());
-    }
-
-    fn get_parameter(&self, key: &str) -> Option<&str> {
-        self.additional_params.get(key)
-    }
-}
-
-impl ProtocolConfigTrait for WormholeConfig {
-    fn protocol_type(&self) -> ProtocolType {
-        ProtocolType::Wormhole
-    }
-
-    fn supported_chains(&self) -> &HashSet<ChainId> {
-        &self.supported_chains
-    }
-
-    fn additional_params(&self) -> &HashMap<String, String> {
-        &self.additional_params
+    /// Copies the message settings (batch ID and finality) from the typed
+    /// Wormhole settings.
+    pub fn apply_settings(&mut self, settings: &WormholeSettings) {
+        self.batch_id = settings.batch_id;
+        self.finality = settings.finality;
     }
 }
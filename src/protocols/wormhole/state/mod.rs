@@ -5,6 +5,8 @@ mod general_message_config;
 mod token_sender_config;
 mod token_redeemer_config;
 mod foreign_token_emitter;
+mod reassembly_buffer;
+mod registered_receiver;
 
 pub use foreign_emitter::ForeignEmitter;
 pub use received::{Received, MESSAGE_MAX_LENGTH};
@@ -13,6 +15,8 @@ pub use general_message_config::GeneralMessageConfig;
 pub use token_sender_config::SenderConfig;
 pub use token_redeemer_config::RedeemerConfig;
 pub use foreign_token_emitter::ForeignTokenEmitter;
+pub use reassembly_buffer::ReassemblyBuffer;
+pub use registered_receiver::{RegisteredReceiver, SEED_PREFIX_DELIVERY_AUTHORITY};
 
 
 // This approach provides a balance between convenience and control.
@@ -0,0 +1,215 @@
+use anchor_lang::prelude::*;
+use crate::protocols::wormhole::WormholeCrossChainMessage;
+use crate::types::{MessageChunk, MAX_REASSEMBLED_PAYLOAD_LENGTH};
+
+#[account]
+#[derive(Default)]
+/// Reassembly buffer account. Collects the fragments of a chunked payload
+/// received from one emitter, in any order, until the whole payload has
+/// arrived.
+pub struct ReassemblyBuffer {
+    /// Transfer ID shared by every fragment of the payload.
+    pub transfer_id: [u8; 32],
+    /// Emitter chain the fragments were sent from.
+    pub emitter_chain: u16,
+    /// Emitter the fragments were sent from.
+    pub emitter_address: [u8; 32],
+    /// Total number of fragments.
+    pub total_chunks: u16,
+    /// Size of every fragment except the last one.
+    pub chunk_size: u16,
+    /// Length of the reassembled payload.
+    pub total_length: u32,
+    /// Keccak256 hash of the reassembled payload.
+    pub checksum: [u8; 32],
+    /// Bitmap of fragment indexes written so far.
+    pub received_chunks: u64,
+    /// Set once every fragment arrived and the checksum matched.
+    pub complete: bool,
+    /// Paid for the account with the first fragment; gets the rent back when
+    /// the buffer is closed.
+    pub payer: Pubkey,
+    /// Envelope of the first fragment, without its payload. Every fragment
+    /// names the same recipient and execution window; the reassembled
+    /// payload is delivered in this envelope.
+    pub envelope: Option<WormholeCrossChainMessage>,
+    /// Sequence of the fragment that completed the payload.
+    pub sequence: u64,
+    /// Keccak256 hash of the fragment that completed the payload.
+    pub vaa_hash: [u8; 32],
+    /// Set once the payload was delivered to its receiver program.
+    pub delivered: bool,
+    /// Reassembled payload. Sized to `total_length` on the first fragment and
+    /// emptied when the buffer is closed.
+    pub data: Vec<u8>,
+}
+
+impl ReassemblyBuffer {
+    pub const MAXIMUM_SIZE: usize = 8 // discriminator
+        + 32 // transfer_id
+        + 2 // emitter_chain
+        + 32 // emitter_address
+        + 2 // total_chunks
+        + 2 // chunk_size
+        + 4 // total_length
+        + 32 // checksum
+        + 8 // received_chunks
+        + 1 // complete
+        + 32 // payer
+        + 1 + Self::ENVELOPE_SIZE // envelope
+        + 8 // sequence
+        + 32 // vaa_hash
+        + 1 // delivered
+        + 4 // Vec length
+        + MAX_REASSEMBLED_PAYLOAD_LENGTH // data
+    ;
+    /// Size left after the buffer is closed: the header stays so fragments
+    /// of the transfer cannot be replayed into a new buffer.
+    pub const CLOSED_SIZE: usize = Self::MAXIMUM_SIZE - MAX_REASSEMBLED_PAYLOAD_LENGTH;
+    /// Largest envelope without its payload: a recipient of at most 32
+    /// bytes and every option set.
+    pub const ENVELOPE_SIZE: usize = 1 // message_type
+        + 2 // payload length
+        + 8 // amount
+        + 1 + 32 // token_address
+        + 1 + 2 + 32 // recipient
+        + 1 + 2 // destination_chain
+        + 4 // nonce
+        + 8 // timestamp
+        + 1 + 8 // expires_at
+        + 1 + 8 // execute_after
+    ;
+    /// AKA `b"reassembly"`.
+    pub const SEED_PREFIX: &'static [u8; 10] = b"reassembly";
+
+    /// Convenience method to check whether a fragment, posted by
+    /// `emitter_address` in `envelope`, belongs to the transfer saved in
+    /// this account. An empty buffer accepts any fragment whose recipient
+    /// fits in 32 bytes.
+    pub fn accepts(
+        &self,
+        emitter_chain: u16,
+        emitter_address: &[u8; 32],
+        envelope: &WormholeCrossChainMessage,
+        chunk: &MessageChunk,
+    ) -> bool {
+        match &self.envelope {
+            None => envelope.recipient.as_ref().map_or(true, |recipient| recipient.len() <= 32),
+            Some(first) => {
+                self.emitter_chain == emitter_chain
+                    && self.emitter_address == *emitter_address
+                    && self.transfer_id == chunk.transfer_id
+                    && self.total_chunks == chunk.total
+                    && self.chunk_size == chunk.chunk_size
+                    && self.total_length == chunk.total_length
+                    && self.checksum == chunk.checksum
+                    && first.recipient == envelope.recipient
+                    && first.expires_at == envelope.expires_at
+                    && first.execute_after == envelope.execute_after
+            }
+        }
+    }
+
+    pub fn has_chunk(&self, index: u16) -> bool {
+        self.received_chunks & (1u64 << index) != 0
+    }
+
+    pub fn received_count(&self) -> u32 {
+        self.received_chunks.count_ones()
+    }
+
+    /// Writes a fragment into the buffer. Returns `true` when it was the last
+    /// missing fragment.
+    pub fn write_chunk(
+        &mut self,
+        emitter_chain: u16,
+        emitter_address: &[u8; 32],
+        envelope: &WormholeCrossChainMessage,
+        chunk: &MessageChunk,
+    ) -> bool {
+        if self.envelope.is_none() {
+            self.transfer_id = chunk.transfer_id;
+            self.emitter_chain = emitter_chain;
+            self.emitter_address = *emitter_address;
+            self.envelope = Some(WormholeCrossChainMessage {
+                payload: Vec::new(),
+                ..envelope.clone()
+            });
+            self.total_chunks = chunk.total;
+            self.chunk_size = chunk.chunk_size;
+            self.total_length = chunk.total_length;
+            self.checksum = chunk.checksum;
+            self.data = vec![0u8; chunk.total_length as usize];
+        }
+
+        let offset = chunk.offset();
+        self.data[offset..offset + chunk.data.len()].copy_from_slice(&chunk.data);
+        self.received_chunks |= 1u64 << chunk.index;
+
+        self.received_count() == self.total_chunks as u32
+    }
+
+    /// The reassembled payload in the envelope of its fragments.
+    pub fn message(&self) -> Option<WormholeCrossChainMessage> {
+        self.envelope.as_ref().map(|envelope| WormholeCrossChainMessage {
+            payload: self.data.clone(),
+            ..envelope.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::protocols::wormhole::MessageType;
+    use crate::types::{payload_checksum, split_payload};
+
+    #[test]
+    fn test_reassemble_fragments_from_envelopes() {
+        let payload: Vec<u8> = (0..2000u32).map(|i| (i % 251) as u8).collect();
+        let mut chunks = split_payload([3u8; 32], &payload, 896).unwrap();
+        chunks.reverse();
+
+        let mut buffer = ReassemblyBuffer::default();
+        for chunk in chunks {
+            // What `CCIHSCore::send_chunked_message` hands to `send_message`
+            // and what ends up in the posted VAA.
+            let envelope = WormholeCrossChainMessage {
+                message_type: MessageType::General,
+                payload: chunk.try_to_vec().unwrap(),
+                amount: 0,
+                token_address: None,
+                recipient: Some(vec![9; 32]),
+                destination_chain: None,
+                nonce: 7,
+                timestamp: 1_700_000_000,
+                expires_at: Some(1_700_003_600),
+                execute_after: None,
+            };
+            let posted = WormholeCrossChainMessage::try_from_slice(&envelope.try_to_vec().unwrap()).unwrap();
+            assert_eq!(posted, envelope);
+
+            let chunk = MessageChunk::try_from_slice(&posted.payload).unwrap();
+            assert!(chunk.is_well_formed() && buffer.accepts(2, &[5; 32], &posted, &chunk));
+            assert!(!buffer.has_chunk(chunk.index));
+
+            let mut redirected = posted.clone();
+            redirected.recipient = Some(vec![8; 32]);
+            assert_eq!(buffer.envelope.is_none(), buffer.accepts(2, &[5; 32], &redirected, &chunk));
+            assert_eq!(buffer.envelope.is_none(), buffer.accepts(2, &[6; 32], &posted, &chunk));
+            buffer.write_chunk(2, &[5; 32], &posted, &chunk);
+        }
+
+        assert_eq!(buffer.received_count(), 3);
+        assert_eq!(buffer.data, payload);
+        assert_eq!(payload_checksum(&buffer.data), buffer.checksum);
+
+        let message = buffer.message().unwrap();
+        assert_eq!(message.payload, payload);
+        assert_eq!(message.recipient, Some(vec![9; 32]));
+        assert_eq!(message.expires_at, Some(1_700_003_600));
+        let mut encoded = Vec::new();
+        buffer.envelope.serialize(&mut encoded).unwrap();
+        assert!(encoded.len() <= 1 + ReassemblyBuffer::ENVELOPE_SIZE);
+    }
+}
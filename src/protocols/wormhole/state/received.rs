@@ -12,6 +12,9 @@ pub struct Received {
     pub batch_id: u32,
     /// Keccak256 hash of verified Wormhole message.
     pub wormhole_message_hash: [u8; 32],
+    /// [`MessageId`](crate::types::MessageId) of the message, from the
+    /// emitter chain, emitter address and sequence of its VAA.
+    pub message_id: [u8; 32],
     /// HelloWorldMessage from [HelloWorldMessage::Hello](crate::message::HelloWorldMessage).
     pub message: Vec<u8>,
 }
@@ -20,6 +23,7 @@ impl Received {
     pub const MAXIMUM_SIZE: usize = 8 // discriminator
         + 4 // batch_id
         + 32 // wormhole_message_hash
+        + 32 // message_id
         + 4 // Vec length
         + MESSAGE_MAX_LENGTH // message
     ;
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use crate::protocols::wormhole::WormholeError;
+
+#[account]
+#[derive(Default)]
+/// Registered receiver account. A program can only be delivered messages
+/// through [receive_and_deliver_message_handler](crate::receive_and_deliver_message_handler)
+/// once the owner has registered it.
+pub struct RegisteredReceiver {
+    /// Receiver program ID. Messages naming this program as their recipient
+    /// are delivered to it by CPI.
+    pub program_id: Pubkey,
+    /// Receiver programs can be paused without removing the registration.
+    pub enabled: bool,
+}
+
+impl RegisteredReceiver {
+    pub const MAXIMUM_SIZE: usize = 8 // discriminator
+        + 32 // program_id
+        + 1 // enabled
+    ;
+    /// AKA `b"registered_receiver"`.
+    pub const SEED_PREFIX: &'static [u8; 19] = b"registered_receiver";
+
+    /// Checks that the receiver is enabled and is the message's `recipient`,
+    /// which must be a 32 byte program ID.
+    pub fn check_recipient(&self, recipient: Option<&[u8]>) -> Result<()> {
+        require!(self.enabled, WormholeError::ReceiverDisabled);
+        let recipient = recipient
+            .and_then(|recipient| <[u8; 32]>::try_from(recipient).ok())
+            .map(Pubkey::new_from_array)
+            .ok_or(WormholeError::InvalidReceiver)?;
+        require!(
+            recipient == self.program_id,
+            WormholeError::InvalidReceiver
+        );
+        Ok(())
+    }
+}
+
+/// AKA `b"delivery_authority"`. Seed of the PDA signing every delivery CPI.
+/// Receivers should check that this PDA (derived with the CCIHS program ID) is
+/// a signer before trusting a delivered message.
+pub const SEED_PREFIX_DELIVERY_AUTHORITY: &[u8; 18] = b"delivery_authority";
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_rejects_disabled_receiver() {
+        let program_id = Pubkey::new_unique();
+        let mut receiver = RegisteredReceiver {
+            program_id,
+            enabled: true,
+        };
+        assert!(receiver.check_recipient(Some(program_id.as_ref())).is_ok());
+
+        receiver.enabled = false;
+        assert_eq!(
+            receiver.check_recipient(Some(program_id.as_ref())).unwrap_err(),
+            WormholeError::ReceiverDisabled.into()
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_recipients() {
+        let receiver = RegisteredReceiver {
+            program_id: Pubkey::new_unique(),
+            enabled: true,
+        };
+        for recipient in [None, Some(&[1u8; 20][..]), Some(Pubkey::new_unique().as_ref())] {
+            assert_eq!(
+                receiver.check_recipient(recipient).unwrap_err(),
+                WormholeError::InvalidReceiver.into()
+            );
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// protocols/wormhole/vaa.rs
+
+use super::PostedVaaInfo;
+use crate::{CCIHSError, CCIHSResult};
+use anchor_lang::solana_program::keccak;
+
+/// Length of one guardian signature: guardian index, r, s and v.
+const GUARDIAN_SIGNATURE_LENGTH: usize = 66;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GuardianSignature {
+    pub guardian_index: u8,
+    pub signature: [u8; 65],
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A version 1 VAA as signed by the guardians, before it is posted to the
+/// core bridge.
+pub struct ParsedVaa {
+    pub version: u8,
+    pub guardian_set_index: u32,
+    pub signatures: Vec<GuardianSignature>,
+    pub timestamp: u32,
+    pub nonce: u32,
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub sequence: u64,
+    pub consistency_level: u8,
+    pub payload: Vec<u8>,
+    body_offset: usize,
+    raw: Vec<u8>,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> CCIHSResult<&'a [u8]> {
+        let end = self.offset + length;
+        let bytes = self.bytes.get(self.offset..end).ok_or_else(|| {
+            CCIHSError::DeserializationError(format!("VAA truncated at byte {}", self.offset))
+        })?;
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> CCIHSResult<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+}
+
+impl ParsedVaa {
+    pub fn parse(bytes: &[u8]) -> CCIHSResult<Self> {
+        let mut reader = Reader { bytes, offset: 0 };
+
+        let version = reader.array::<1>()?[0];
+        if version != 1 {
+            return Err(CCIHSError::DeserializationError(format!("unsupported VAA version {version}")));
+        }
+        let guardian_set_index = u32::from_be_bytes(reader.array()?);
+        let signature_count = reader.array::<1>()?[0] as usize;
+        let mut signatures = Vec::with_capacity(signature_count);
+        for _ in 0..signature_count {
+            let raw = reader.take(GUARDIAN_SIGNATURE_LENGTH)?;
+            let mut signature = [0u8; 65];
+            signature.copy_from_slice(&raw[1..]);
+            signatures.push(GuardianSignature {
+                guardian_index: raw[0],
+                signature,
+            });
+        }
+
+        let body_offset = reader.offset;
+        Ok(Self {
+            version,
+            guardian_set_index,
+            signatures,
+            timestamp: u32::from_be_bytes(reader.array()?),
+            nonce: u32::from_be_bytes(reader.array()?),
+            emitter_chain: u16::from_be_bytes(reader.array()?),
+            emitter_address: reader.array()?,
+            sequence: u64::from_be_bytes(reader.array()?),
+            consistency_level: reader.array::<1>()?[0],
+            payload: bytes[reader.offset..].to_vec(),
+            body_offset,
+            raw: bytes.to_vec(),
+        })
+    }
+
+    /// The signed part of the VAA, from the timestamp on.
+    pub fn body(&self) -> &[u8] {
+        &self.raw[self.body_offset..]
+    }
+
+    /// Keccak256 of the body. The core bridge derives the posted VAA account
+    /// from this hash.
+    pub fn hash(&self) -> [u8; 32] {
+        keccak::hash(self.body()).to_bytes()
+    }
+
+    /// Keccak256 of [`hash`](Self::hash), the digest the guardians sign.
+    pub fn digest(&self) -> [u8; 32] {
+        keccak::hash(&self.hash()).to_bytes()
+    }
+
+    pub fn posted_vaa_info(&self) -> PostedVaaInfo {
+        PostedVaaInfo {
+            vaa_hash: self.hash(),
+            emitter_chain: self.emitter_chain,
+            emitter_address: self.emitter_address,
+            sequence: self.sequence,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_header_and_body() {
+        let mut vaa = vec![1];
+        vaa.extend_from_slice(&3u32.to_be_bytes());
+        vaa.push(1);
+        vaa.push(7);
+        vaa.extend_from_slice(&[0x11; 65]);
+        let body_offset = vaa.len();
+        vaa.extend_from_slice(&1_700_000_000u32.to_be_bytes());
+        vaa.extend_from_slice(&42u32.to_be_bytes());
+        vaa.extend_from_slice(&2u16.to_be_bytes());
+        vaa.extend_from_slice(&[0xee; 32]);
+        vaa.extend_from_slice(&9u64.to_be_bytes());
+        vaa.push(1);
+        vaa.extend_from_slice(b"payload");
+
+        let parsed = ParsedVaa::parse(&vaa).unwrap();
+        assert_eq!(parsed.guardian_set_index, 3);
+        assert_eq!(parsed.signatures[0].guardian_index, 7);
+        assert_eq!(parsed.nonce, 42);
+        assert_eq!(parsed.emitter_chain, 2);
+        assert_eq!(parsed.sequence, 9);
+        assert_eq!(parsed.payload, b"payload");
+        assert_eq!(parsed.hash(), keccak::hash(&vaa[body_offset..]).to_bytes());
+
+        assert!(ParsedVaa::parse(&vaa[..body_offset + 10]).is_err());
+    }
+}
@@ -0,0 +1,600 @@
+// src/relayer/engine.rs
+
+use super::{SequenceStore, TransactionSubmitter, VaaId, VaaSource};
+use crate::protocols::wormhole::{
+    ForeignEmitter, ForeignTokenEmitter, InstructionBuilder, ParsedVaa, PostedVaaInfo, RedeemerConfig,
+    WormholeCrossChainMessage, WrappedToken,
+};
+use crate::{CCIHSError, CCIHSResult};
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::AnchorDeserialize;
+use std::collections::{HashMap, VecDeque};
+
+/// Wormhole chain ID of Solana.
+const SOLANA_CHAIN: u16 = 1;
+/// Token Bridge payload ID of a transfer with payload.
+const TRANSFER_WITH_PAYLOAD: u8 = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A Token Bridge transfer with payload, as carried by the VAA.
+pub struct TokenTransfer {
+    /// Normalized to at most 8 decimals by the Token Bridge.
+    pub amount: u64,
+    pub token_address: [u8; 32],
+    pub token_chain: u16,
+    pub to: [u8; 32],
+    pub to_chain: u16,
+    /// The contract that initiated the transfer on the source chain.
+    pub from_address: [u8; 32],
+    pub payload: Vec<u8>,
+}
+
+impl TokenTransfer {
+    pub fn parse(payload: &[u8]) -> CCIHSResult<Self> {
+        let truncated = || CCIHSError::DeserializationError("token transfer truncated".to_string());
+        let field = |start: usize, length: usize| payload.get(start..start + length).ok_or_else(truncated);
+        let array = |start: usize| -> CCIHSResult<[u8; 32]> {
+            let mut array = [0u8; 32];
+            array.copy_from_slice(field(start, 32)?);
+            Ok(array)
+        };
+
+        if payload.first() != Some(&TRANSFER_WITH_PAYLOAD) {
+            return Err(CCIHSError::DeserializationError("not a token transfer with payload".to_string()));
+        }
+        let amount = array(1)?;
+        if amount[..24].iter().any(|&byte| byte != 0) {
+            return Err(CCIHSError::DeserializationError("transfer amount exceeds u64".to_string()));
+        }
+        let mut low = [0u8; 8];
+        low.copy_from_slice(&amount[24..]);
+        Ok(Self {
+            amount: u64::from_be_bytes(low),
+            token_address: array(33)?,
+            token_chain: u16::from_be_bytes([field(65, 1)?[0], field(66, 1)?[0]]),
+            to: array(67)?,
+            to_chain: u16::from_be_bytes([field(99, 1)?[0], field(100, 1)?[0]]),
+            from_address: array(101)?,
+            payload: payload[133..].to_vec(),
+        })
+    }
+
+    /// The CCIHS message envelope the payload carries.
+    pub fn message(&self) -> CCIHSResult<WormholeCrossChainMessage> {
+        WormholeCrossChainMessage::try_from_slice(&self.payload)
+            .map_err(|e| CCIHSError::DeserializationError(e.to_string()))
+    }
+
+    /// The recipient named in the message envelope, which the program pays
+    /// out to on redemption.
+    pub fn recipient(&self) -> CCIHSResult<Pubkey> {
+        let recipient = self
+            .message()?
+            .recipient
+            .ok_or_else(|| CCIHSError::DeserializationError("transfer payload has no recipient".to_string()))?;
+        <[u8; 32]>::try_from(recipient.as_slice())
+            .map(Pubkey::new_from_array)
+            .map_err(|_| CCIHSError::DeserializationError("transfer recipient is not 32 bytes".to_string()))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RelayKind {
+    Message,
+    NativeTransfer { mint: Pubkey, fee: u64 },
+    WrappedTransfer { mint: Pubkey, fee: u64 },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Not sent by one of our registered foreign emitters, or a transfer not
+    /// emitted by the registered Token Bridge of its chain.
+    UnknownEmitter,
+    AlreadyProcessed,
+    /// A transfer addressed to another chain or program.
+    NotForUs,
+    MessagesDisabled,
+    /// The relayer fee does not cover the configured minimum.
+    Unprofitable { fee: u64, minimum: Option<u64> },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RelayOutcome {
+    Submitted { vaa: PostedVaaInfo, kind: RelayKind, signature: String },
+    Skipped { vaa: PostedVaaInfo, reason: SkipReason },
+    /// Posting or submitting failed. Unless `will_retry` is set, the VAA
+    /// has been given up on and is marked processed.
+    Failed { vaa: PostedVaaInfo, error: String, will_retry: bool },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Which VAAs are worth relaying.
+pub struct RelayerPolicy {
+    /// Relay general messages, which pay no relayer fee.
+    pub relay_messages: bool,
+    /// Minimum relayer fee by mint, in the transfer's normalized amount.
+    pub min_fees: HashMap<Pubkey, u64>,
+    /// Minimum for mints not in `min_fees`. `None` skips those mints.
+    pub default_min_fee: Option<u64>,
+    /// How often a VAA is submitted before the relayer gives up on it.
+    pub max_attempts: u32,
+}
+
+impl Default for RelayerPolicy {
+    fn default() -> Self {
+        Self {
+            relay_messages: true,
+            min_fees: HashMap::new(),
+            default_min_fee: None,
+            max_attempts: 3,
+        }
+    }
+}
+
+/// Relays VAAs from a [`VaaSource`] to the CCIHS Wormhole program: general
+/// messages from registered [`ForeignEmitter`]s are received with
+/// `receive_message`, token transfers from registered
+/// [`ForeignTokenEmitter`]s are redeemed for the relayer fee. VAAs that
+/// fail to land are retried on the next [`run_once`](Self::run_once), up to
+/// [`RelayerPolicy::max_attempts`] times.
+pub struct RelayerEngine<S, T, P> {
+    builder: InstructionBuilder,
+    payer: Pubkey,
+    redeemer_config: RedeemerConfig,
+    policy: RelayerPolicy,
+    foreign_emitters: HashMap<u16, [u8; 32]>,
+    /// Emitter address and Token Bridge endpoint by chain.
+    foreign_token_emitters: HashMap<u16, ([u8; 32], Pubkey)>,
+    /// Failed submissions by VAA.
+    attempts: HashMap<VaaId, u32>,
+    retries: VecDeque<ParsedVaa>,
+    source: S,
+    submitter: T,
+    store: P,
+}
+
+impl<S: VaaSource, T: TransactionSubmitter, P: SequenceStore> RelayerEngine<S, T, P> {
+    /// `payer` signs and pays for every transaction and receives the
+    /// relayer fees.
+    pub fn new(
+        builder: InstructionBuilder,
+        payer: Pubkey,
+        redeemer_config: RedeemerConfig,
+        source: S,
+        submitter: T,
+        store: P,
+    ) -> Self {
+        Self {
+            builder,
+            payer,
+            redeemer_config,
+            policy: RelayerPolicy::default(),
+            foreign_emitters: HashMap::new(),
+            foreign_token_emitters: HashMap::new(),
+            attempts: HashMap::new(),
+            retries: VecDeque::new(),
+            source,
+            submitter,
+            store,
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RelayerPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn add_foreign_emitter(&mut self, emitter: &ForeignEmitter) {
+        self.foreign_emitters.insert(emitter.chain, emitter.address);
+    }
+
+    pub fn add_foreign_token_emitter(&mut self, emitter: &ForeignTokenEmitter) {
+        self.foreign_token_emitters
+            .insert(emitter.chain, (emitter.address, emitter.token_bridge_foreign_endpoint));
+    }
+
+    pub fn submitter(&self) -> &T {
+        &self.submitter
+    }
+
+    pub fn store(&self) -> &P {
+        &self.store
+    }
+
+    /// Retries the VAAs that failed last time, then relays every VAA the
+    /// source has right now. VAAs that fail to parse are logged and dropped.
+    pub fn run_once(&mut self) -> CCIHSResult<Vec<RelayOutcome>> {
+        let mut outcomes = Vec::new();
+        let mut retries = std::mem::take(&mut self.retries);
+        loop {
+            let vaa = match retries.pop_front() {
+                Some(vaa) => vaa,
+                None => match self.source.next_vaa()? {
+                    Some(bytes) => match ParsedVaa::parse(&bytes) {
+                        Ok(vaa) => vaa,
+                        Err(e) => {
+                            log::warn!("Dropping unparsable VAA: {}", e);
+                            continue;
+                        }
+                    },
+                    None => break,
+                },
+            };
+            let outcome = self.relay(&vaa)?;
+            if let RelayOutcome::Failed { will_retry: true, .. } = outcome {
+                self.retries.push_back(vaa);
+            }
+            match &outcome {
+                RelayOutcome::Submitted { vaa, signature, .. } => {
+                    log::info!("Relayed {}/{} in {}", vaa.emitter_chain, vaa.sequence, signature)
+                }
+                RelayOutcome::Skipped { vaa, reason } => {
+                    log::debug!("Skipped {}/{}: {:?}", vaa.emitter_chain, vaa.sequence, reason)
+                }
+                RelayOutcome::Failed { vaa, error, will_retry } => log::warn!(
+                    "Relaying {}/{} failed{}: {}",
+                    vaa.emitter_chain,
+                    vaa.sequence,
+                    if *will_retry { "" } else { ", giving up" },
+                    error
+                ),
+            }
+            outcomes.push(outcome);
+        }
+        Ok(outcomes)
+    }
+
+    /// Relays a single VAA. Only errors of the sequence store are returned
+    /// as errors; everything else is reported in the outcome. A failed VAA
+    /// is not queued for retry here, only by [`run_once`](Self::run_once).
+    pub fn relay(&mut self, vaa: &ParsedVaa) -> CCIHSResult<RelayOutcome> {
+        let info = vaa.posted_vaa_info();
+        let skip = |reason| Ok(RelayOutcome::Skipped { vaa: info, reason });
+
+        if self.store.is_processed(info.emitter_chain, &info.emitter_address, info.sequence) {
+            return skip(SkipReason::AlreadyProcessed);
+        }
+
+        let (kind, instruction) = if self.foreign_emitters.get(&info.emitter_chain) == Some(&info.emitter_address) {
+            if !self.policy.relay_messages {
+                return skip(SkipReason::MessagesDisabled);
+            }
+            (RelayKind::Message, self.builder.receive_message(&self.payer, &info))
+        } else {
+            let transfer = match TokenTransfer::parse(&vaa.payload) {
+                Ok(transfer) => transfer,
+                Err(_) => return skip(SkipReason::UnknownEmitter),
+            };
+            // The sending contract is in the payload, which anyone can
+            // publish; only the Token Bridge endpoint makes it a transfer.
+            match self.foreign_token_emitters.get(&info.emitter_chain) {
+                Some((address, endpoint))
+                    if *address == transfer.from_address
+                        && *endpoint == self.builder.token_bridge_endpoint(info.emitter_chain, &info.emitter_address) => {}
+                _ => return skip(SkipReason::UnknownEmitter),
+            }
+            if transfer.to_chain != SOLANA_CHAIN || transfer.to != self.builder.redeemer_config().to_bytes() {
+                return skip(SkipReason::NotForUs);
+            }
+            // Resubmitting does not fix the payload, so give up right away.
+            let recipient = match transfer.recipient() {
+                Ok(recipient) => recipient,
+                Err(e) => return self.give_up(info, e.to_string()),
+            };
+
+            let native = transfer.token_chain == SOLANA_CHAIN;
+            let token = WrappedToken {
+                token_chain: transfer.token_chain,
+                token_address: transfer.token_address,
+            };
+            let mint = if native {
+                Pubkey::new_from_array(transfer.token_address)
+            } else {
+                self.builder.wrapped_mint(&token)
+            };
+
+            let fee = self.relayer_fee(transfer.amount);
+            let minimum = self.policy.min_fees.get(&mint).copied().or(self.policy.default_min_fee);
+            if minimum.map_or(true, |minimum| fee < minimum) {
+                return skip(SkipReason::Unprofitable { fee, minimum });
+            }
+
+            if native {
+                let instruction = self.builder.redeem_native_transfer_with_payload(&self.payer, &recipient, &mint, &info);
+                (RelayKind::NativeTransfer { mint, fee }, instruction)
+            } else {
+                let instruction = self.builder.redeem_wrapped_transfer_with_payload(&self.payer, &recipient, &token, &info);
+                (RelayKind::WrappedTransfer { mint, fee }, instruction)
+            }
+        };
+
+        let submitted = instruction
+            .and_then(|instruction| {
+                self.submitter.post_vaa(vaa)?;
+                self.submitter.submit(vec![instruction])
+            });
+        let id = (info.emitter_chain, info.emitter_address, info.sequence);
+        match submitted {
+            Ok(signature) => {
+                self.attempts.remove(&id);
+                self.store.mark_processed(info.emitter_chain, &info.emitter_address, info.sequence)?;
+                Ok(RelayOutcome::Submitted { vaa: info, kind, signature })
+            }
+            Err(e) => {
+                let attempts = self.attempts.entry(id).or_insert(0);
+                *attempts += 1;
+                if *attempts >= self.policy.max_attempts {
+                    self.attempts.remove(&id);
+                    return self.give_up(info, e.to_string());
+                }
+                Ok(RelayOutcome::Failed { vaa: info, error: e.to_string(), will_retry: true })
+            }
+        }
+    }
+
+    fn give_up(&mut self, info: PostedVaaInfo, error: String) -> CCIHSResult<RelayOutcome> {
+        self.store.mark_processed(info.emitter_chain, &info.emitter_address, info.sequence)?;
+        Ok(RelayOutcome::Failed { vaa: info, error, will_retry: false })
+    }
+
+    /// What the program pays the relayer for redeeming `amount`, as in
+    /// [`RedeemerConfig::compute_relayer_amount`] but without overflowing.
+    fn relayer_fee(&self, amount: u64) -> u64 {
+        let precision = self.redeemer_config.relayer_fee_precision as u128;
+        if precision == 0 {
+            return 0;
+        }
+        (amount as u128 * self.redeemer_config.relayer_fee as u128 / precision) as u64
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::protocols::wormhole::{event_topic, EvmCall, EvmLog, MessagePublished, MessageType, LOG_MESSAGE_PUBLISHED};
+    use crate::relayer::{MemorySequenceStore, MemoryVaaSource, MockSubmitter};
+    use crate::types::{ChainId, CrossChainAddress, CrossChainMessage};
+    use crate::utility::abi::{self, Token};
+    use crate::utility::ecdsa::EvmAddress;
+    use anchor_lang::AnchorSerialize;
+
+    fn vaa(emitter_chain: u16, emitter_address: [u8; 32], sequence: u64, payload: &[u8]) -> Vec<u8> {
+        let mut vaa = vec![1];
+        vaa.extend_from_slice(&0u32.to_be_bytes());
+        vaa.push(0);
+        vaa.extend_from_slice(&1_700_000_000u32.to_be_bytes());
+        vaa.extend_from_slice(&0u32.to_be_bytes());
+        vaa.extend_from_slice(&emitter_chain.to_be_bytes());
+        vaa.extend_from_slice(&emitter_address);
+        vaa.extend_from_slice(&sequence.to_be_bytes());
+        vaa.push(1);
+        vaa.extend_from_slice(payload);
+        vaa
+    }
+
+    fn transfer(to: [u8; 32], from_address: [u8; 32], amount: u64, recipient: &Pubkey) -> Vec<u8> {
+        let mut payload = vec![TRANSFER_WITH_PAYLOAD];
+        payload.extend_from_slice(&[0; 24]);
+        payload.extend_from_slice(&amount.to_be_bytes());
+        payload.extend_from_slice(&[0x44; 32]);
+        payload.extend_from_slice(&2u16.to_be_bytes());
+        payload.extend_from_slice(&to);
+        payload.extend_from_slice(&SOLANA_CHAIN.to_be_bytes());
+        payload.extend_from_slice(&from_address);
+        let envelope = WormholeCrossChainMessage {
+            message_type: MessageType::TokenTransfer,
+            payload: Vec::new(),
+            amount,
+            token_address: None,
+            recipient: Some(recipient.to_bytes().to_vec()),
+            destination_chain: None,
+            nonce: 0,
+            timestamp: 0,
+            expires_at: None,
+            execute_after: None,
+        };
+        payload.extend_from_slice(&envelope.try_to_vec().unwrap());
+        payload
+    }
+
+    fn token_emitter(builder: &InstructionBuilder) -> ForeignTokenEmitter {
+        ForeignTokenEmitter {
+            chain: 2,
+            address: [0xdd; 32],
+            token_bridge_foreign_endpoint: builder.token_bridge_endpoint(2, &[0xcc; 32]),
+        }
+    }
+
+    #[test]
+    fn test_relays_registered_emitters_once() {
+        let builder = InstructionBuilder::new(Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let redeemer_config = RedeemerConfig {
+            relayer_fee: 1_000_000,
+            relayer_fee_precision: 100_000_000,
+            ..Default::default()
+        };
+        let redeemer = builder.redeemer_config().to_bytes();
+        let recipient = Pubkey::new_unique();
+        let token_emitter = token_emitter(&builder);
+
+        let mut source = MemoryVaaSource::new();
+        source.push(vaa(2, [0xaa; 32], 1, b"hello"));
+        source.push(vaa(2, [0xaa; 32], 1, b"hello"));
+        source.push(vaa(2, [0xbb; 32], 2, b"spoofed"));
+        source.push(vaa(2, [0xcc; 32], 3, &transfer(redeemer, [0xdd; 32], 50_000, &recipient)));
+        source.push(vaa(2, [0xcc; 32], 4, &transfer(redeemer, [0xdd; 32], 500_000, &recipient)));
+        source.push(vaa(2, [0xee; 32], 5, &transfer(redeemer, [0xdd; 32], 500_000, &recipient)));
+
+        let mut engine = RelayerEngine::new(
+            builder,
+            Pubkey::new_unique(),
+            redeemer_config,
+            source,
+            MockSubmitter::new(),
+            MemorySequenceStore::new(),
+        )
+        .with_policy(RelayerPolicy {
+            default_min_fee: Some(1_000),
+            ..Default::default()
+        });
+        engine.add_foreign_emitter(&ForeignEmitter { chain: 2, address: [0xaa; 32] });
+        engine.add_foreign_token_emitter(&token_emitter);
+
+        let outcomes = engine.run_once().unwrap();
+        assert!(matches!(outcomes[0], RelayOutcome::Submitted { kind: RelayKind::Message, .. }));
+        assert!(matches!(outcomes[1], RelayOutcome::Skipped { reason: SkipReason::AlreadyProcessed, .. }));
+        assert!(matches!(outcomes[2], RelayOutcome::Skipped { reason: SkipReason::UnknownEmitter, .. }));
+        assert!(matches!(
+            outcomes[3],
+            RelayOutcome::Skipped { reason: SkipReason::Unprofitable { fee: 500, minimum: Some(1_000) }, .. }
+        ));
+        assert!(matches!(outcomes[4], RelayOutcome::Submitted { kind: RelayKind::WrappedTransfer { fee: 5_000, .. }, .. }));
+        assert!(matches!(outcomes[5], RelayOutcome::Skipped { reason: SkipReason::UnknownEmitter, .. }));
+
+        assert_eq!(engine.submitter().submitted.len(), 2);
+        assert_eq!(engine.submitter().submitted[1][0].accounts[6].pubkey, recipient);
+        assert!(engine.store().is_processed(2, &[0xcc; 32], 4));
+        assert!(!engine.store().is_processed(2, &[0xcc; 32], 3));
+    }
+
+    #[test]
+    fn test_retries_failed_submissions_up_to_max_attempts() {
+        let builder = InstructionBuilder::new(Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut source = MemoryVaaSource::new();
+        source.push(vaa(2, [0xaa; 32], 1, b"hello"));
+
+        let mut submitter = MockSubmitter::new();
+        submitter.failure = Some("rpc unavailable".to_string());
+        let mut engine = RelayerEngine::new(
+            builder,
+            Pubkey::new_unique(),
+            RedeemerConfig::default(),
+            source,
+            submitter,
+            MemorySequenceStore::new(),
+        )
+        .with_policy(RelayerPolicy {
+            max_attempts: 2,
+            ..Default::default()
+        });
+        engine.add_foreign_emitter(&ForeignEmitter { chain: 2, address: [0xaa; 32] });
+
+        let outcomes = engine.run_once().unwrap();
+        assert!(matches!(outcomes[..], [RelayOutcome::Failed { will_retry: true, .. }]));
+        assert!(!engine.store().is_processed(2, &[0xaa; 32], 1));
+
+        engine.submitter.failure = None;
+        let outcomes = engine.run_once().unwrap();
+        assert!(matches!(outcomes[..], [RelayOutcome::Submitted { .. }]));
+        assert!(engine.store().is_processed(2, &[0xaa; 32], 1));
+
+        engine.submitter.failure = Some("rpc unavailable".to_string());
+        engine.source.push(vaa(2, [0xaa; 32], 2, b"hello"));
+        assert!(matches!(engine.run_once().unwrap()[..], [RelayOutcome::Failed { will_retry: true, .. }]));
+        assert!(matches!(engine.run_once().unwrap()[..], [RelayOutcome::Failed { will_retry: false, .. }]));
+        assert!(engine.store().is_processed(2, &[0xaa; 32], 2));
+        assert!(engine.run_once().unwrap().is_empty());
+    }
+
+    /// The `LogMessagePublished` of the token bridge at `token_bridge` for
+    /// `call`, a `transferTokensWithPayload` made by `caller`.
+    fn published_transfer(call: &EvmCall, token_bridge: EvmAddress, caller: EvmAddress, sequence: u64) -> EvmLog {
+        let (token, amount, recipient_chain, recipient, nonce, payload) = match call {
+            EvmCall::TransferTokensWithPayload { token, amount, recipient_chain, recipient, nonce, payload } => {
+                (token, amount, recipient_chain, recipient, nonce, payload)
+            }
+            other => panic!("unexpected call {:?}", other),
+        };
+        let mut transfer = vec![TRANSFER_WITH_PAYLOAD];
+        transfer.extend_from_slice(&abi::encode(&[Token::Uint(*amount), Token::Address(*token)]));
+        transfer.extend_from_slice(&2u16.to_be_bytes());
+        transfer.extend_from_slice(recipient);
+        transfer.extend_from_slice(&recipient_chain.to_be_bytes());
+        transfer.extend_from_slice(&abi::encode(&[Token::Address(caller)]));
+        transfer.extend_from_slice(payload);
+
+        let mut emitter = [0u8; 32];
+        emitter[12..].copy_from_slice(&token_bridge);
+        EvmLog {
+            address: [0x11; 20],
+            topics: vec![event_topic(LOG_MESSAGE_PUBLISHED), emitter],
+            data: abi::encode(&[
+                Token::Uint(sequence as u128),
+                Token::Uint(*nonce as u128),
+                Token::Bytes(transfer),
+                Token::Uint(1),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_relays_evm_transfers_from_the_registered_contract() {
+        let builder = InstructionBuilder::new(Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let redeemer_config = RedeemerConfig {
+            relayer_fee: 1_000_000,
+            relayer_fee_precision: 100_000_000,
+            ..Default::default()
+        };
+        let recipient = Pubkey::new_unique();
+        let message = CrossChainMessage {
+            message_type: crate::types::MessageType::TokenTransfer,
+            payload: b"hello".to_vec(),
+            amount: 500_000,
+            token_address: None,
+            sender: CrossChainAddress::Ethereum(ethereum_types::Address::repeat_byte(0xdd)),
+            recipient: CrossChainAddress::Solana(recipient),
+            source_chain: ChainId::ETHEREUM,
+            destination_chain: ChainId::SOLANA,
+            nonce: 7,
+            timestamp: 0,
+            consistency_level: 1,
+            expires_at: None,
+            execute_after: None,
+            message_id: None,
+        };
+        let call = EvmCall::from_message(&message, Some([0x44; 20]), &builder.redeemer_config()).unwrap();
+
+        let token_bridge = [0xcc; 20];
+        let contract = [0xdd; 20];
+        let mut source = MemoryVaaSource::new();
+        for (sequence, caller) in [(1, contract), (2, [0xee; 20])] {
+            let published = MessagePublished::decode(&published_transfer(&call, token_bridge, caller, sequence))
+                .unwrap()
+                .unwrap();
+            let transfer = published.token_transfer().unwrap();
+            assert_eq!(transfer.recipient().unwrap(), recipient);
+            let mut emitter = [0u8; 32];
+            emitter[12..].copy_from_slice(&published.sender);
+            source.push(vaa(2, emitter, published.sequence, &published.payload));
+        }
+
+        let mut contract_address = [0u8; 32];
+        contract_address[12..].copy_from_slice(&contract);
+        let mut token_bridge_address = [0u8; 32];
+        token_bridge_address[12..].copy_from_slice(&token_bridge);
+        let token_emitter = ForeignTokenEmitter {
+            chain: 2,
+            address: contract_address,
+            token_bridge_foreign_endpoint: builder.token_bridge_endpoint(2, &token_bridge_address),
+        };
+        let mut engine = RelayerEngine::new(
+            builder,
+            Pubkey::new_unique(),
+            redeemer_config,
+            source,
+            MockSubmitter::new(),
+            MemorySequenceStore::new(),
+        )
+        .with_policy(RelayerPolicy {
+            default_min_fee: Some(1_000),
+            ..Default::default()
+        });
+        engine.add_foreign_token_emitter(&token_emitter);
+
+        // The same calldata sent straight from an EOA is never redeemed.
+        let outcomes = engine.run_once().unwrap();
+        assert!(matches!(outcomes[0], RelayOutcome::Submitted { kind: RelayKind::WrappedTransfer { fee: 5_000, .. }, .. }));
+        assert!(matches!(outcomes[1], RelayOutcome::Skipped { reason: SkipReason::UnknownEmitter, .. }));
+        assert_eq!(engine.submitter().submitted[0][0].accounts[6].pubkey, recipient);
+    }
+}
@@ -0,0 +1,21 @@
+// src/relayer/mod.rs
+
+//! Off-chain relayer for the CCIHS Wormhole program. Signed VAAs come from a
+//! [`VaaSource`] (a guardian spy, or a stand-in), are filtered down to the
+//! ones our registered foreign emitters sent, checked for profitability and
+//! redeemed through a [`TransactionSubmitter`], on a live cluster a
+//! [`ChainSubmitter`] over the chain's RPC. Redeemed sequences are kept
+//! in a [`SequenceStore`] so a restarted relayer does not submit them again.
+
+mod source;
+mod submitter;
+mod store;
+mod engine;
+
+/// Identifies a VAA: emitter chain, emitter address and sequence.
+type VaaId = (u16, [u8; 32], u64);
+
+pub use source::{VaaSource, MemoryVaaSource, FileVaaSource};
+pub use submitter::{TransactionSubmitter, MockSubmitter, ChainSubmitter};
+pub use store::{SequenceStore, MemorySequenceStore, FileSequenceStore};
+pub use engine::{RelayerEngine, RelayerPolicy, RelayOutcome, RelayKind, SkipReason, TokenTransfer};
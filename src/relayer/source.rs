@@ -0,0 +1,73 @@
+// src/relayer/source.rs
+
+use crate::utility::encoding::from_hex_or_base64;
+use crate::{CCIHSError, CCIHSResult};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+/// Where the relayer gets signed VAAs from, e.g. a guardian spy
+/// subscription.
+pub trait VaaSource {
+    /// The next signed VAA, or `None` if there is nothing new right now.
+    fn next_vaa(&mut self) -> CCIHSResult<Option<Vec<u8>>>;
+}
+
+#[derive(Default)]
+/// VAAs pushed by the caller, handed out in order.
+pub struct MemoryVaaSource {
+    queue: VecDeque<Vec<u8>>,
+}
+
+impl MemoryVaaSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, vaa: Vec<u8>) {
+        self.queue.push_back(vaa);
+    }
+}
+
+impl VaaSource for MemoryVaaSource {
+    fn next_vaa(&mut self) -> CCIHSResult<Option<Vec<u8>>> {
+        Ok(self.queue.pop_front())
+    }
+}
+
+/// Reads a file with one VAA per line, hex or base64. Blank lines and lines
+/// starting with `#` are skipped. The file is re-read on every call, so
+/// lines appended later are picked up.
+pub struct FileVaaSource {
+    path: PathBuf,
+    consumed: usize,
+}
+
+impl FileVaaSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            consumed: 0,
+        }
+    }
+}
+
+impl VaaSource for FileVaaSource {
+    fn next_vaa(&mut self) -> CCIHSResult<Option<Vec<u8>>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(CCIHSError::Io(format!("{}: {}", self.path.display(), e))),
+        };
+        // Only complete lines, a writer may be in the middle of the last one.
+        let complete = contents.rfind('\n').map_or("", |end| &contents[..end]);
+        let line = match complete.lines().nth(self.consumed) {
+            Some(line) => line.trim(),
+            None => return Ok(None),
+        };
+        self.consumed += 1;
+        if line.is_empty() || line.starts_with('#') {
+            return self.next_vaa();
+        }
+        from_hex_or_base64(line).map(Some)
+    }
+}
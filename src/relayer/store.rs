@@ -0,0 +1,132 @@
+// src/relayer/store.rs
+
+use super::VaaId;
+use crate::utility::encoding::{from_hex, to_hex};
+use crate::{CCIHSError, CCIHSResult};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// VAAs the relayer has already redeemed.
+pub trait SequenceStore {
+    fn is_processed(&self, emitter_chain: u16, emitter_address: &[u8; 32], sequence: u64) -> bool;
+    fn mark_processed(&mut self, emitter_chain: u16, emitter_address: &[u8; 32], sequence: u64) -> CCIHSResult<()>;
+}
+
+#[derive(Default)]
+pub struct MemorySequenceStore {
+    processed: HashSet<VaaId>,
+}
+
+impl MemorySequenceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SequenceStore for MemorySequenceStore {
+    fn is_processed(&self, emitter_chain: u16, emitter_address: &[u8; 32], sequence: u64) -> bool {
+        self.processed.contains(&(emitter_chain, *emitter_address, sequence))
+    }
+
+    fn mark_processed(&mut self, emitter_chain: u16, emitter_address: &[u8; 32], sequence: u64) -> CCIHSResult<()> {
+        self.processed.insert((emitter_chain, *emitter_address, sequence));
+        Ok(())
+    }
+}
+
+/// Appends one `<chain>/<emitter hex>/<sequence>` line per processed VAA.
+pub struct FileSequenceStore {
+    path: PathBuf,
+    processed: HashSet<VaaId>,
+}
+
+impl FileSequenceStore {
+    /// Loads the processed VAAs recorded in `path`, which need not exist
+    /// yet.
+    pub fn open(path: impl Into<PathBuf>) -> CCIHSResult<Self> {
+        let path = path.into();
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(CCIHSError::Io(format!("{}: {}", path.display(), e))),
+        };
+
+        let mut processed = HashSet::new();
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = || {
+                CCIHSError::DeserializationError(format!("{}:{}: invalid entry `{}`", path.display(), index + 1, line))
+            };
+            let mut fields = line.trim().split('/');
+            let chain = fields.next().and_then(|chain| chain.parse().ok()).ok_or_else(invalid)?;
+            let emitter = fields
+                .next()
+                .and_then(|emitter| from_hex(emitter).ok())
+                .and_then(|emitter| <[u8; 32]>::try_from(emitter.as_slice()).ok())
+                .ok_or_else(invalid)?;
+            let sequence = fields.next().and_then(|sequence| sequence.parse().ok()).ok_or_else(invalid)?;
+            processed.insert((chain, emitter, sequence));
+        }
+        Ok(Self { path, processed })
+    }
+}
+
+impl SequenceStore for FileSequenceStore {
+    fn is_processed(&self, emitter_chain: u16, emitter_address: &[u8; 32], sequence: u64) -> bool {
+        self.processed.contains(&(emitter_chain, *emitter_address, sequence))
+    }
+
+    fn mark_processed(&mut self, emitter_chain: u16, emitter_address: &[u8; 32], sequence: u64) -> CCIHSResult<()> {
+        let id = (emitter_chain, *emitter_address, sequence);
+        if self.processed.contains(&id) {
+            return Ok(());
+        }
+        // Recorded only once the line is written, so a failed write leaves
+        // the VAA to be redeemed again rather than skipped until restart.
+        let error = |e: std::io::Error| CCIHSError::Io(format!("{}: {}", self.path.display(), e));
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(error)?;
+        writeln!(file, "{}/{}/{}", emitter_chain, to_hex(emitter_address), sequence).map_err(error)?;
+        self.processed.insert(id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    fn path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ccihs-store-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_reloads_processed_vaas() {
+        let path = path("reload");
+        let _ = std::fs::remove_file(&path);
+
+        let mut store = FileSequenceStore::open(&path).unwrap();
+        store.mark_processed(2, &[0xcc; 32], 4).unwrap();
+        store.mark_processed(2, &[0xcc; 32], 4).unwrap();
+
+        let store = FileSequenceStore::open(&path).unwrap();
+        assert!(store.is_processed(2, &[0xcc; 32], 4));
+        assert!(!store.is_processed(2, &[0xcc; 32], 5));
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_failed_writes_leave_vaas_unprocessed() {
+        let mut store = FileSequenceStore::open(path("missing").join("processed")).unwrap();
+
+        assert!(matches!(store.mark_processed(2, &[0xcc; 32], 4), Err(CCIHSError::Io(_))));
+        assert!(!store.is_processed(2, &[0xcc; 32], 4));
+    }
+}
@@ -0,0 +1,151 @@
+// src/relayer/submitter.rs
+
+use crate::client::{sign_transaction, ChainClient, TransactionSigner};
+use crate::protocols::wormhole::{InstructionBuilder, ParsedVaa, PostedVaaInfo};
+use crate::{CCIHSError, CCIHSResult};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::pubkey::Pubkey;
+
+/// Lands the relayer's transactions on Solana.
+pub trait TransactionSubmitter {
+    /// Verifies the guardian signatures of `vaa` and posts it to the core
+    /// bridge. Must succeed before the VAA can be redeemed.
+    fn post_vaa(&mut self, vaa: &ParsedVaa) -> CCIHSResult<()>;
+
+    /// Signs `instructions` with the relayer's payer, submits them as one
+    /// transaction and returns its signature.
+    fn submit(&mut self, instructions: Vec<Instruction>) -> CCIHSResult<String>;
+}
+
+#[derive(Default)]
+/// Records everything instead of submitting it. Submissions fail while
+/// `failure` is set.
+pub struct MockSubmitter {
+    pub posted: Vec<PostedVaaInfo>,
+    pub submitted: Vec<Vec<Instruction>>,
+    pub failure: Option<String>,
+}
+
+impl MockSubmitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TransactionSubmitter for MockSubmitter {
+    fn post_vaa(&mut self, vaa: &ParsedVaa) -> CCIHSResult<()> {
+        let info = vaa.posted_vaa_info();
+        if !self.posted.contains(&info) {
+            self.posted.push(info);
+        }
+        Ok(())
+    }
+
+    fn submit(&mut self, instructions: Vec<Instruction>) -> CCIHSResult<String> {
+        if let Some(reason) = &self.failure {
+            return Err(CCIHSError::NetworkError(reason.clone()));
+        }
+        self.submitted.push(instructions);
+        Ok(format!("mock-signature-{}", self.submitted.len()))
+    }
+}
+
+/// Submits through a [`ChainClient`], signing with a [`TransactionSigner`]
+/// that must also be the engine's payer.
+///
+/// It does not verify guardian signatures itself: a VAA is only redeemed
+/// once someone, e.g. the guardian spy's poster, has posted it to the core
+/// bridge. Until then `post_vaa` fails and the engine retries later.
+pub struct ChainSubmitter<C, S> {
+    client: C,
+    signer: S,
+    builder: InstructionBuilder,
+}
+
+impl<C: ChainClient, S: TransactionSigner> ChainSubmitter<C, S> {
+    pub fn new(client: C, signer: S, builder: InstructionBuilder) -> Self {
+        Self { client, signer, builder }
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.signer.pubkey()
+    }
+
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+}
+
+impl<C: ChainClient, S: TransactionSigner> TransactionSubmitter for ChainSubmitter<C, S> {
+    fn post_vaa(&mut self, vaa: &ParsedVaa) -> CCIHSResult<()> {
+        match self.client.get_account(&self.builder.posted_vaa(&vaa.hash()))? {
+            Some(_) => Ok(()),
+            None => Err(CCIHSError::ProtocolError(format!(
+                "VAA {}/{} is not posted to the core bridge yet",
+                vaa.emitter_chain, vaa.sequence
+            ))),
+        }
+    }
+
+    fn submit(&mut self, instructions: Vec<Instruction>) -> CCIHSResult<String> {
+        let blockhash = self.client.get_latest_blockhash()?;
+        let transaction = sign_transaction(&self.signer, &instructions, blockhash)?;
+        self.client.send_transaction(&transaction)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::client::{AccountData, FakeChainClient};
+    use anchor_lang::solana_program::instruction::AccountMeta;
+
+    struct FixedSigner(Pubkey);
+
+    impl TransactionSigner for FixedSigner {
+        fn pubkey(&self) -> Pubkey {
+            self.0
+        }
+
+        fn sign_message(&self, _message: &[u8]) -> [u8; 64] {
+            [7; 64]
+        }
+    }
+
+    fn vaa() -> ParsedVaa {
+        let mut vaa = vec![1];
+        vaa.extend_from_slice(&0u32.to_be_bytes());
+        vaa.push(0);
+        vaa.extend_from_slice(&1_700_000_000u32.to_be_bytes());
+        vaa.extend_from_slice(&0u32.to_be_bytes());
+        vaa.extend_from_slice(&2u16.to_be_bytes());
+        vaa.extend_from_slice(&[0xaa; 32]);
+        vaa.extend_from_slice(&1u64.to_be_bytes());
+        vaa.push(1);
+        vaa.extend_from_slice(b"hello");
+        ParsedVaa::parse(&vaa).unwrap()
+    }
+
+    #[test]
+    fn test_submits_signed_transactions_for_posted_vaas() {
+        let builder = InstructionBuilder::new(Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let posted_vaa = builder.posted_vaa(&vaa().hash());
+        let payer = Pubkey::new_unique();
+        let mut submitter = ChainSubmitter::new(FakeChainClient::new(), FixedSigner(payer), builder);
+
+        assert!(submitter.post_vaa(&vaa()).is_err());
+        submitter.client().set_account(posted_vaa, AccountData::default());
+        submitter.post_vaa(&vaa()).unwrap();
+
+        let instruction = Instruction::new_with_bytes(Pubkey::new_unique(), b"data", vec![AccountMeta::new(payer, true)]);
+        let signature = submitter.submit(vec![instruction]).unwrap();
+        let sent = submitter.client().sent_transactions();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0][0], 1);
+        assert_eq!(sent[0][1..65], [7; 64]);
+        assert!(submitter.client().get_signature_status(&signature).unwrap().is_some());
+
+        let cosigned = Instruction::new_with_bytes(Pubkey::new_unique(), b"data", vec![AccountMeta::new(Pubkey::new_unique(), true)]);
+        assert!(submitter.submit(vec![cosigned]).is_err());
+    }
+}
@@ -10,6 +10,18 @@ pub struct CrossChainMessageState {
     pub bump: u8,
 }
 
+#[cfg(feature = "anchor")]
+impl CrossChainMessageState {
+    pub const MAXIMUM_SIZE: usize = 8 // discriminator
+        + 8 // last_nonce
+        + 8 // message_count
+        + 8 // last_message_timestamp
+        + 1 // bump
+    ;
+    /// AKA `b"cross_chain_state"`.
+    pub const SEED_PREFIX: &'static [u8] = crate::state::common::STATE_SEED_PREFIX;
+}
+
 #[cfg(feature = "anchor")]
 impl crate::state::common::CrossChainMessageStateTrait for CrossChainMessageState {
     fn new() -> Self {
@@ -32,4 +44,29 @@ impl crate::state::common::CrossChainMessageStateTrait for CrossChainMessageStat
     fn last_nonce(&self) -> u64 { self.last_nonce }
     fn message_count(&self) -> u64 { self.message_count }
     fn last_message_timestamp(&self) -> i64 { self.last_message_timestamp }
-}
\ No newline at end of file
+}
+#[cfg(all(test, feature = "anchor"))]
+pub mod test {
+    use super::*;
+    use crate::state::common::CrossChainMessageStateTrait;
+
+    #[test]
+    fn test_maximum_size_fits_account() {
+        let state = CrossChainMessageState::new();
+        assert_eq!(
+            8 + state.try_to_vec().unwrap().len(),
+            CrossChainMessageState::MAXIMUM_SIZE
+        );
+    }
+
+    #[test]
+    fn test_update_keeps_highest_nonce() {
+        let mut state = CrossChainMessageState::new();
+        state.update_with_message(5, 100);
+        state.update_with_message(3, 101);
+
+        assert_eq!(state.last_nonce(), 5);
+        assert_eq!(state.message_count(), 2);
+        assert_eq!(state.last_message_timestamp(), 101);
+    }
+}
@@ -17,6 +17,15 @@ pub fn derive_state_address(program_id: &Pubkey, sender: &Pubkey) -> (Pubkey, u8
     )
 }
 
+/// State address of a sender on another chain. The emitter chain is part of
+/// the seeds since the same 32 byte address can exist on several chains.
+pub fn derive_foreign_state_address(program_id: &Pubkey, chain: u16, sender: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[STATE_SEED_PREFIX, &chain.to_le_bytes(), sender.as_ref()],
+        program_id
+    )
+}
+
 //I could remove the state folder later tho
 // The `state` folder in CCIHS is primarily used to define structures and utilities for managing cross-chain message state. Its main purposes are:
 
@@ -7,6 +7,14 @@ pub struct CrossChainMessageState {
     pub last_message_timestamp: i64,
 }
 
+impl CrossChainMessageState {
+    /// Borsh encoded size of the account data (there is no discriminator).
+    pub const LEN: usize = 8 // last_nonce
+        + 8 // message_count
+        + 8 // last_message_timestamp
+    ;
+}
+
 impl crate::state::common::CrossChainMessageStateTrait for CrossChainMessageState {
     fn new() -> Self {
         Self {
@@ -27,4 +35,26 @@ impl crate::state::common::CrossChainMessageStateTrait for CrossChainMessageStat
     fn last_nonce(&self) -> u64 { self.last_nonce }
     fn message_count(&self) -> u64 { self.message_count }
     fn last_message_timestamp(&self) -> i64 { self.last_message_timestamp }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::state::common::CrossChainMessageStateTrait;
+
+    #[test]
+    fn test_len_matches_encoding() {
+        let state = CrossChainMessageState::new();
+        assert_eq!(state.try_to_vec().unwrap().len(), CrossChainMessageState::LEN);
+    }
+
+    #[test]
+    fn test_update_keeps_highest_nonce() {
+        let mut state = CrossChainMessageState::new();
+        state.update_with_message(5, 100);
+        state.update_with_message(3, 101);
+
+        assert_eq!(state.last_nonce(), 5);
+        assert_eq!(state.message_count(), 2);
+        assert_eq!(state.last_message_timestamp(), 101);
+    }
+}
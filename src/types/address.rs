@@ -1,9 +1,83 @@
 use solana_program::pubkey::Pubkey;
 use ethereum_types::Address as EthereumAddress;
+use super::{AddressFormat, CCIHSResult, ChainId};
+use crate::CCIHSError;
+use crate::utility::bech32;
+use crate::utility::encoding::{from_hex, to_hex};
+use std::fmt;
 
 #[derive(PartialEq, Debug)]
 pub enum CrossChainAddress {
     Solana(Pubkey),
     Ethereum(EthereumAddress),
+    /// Cosmos SDK account: the bech32 prefix of its chain and 20 or 32
+    /// bytes.
+    Cosmos { hrp: String, bytes: Vec<u8> },
     // Add more as needed
-}
\ No newline at end of file
+}
+impl CrossChainAddress {
+    /// Left-padded 32 byte form used on the wire by Wormhole, CCTP and
+    /// Hyperlane.
+    pub fn to_bytes32(&self) -> [u8; 32] {
+        match self {
+            CrossChainAddress::Solana(pubkey) => pubkey.to_bytes(),
+            CrossChainAddress::Ethereum(address) => {
+                let mut bytes = [0u8; 32];
+                bytes[12..].copy_from_slice(address.as_bytes());
+                bytes
+            }
+            CrossChainAddress::Cosmos { bytes: address, .. } => {
+                let mut bytes = [0u8; 32];
+                bytes[32 - address.len()..].copy_from_slice(address);
+                bytes
+            }
+        }
+    }
+
+    /// Reads the 32 byte form of an address on `chain`.
+    pub fn from_bytes32(chain: ChainId, bytes: &[u8; 32]) -> CCIHSResult<Self> {
+        match chain.address_format() {
+            Some(AddressFormat::Solana) => Ok(CrossChainAddress::Solana(Pubkey::new_from_array(*bytes))),
+            Some(AddressFormat::Evm) if bytes[..12].iter().all(|&byte| byte == 0) => {
+                Ok(CrossChainAddress::Ethereum(EthereumAddress::from_slice(&bytes[12..])))
+            }
+            Some(AddressFormat::Evm) => Err(CCIHSError::InvalidAddress),
+            // Left-padded accounts are 20 bytes, anything else a 32 byte
+            // contract or module account.
+            Some(AddressFormat::Bech32 { hrp }) => Ok(CrossChainAddress::Cosmos {
+                hrp: hrp.to_string(),
+                bytes: match bytes[..12].iter().all(|&byte| byte == 0) {
+                    true => bytes[12..].to_vec(),
+                    false => bytes.to_vec(),
+                },
+            }),
+            None => Err(CCIHSError::InvalidChainId),
+        }
+    }
+
+    /// Parses the text form of an address on `chain`, as written by
+    /// `Display`.
+    pub fn parse(chain: ChainId, text: &str) -> CCIHSResult<Self> {
+        match chain.address_format() {
+            Some(format) if !format.is_valid(text) => Err(CCIHSError::InvalidAddress),
+            Some(AddressFormat::Solana) => text.parse().map(CrossChainAddress::Solana).map_err(|_| CCIHSError::InvalidAddress),
+            Some(AddressFormat::Evm) => Ok(CrossChainAddress::Ethereum(EthereumAddress::from_slice(&from_hex(text)?))),
+            Some(AddressFormat::Bech32 { .. }) => {
+                let (hrp, bytes) = bech32::decode(text)?;
+                Ok(CrossChainAddress::Cosmos { hrp, bytes })
+            }
+            None => Err(CCIHSError::InvalidChainId),
+        }
+    }
+}
+
+/// Base58 for Solana, lowercase `0x` hex for EVM chains, bech32 for Cosmos.
+impl fmt::Display for CrossChainAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrossChainAddress::Solana(pubkey) => write!(f, "{}", pubkey),
+            CrossChainAddress::Ethereum(address) => write!(f, "0x{}", to_hex(address.as_bytes())),
+            CrossChainAddress::Cosmos { hrp, bytes } => write!(f, "{}", bech32::encode(hrp, bytes)),
+        }
+    }
+}
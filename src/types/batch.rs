@@ -0,0 +1,159 @@
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use solana_program::keccak;
+use std::io;
+use std::io::Read;
+
+use crate::constants::MAX_PAYLOAD_SIZE;
+
+/// Payload ID used to tag a [`BatchMember`] on the wire.
+pub const PAYLOAD_ID_BATCH_MEMBER: u8 = 3;
+
+/// Maximum number of messages that can be sent as one batch.
+pub const MAX_BATCH_SIZE: usize = 16;
+
+/// Largest payload of a single batch member. Members travel as ordinary
+/// messages, so the member header has to fit in `MAX_PAYLOAD_SIZE` as well.
+pub const MAX_BATCH_MEMBER_PAYLOAD_LENGTH: usize = MAX_PAYLOAD_SIZE - BatchMember::HEADER_LENGTH;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Envelope wrapping the payload of one message sent as part of a batch.
+/// Every member carries the batch size and a hash over all member payloads,
+/// so the receiving side can tell when the whole batch has arrived and that
+/// nothing was swapped in.
+///
+/// Encoded as (all integers big endian):
+/// `PAYLOAD_ID_BATCH_MEMBER | batch_id: u32 | index: u16 | size: u16 |
+/// batch_hash | payload length: u32 | payload`.
+pub struct BatchMember {
+    /// Wormhole batch ID (AKA nonce) shared by every member.
+    pub batch_id: u32,
+    pub index: u16,
+    pub size: u16,
+    pub batch_hash: [u8; 32],
+    pub payload: Vec<u8>,
+}
+
+impl BatchMember {
+    /// Size of the encoded header, everything except the payload bytes.
+    pub const HEADER_LENGTH: usize = 1 // payload id
+        + 4 // batch_id
+        + 2 // index
+        + 2 // size
+        + 32 // batch_hash
+        + 4 // payload length
+    ;
+}
+
+impl AnchorSerialize for BatchMember {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        if self.payload.len() > MAX_BATCH_MEMBER_PAYLOAD_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("payload exceeds {MAX_BATCH_MEMBER_PAYLOAD_LENGTH} bytes"),
+            ));
+        }
+        writer.write_all(&[PAYLOAD_ID_BATCH_MEMBER])?;
+        writer.write_all(&self.batch_id.to_be_bytes())?;
+        writer.write_all(&self.index.to_be_bytes())?;
+        writer.write_all(&self.size.to_be_bytes())?;
+        writer.write_all(&self.batch_hash)?;
+        writer.write_all(&(self.payload.len() as u32).to_be_bytes())?;
+        writer.write_all(&self.payload)
+    }
+}
+
+impl AnchorDeserialize for BatchMember {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        Self::deserialize_reader(buf)
+    }
+
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut payload_id = [0u8; 1];
+        reader.read_exact(&mut payload_id)?;
+        if payload_id[0] != PAYLOAD_ID_BATCH_MEMBER {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid payload ID"));
+        }
+
+        let mut u32_buf = [0u8; 4];
+        reader.read_exact(&mut u32_buf)?;
+        let batch_id = u32::from_be_bytes(u32_buf);
+
+        let mut u16_buf = [0u8; 2];
+        reader.read_exact(&mut u16_buf)?;
+        let index = u16::from_be_bytes(u16_buf);
+        reader.read_exact(&mut u16_buf)?;
+        let size = u16::from_be_bytes(u16_buf);
+        if size == 0 || index >= size || size as usize > MAX_BATCH_SIZE {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid batch index"));
+        }
+
+        let mut batch_hash = [0u8; 32];
+        reader.read_exact(&mut batch_hash)?;
+
+        reader.read_exact(&mut u32_buf)?;
+        let length = u32::from_be_bytes(u32_buf) as usize;
+        if length > MAX_BATCH_MEMBER_PAYLOAD_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("payload exceeds {MAX_BATCH_MEMBER_PAYLOAD_LENGTH} bytes"),
+            ));
+        }
+        let mut payload = vec![0u8; length];
+        reader.read_exact(&mut payload)?;
+
+        Ok(BatchMember {
+            batch_id,
+            index,
+            size,
+            batch_hash,
+            payload,
+        })
+    }
+}
+
+/// Hash committing to every member payload of a batch, in order.
+pub fn batch_hash<'a, I>(payloads: I) -> [u8; 32]
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let hashes: Vec<[u8; 32]> = payloads
+        .into_iter()
+        .map(|payload| keccak::hash(payload).to_bytes())
+        .collect();
+    let slices: Vec<&[u8]> = hashes.iter().map(|hash| hash.as_ref()).collect();
+    keccak::hashv(&slices).to_bytes()
+}
+
+/// Derives a non-zero batch ID from the batch hash. Wormhole only uses the
+/// batch ID (AKA nonce) for grouping, so it does not need to be unique across
+/// batches; members are still matched on the full batch hash.
+pub fn derive_batch_id(batch_hash: &[u8; 32]) -> u32 {
+    let batch_id = u32::from_be_bytes([batch_hash[0], batch_hash[1], batch_hash[2], batch_hash[3]]);
+    batch_id.max(1)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_member_round_trip() {
+        let member = BatchMember {
+            batch_id: derive_batch_id(&batch_hash([b"a".as_ref(), b"bc".as_ref()])),
+            index: 1,
+            size: 2,
+            batch_hash: batch_hash([b"a".as_ref(), b"bc".as_ref()]),
+            payload: b"bc".to_vec(),
+        };
+        let encoded = member.try_to_vec().unwrap();
+        assert_eq!(encoded.len(), BatchMember::HEADER_LENGTH + 2);
+        assert_eq!(BatchMember::try_from_slice(&encoded).unwrap(), member);
+
+        let oversized = BatchMember {
+            payload: vec![0; MAX_BATCH_MEMBER_PAYLOAD_LENGTH + 1],
+            ..member
+        };
+        assert!(oversized.try_to_vec().is_err());
+        assert_ne!(batch_hash([b"a".as_ref(), b"bc".as_ref()]), batch_hash([b"bc".as_ref(), b"a".as_ref()]));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::utility::bech32;
 use std::fmt;
 
 
@@ -8,11 +9,65 @@ pub struct ChainId(pub u16);
 impl ChainId {
     pub const SOLANA: ChainId = ChainId(1);
     pub const ETHEREUM: ChainId = ChainId(2);
+    pub const OSMOSIS: ChainId = ChainId(20);
+    pub const COSMOS_HUB: ChainId = ChainId(4000);
     // Add more chain IDs as needed
 
     pub fn new(id: u16) -> Self {
         ChainId(id)
     }
+
+    /// Address format of the chain, for the chains whose format is known.
+    pub fn address_format(&self) -> Option<AddressFormat> {
+        match self.0 {
+            1 => Some(AddressFormat::Solana),
+            // Ethereum, BSC, Polygon, Avalanche, Fantom, Celo, Moonbeam,
+            // Arbitrum, Optimism, Base, and the Sepolia testnets of
+            // Ethereum, Arbitrum, Base and Optimism
+            2 | 4 | 5 | 6 | 10 | 14 | 16 | 23 | 24 | 30 | 10002..=10005 => Some(AddressFormat::Evm),
+            18 => Some(AddressFormat::Bech32 { hrp: "terra" }),
+            19 => Some(AddressFormat::Bech32 { hrp: "inj" }),
+            20 => Some(AddressFormat::Bech32 { hrp: "osmo" }),
+            32 => Some(AddressFormat::Bech32 { hrp: "sei" }),
+            4000 => Some(AddressFormat::Bech32 { hrp: "cosmos" }),
+            4001 => Some(AddressFormat::Bech32 { hrp: "evmos" }),
+            4002 => Some(AddressFormat::Bech32 { hrp: "kujira" }),
+            4003 => Some(AddressFormat::Bech32 { hrp: "neutron" }),
+            4004 => Some(AddressFormat::Bech32 { hrp: "celestia" }),
+            4005 => Some(AddressFormat::Bech32 { hrp: "stars" }),
+            4007 => Some(AddressFormat::Bech32 { hrp: "dym" }),
+            _ => None,
+        }
+    }
+
+    /// Whether the chain is a Cosmos SDK chain, reached over IBC.
+    pub fn is_cosmos(&self) -> bool {
+        matches!(self.address_format(), Some(AddressFormat::Bech32 { .. }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressFormat {
+    /// Base58 encoded 32 byte public key.
+    Solana,
+    /// `0x` followed by 40 hex characters.
+    Evm,
+    /// Bech32 with the chain's human readable part, holding a 20 byte
+    /// account or a 32 byte contract or module account.
+    Bech32 { hrp: &'static str },
+}
+
+impl AddressFormat {
+    pub fn is_valid(&self, address: &str) -> bool {
+        match self {
+            AddressFormat::Solana => address.parse::<solana_program::pubkey::Pubkey>().is_ok(),
+            AddressFormat::Evm => address
+                .strip_prefix("0x")
+                .map_or(false, |hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit())),
+            AddressFormat::Bech32 { hrp } => bech32::decode(address)
+                .map_or(false, |(prefix, data)| prefix == *hrp && matches!(data.len(), 20 | 32)),
+        }
+    }
 }
 
 impl fmt::Display for ChainId {
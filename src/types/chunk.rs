@@ -0,0 +1,223 @@
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use solana_program::keccak;
+use std::io;
+use std::io::Read;
+
+use super::{ChainId, CrossChainMessage};
+
+/// Payload ID used to tag a [`MessageChunk`] on the wire. `0` and `1` are
+/// taken by `CrossChainPayload::Initialize` and `CrossChainPayload::Message`.
+pub const PAYLOAD_ID_CHUNK: u8 = 2;
+
+/// Largest amount of payload bytes carried by a single fragment. Leaves room
+/// for the chunk header inside a `MESSAGE_MAX_LENGTH` sized `Received` account.
+pub const MAX_CHUNK_DATA_LENGTH: usize = 896;
+
+/// Largest payload that can be reassembled. The on-chain reassembly buffer
+/// tracks received fragments in a `u64` bitmap and is created through CPI, so
+/// it has to stay under Solana's 10KiB account creation limit.
+pub const MAX_REASSEMBLED_PAYLOAD_LENGTH: usize = 8 * 1024;
+
+/// Maximum number of fragments a single transfer can be split into.
+pub const MAX_CHUNKS_PER_TRANSFER: usize = 64;
+
+#[derive(Clone, Debug, PartialEq)]
+/// One sequenced fragment of a payload that is too large to fit in a single
+/// cross-chain message. Every fragment of the same payload shares a
+/// `transfer_id` and the keccak256 `checksum` of the whole payload, so the
+/// receiving side can accept fragments out of order and verify the result.
+///
+/// Encoded as (all integers big endian, like every other Wormhole payload):
+/// `PAYLOAD_ID_CHUNK | transfer_id | index: u16 | total: u16 | checksum |
+/// total_length: u32 | chunk_size: u16 | data length: u16 | data`.
+pub struct MessageChunk {
+    pub transfer_id: [u8; 32],
+    pub index: u16,
+    pub total: u16,
+    pub checksum: [u8; 32],
+    pub total_length: u32,
+    /// Size of every fragment except the last one.
+    pub chunk_size: u16,
+    pub data: Vec<u8>,
+}
+
+impl MessageChunk {
+    /// Size of the encoded header, everything except the data bytes.
+    pub const HEADER_LENGTH: usize = 1 // payload id
+        + 32 // transfer_id
+        + 2 // index
+        + 2 // total
+        + 32 // checksum
+        + 4 // total_length
+        + 2 // chunk_size
+        + 2 // data length
+    ;
+
+    /// Offset of this fragment's data inside the reassembled payload.
+    pub fn offset(&self) -> usize {
+        self.index as usize * self.chunk_size as usize
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.index + 1 == self.total
+    }
+
+    /// Checks that the fragment's data lines up with the header: every
+    /// fragment but the last is exactly `chunk_size` bytes long and the last
+    /// one ends at `total_length`.
+    pub fn is_well_formed(&self) -> bool {
+        let end = self.offset() + self.data.len();
+        if self.is_last() {
+            end == self.total_length as usize
+        } else {
+            self.data.len() == self.chunk_size as usize && end < self.total_length as usize
+        }
+    }
+}
+
+impl AnchorSerialize for MessageChunk {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        if self.data.len() > MAX_CHUNK_DATA_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("chunk exceeds {MAX_CHUNK_DATA_LENGTH} bytes"),
+            ));
+        }
+        writer.write_all(&[PAYLOAD_ID_CHUNK])?;
+        writer.write_all(&self.transfer_id)?;
+        writer.write_all(&self.index.to_be_bytes())?;
+        writer.write_all(&self.total.to_be_bytes())?;
+        writer.write_all(&self.checksum)?;
+        writer.write_all(&self.total_length.to_be_bytes())?;
+        writer.write_all(&self.chunk_size.to_be_bytes())?;
+        writer.write_all(&(self.data.len() as u16).to_be_bytes())?;
+        writer.write_all(&self.data)
+    }
+}
+
+impl AnchorDeserialize for MessageChunk {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        Self::deserialize_reader(buf)
+    }
+
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut payload_id = [0u8; 1];
+        reader.read_exact(&mut payload_id)?;
+        if payload_id[0] != PAYLOAD_ID_CHUNK {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid payload ID"));
+        }
+
+        let mut transfer_id = [0u8; 32];
+        reader.read_exact(&mut transfer_id)?;
+
+        let mut u16_buf = [0u8; 2];
+        reader.read_exact(&mut u16_buf)?;
+        let index = u16::from_be_bytes(u16_buf);
+        reader.read_exact(&mut u16_buf)?;
+        let total = u16::from_be_bytes(u16_buf);
+        if total == 0 || index >= total || total as usize > MAX_CHUNKS_PER_TRANSFER {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid chunk index"));
+        }
+
+        let mut checksum = [0u8; 32];
+        reader.read_exact(&mut checksum)?;
+
+        let mut u32_buf = [0u8; 4];
+        reader.read_exact(&mut u32_buf)?;
+        let total_length = u32::from_be_bytes(u32_buf);
+        if total_length as usize > MAX_REASSEMBLED_PAYLOAD_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("payload exceeds {MAX_REASSEMBLED_PAYLOAD_LENGTH} bytes"),
+            ));
+        }
+
+        reader.read_exact(&mut u16_buf)?;
+        let chunk_size = u16::from_be_bytes(u16_buf);
+
+        reader.read_exact(&mut u16_buf)?;
+        let length = u16::from_be_bytes(u16_buf) as usize;
+        if length > MAX_CHUNK_DATA_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("chunk exceeds {MAX_CHUNK_DATA_LENGTH} bytes"),
+            ));
+        }
+        let mut data = vec![0u8; length];
+        reader.read_exact(&mut data)?;
+
+        Ok(MessageChunk {
+            transfer_id,
+            index,
+            total,
+            checksum,
+            total_length,
+            chunk_size,
+            data,
+        })
+    }
+}
+
+/// Keccak256 checksum of a whole payload, shared by all of its fragments.
+pub fn payload_checksum(payload: &[u8]) -> [u8; 32] {
+    keccak::hash(payload).to_bytes()
+}
+
+/// Derives the transfer ID shared by all fragments of `message`'s payload.
+pub fn chunk_transfer_id(message: &CrossChainMessage, checksum: &[u8; 32]) -> [u8; 32] {
+    keccak::hashv(&[
+        &message.source_chain.0.to_be_bytes(),
+        &message.destination_chain.0.to_be_bytes(),
+        &message.nonce.to_be_bytes(),
+        &message.timestamp.to_be_bytes(),
+        checksum,
+    ])
+    .to_bytes()
+}
+
+/// Splits `payload` into fragments of at most `chunk_size` bytes.
+pub fn split_payload(
+    transfer_id: [u8; 32],
+    payload: &[u8],
+    chunk_size: usize,
+) -> io::Result<Vec<MessageChunk>> {
+    if chunk_size == 0 || chunk_size > MAX_CHUNK_DATA_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("chunk size must be between 1 and {MAX_CHUNK_DATA_LENGTH} bytes"),
+        ));
+    }
+    if payload.is_empty() || payload.len() > MAX_REASSEMBLED_PAYLOAD_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("payload must be between 1 and {MAX_REASSEMBLED_PAYLOAD_LENGTH} bytes"),
+        ));
+    }
+
+    let total = (payload.len() + chunk_size - 1) / chunk_size;
+    if total > MAX_CHUNKS_PER_TRANSFER {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("payload needs more than {MAX_CHUNKS_PER_TRANSFER} chunks"),
+        ));
+    }
+
+    let checksum = payload_checksum(payload);
+    Ok(payload
+        .chunks(chunk_size)
+        .enumerate()
+        .map(|(index, data)| MessageChunk {
+            transfer_id,
+            index: index as u16,
+            total: total as u16,
+            checksum,
+            total_length: payload.len() as u32,
+            chunk_size: chunk_size as u16,
+            data: data.to_vec(),
+        })
+        .collect())
+}
+
+/// Identifies a transfer being reassembled. Transfer IDs are only unique per
+/// source chain, so both are part of the key.
+pub type ChunkKey = (ChainId, [u8; 32]);
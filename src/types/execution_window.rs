@@ -0,0 +1,68 @@
+use super::CCIHSResult;
+use crate::CCIHSError;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// When a message may be executed, in unix seconds. Both bounds are
+/// optional: a message without `expires_at` never expires and one without
+/// `execute_after` can be executed as soon as it arrives.
+pub struct ExecutionWindow {
+    pub expires_at: Option<u64>,
+    pub execute_after: Option<u64>,
+}
+
+impl ExecutionWindow {
+    pub fn new(expires_at: Option<u64>, execute_after: Option<u64>) -> Self {
+        Self { expires_at, execute_after }
+    }
+
+    /// Whether the window opens before it closes.
+    pub fn is_valid(&self) -> bool {
+        match (self.expires_at, self.execute_after) {
+            (Some(expires_at), Some(execute_after)) => execute_after <= expires_at,
+            _ => true,
+        }
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.map_or(false, |expires_at| now > expires_at)
+    }
+
+    /// Whether the message asked for delayed execution and `now` is too early.
+    pub fn is_scheduled_after(&self, now: u64) -> bool {
+        self.execute_after.map_or(false, |execute_after| now < execute_after)
+    }
+
+    /// Checks that `now` lies in the window.
+    pub fn check(&self, now: u64) -> CCIHSResult<()> {
+        if self.is_expired(now) {
+            return Err(CCIHSError::MessageExpired);
+        }
+        match self.execute_after {
+            Some(execute_after) if now < execute_after => Err(CCIHSError::MessageNotYetExecutable(execute_after)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_unbounded_window_never_expires() {
+        let window = ExecutionWindow::default();
+        assert!(window.check(u64::MAX).is_ok());
+        assert!(window.is_valid());
+    }
+
+    #[test]
+    fn test_bounds() {
+        let window = ExecutionWindow::new(Some(200), Some(100));
+        assert!(matches!(window.check(99), Err(CCIHSError::MessageNotYetExecutable(100))));
+        assert!(window.check(100).is_ok());
+        assert!(window.check(200).is_ok());
+        assert!(matches!(window.check(201), Err(CCIHSError::MessageExpired)));
+
+        assert!(!ExecutionWindow::new(Some(100), Some(200)).is_valid());
+    }
+}
@@ -1,10 +1,12 @@
 use super::{CrossChainMessage, ChainId, CCIHSResult, MessageStatus};
 use crate::CCIHSError;
+use crate::utility::Clock;
 use std::log;
 //use crate::sol_log;
 
 pub use anchor_lang::solana_program::log::sol_log;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum HookType {
     PreDispatch,
     PostDispatch,
@@ -13,16 +15,16 @@ pub enum HookType {
 }
 
 impl HookType {
-    pub fn execute_default(&self, message: &mut CrossChainMessage, source_chain: ChainId, destination_chain: ChainId) -> CCIHSResult<()> {
+    pub fn execute_default(&self, message: &mut CrossChainMessage, source_chain: ChainId, destination_chain: ChainId, clock: &dyn Clock) -> CCIHSResult<()> {
         match self {
-            HookType::PreDispatch => self.default_pre_dispatch(message, source_chain, destination_chain),
+            HookType::PreDispatch => self.default_pre_dispatch(message, source_chain, destination_chain, clock),
             HookType::PostDispatch => self.default_post_dispatch(message, source_chain, destination_chain),
-            HookType::PreExecution => self.default_pre_execution(message, source_chain, destination_chain),
+            HookType::PreExecution => self.default_pre_execution(message, source_chain, destination_chain, clock),
             HookType::PostExecution => self.default_post_execution(message, source_chain, destination_chain),
         }
     }
 
-    fn default_pre_dispatch(&self, message: &mut CrossChainMessage, _source_chain: ChainId, _destination_chain: ChainId) -> CCIHSResult<()> {
+    fn default_pre_dispatch(&self, message: &mut CrossChainMessage, _source_chain: ChainId, _destination_chain: ChainId, clock: &dyn Clock) -> CCIHSResult<()> {
         log::info!("Performing default pre-dispatch checks");
         
         // Validate the message
@@ -36,10 +38,7 @@ impl HookType {
         }
 
         // Add a timestamp to the message
-        message.timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map_err(|_| CCIHSError::TimestampError)?
-            .as_secs();
+        message.timestamp = clock.unix_timestamp()?;
 
         Ok(())
     }
@@ -63,18 +62,11 @@ impl HookType {
         Ok(())
     }
 
-    fn default_pre_execution(&self, message: &mut CrossChainMessage, _source_chain: ChainId, _destination_chain: ChainId) -> CCIHSResult<()> {
+    fn default_pre_execution(&self, message: &mut CrossChainMessage, _source_chain: ChainId, _destination_chain: ChainId, clock: &dyn Clock) -> CCIHSResult<()> {
         log::info!("Performing default pre-execution checks");
         
-        // Verify that the message hasn't expired
-        let current_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map_err(|_| CCIHSError::TimestampError)?
-            .as_secs();
-
-        if current_time - message.timestamp > MAX_MESSAGE_AGE {
-            return Err(CCIHSError::MessageExpired);
-        }
+        // Verify that the message hasn't expired and may already be executed
+        message.check_execution_window(clock.unix_timestamp()?)?;
 
         // Verify that the source chain matches the expected chain
         if message.source_chain != source_chain {
@@ -102,14 +94,12 @@ impl HookType {
         // You could update some on-chain statistics here if needed
         // For example, incrementing a counter for messages received and executed
 
-        // If you're implementing a request-response pattern, you could trigger a response here
+        // Request/response messages are answered by `CCIHSAPI::process_incoming`
 
         Ok(())
     }
 }
 
-const MAX_MESSAGE_AGE: u64 = 3600; // 1 hour, adjust as needed
-
 fn is_supported_chain(chain_id: ChainId) -> bool {
     // Implement your chain support logic here
     // For example:
@@ -1,4 +1,5 @@
-use super::{chain::ChainId, CCIHSResult};
+use super::{chain::ChainId, CCIHSResult, ExecutionWindow, MessageId};
+use crate::CCIHSError;
 //use solana_program::pubkey::Pubkey;
 use anchor_lang::{prelude::Pubkey, AnchorDeserialize, AnchorSerialize};
 use std::io;
@@ -11,10 +12,10 @@ use borsh::{BorshSerialize, BorshDeserialize};
 #[cfg(feature = "anchor")]
 use anchor_lang::prelude::*;
 
-//I need to come back to this payload ID
-
-// const PAYLOAD_ID_INITIALIZE: u8 = 0;
-// const PAYLOAD_ID_MESSAGE: u8 = 1;
+// Payload IDs are the first byte of a message payload. Received messages are
+// dispatched to application handlers by this byte (see `core::HandlerRegistry`).
+pub const PAYLOAD_ID_INITIALIZE: u8 = 0;
+pub const PAYLOAD_ID_MESSAGE: u8 = 1;
 
 // pub const MAX_PAYLOAD_LENGTH: usize = 1024;
 
@@ -24,6 +25,15 @@ pub enum CrossChainPayload {
     Message { content: Vec<u8> },
 }
 
+impl CrossChainPayload {
+    pub fn payload_id(&self) -> u8 {
+        match self {
+            CrossChainPayload::Initialize { .. } => PAYLOAD_ID_INITIALIZE,
+            CrossChainPayload::Message { .. } => PAYLOAD_ID_MESSAGE,
+        }
+    }
+}
+
 #[cfg_attr(feature = "native", derive(BorshSerialize, BorshDeserialize))]
 #[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
 #[derive(Clone, Debug, PartialEq)]
@@ -40,6 +50,14 @@ pub struct CrossChainMessage {
     pub nonce: u32,
     pub timestamp: u64,
     pub consistency_level: u8,
+    /// Unix time after which the message must not be executed. `None`
+    /// never expires.
+    pub expires_at: Option<u64>,
+    /// Unix time before which the message must not be executed.
+    pub execute_after: Option<u64>,
+    /// ID the transport derived from its emitter and sequence, set by the
+    /// adapter that received the message. `None` until then.
+    pub message_id: Option<MessageId>,
 }
 
 pub enum MessageType {
@@ -57,6 +75,11 @@ pub enum MessageStatus {
     Delivered,
     Executed,
     Failed,
+    /// The execution window closed before the message was executed. The
+    /// source chain of a token transfer in this state is notified once with
+    /// a [`RefundNotice`](crate::types::RefundNotice); nothing refunds the
+    /// sender.
+    Expired,
 }
 
 impl CrossChainMessage {
@@ -81,14 +104,29 @@ impl CrossChainMessage {
             source_chain,
             destination_chain,
             nonce: 0, // This should be generated
-            timestamp: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs(),
+            timestamp: 0, // Set by the pre-dispatch hook from the injected clock
             consistency_level,
+            expires_at: None,
+            execute_after: None,
+            message_id: None,
         }
     }
 
+    /// Canonical ID: the transport's [`message_id`](Self::message_id) once
+    /// there is one, [`MessageId::of`] before.
+    pub fn id(&self) -> MessageId {
+        self.message_id.unwrap_or_else(|| MessageId::of(self))
+    }
+
+    pub fn execution_window(&self) -> ExecutionWindow {
+        ExecutionWindow::new(self.expires_at, self.execute_after)
+    }
+
+    /// Checks that `now` lies in the message's execution window.
+    pub fn check_execution_window(&self, now: u64) -> CCIHSResult<()> {
+        self.execution_window().check(now)
+    }
+
     
 impl AnchorSerialize for CrossChainMessage {
     fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
@@ -106,7 +144,9 @@ impl AnchorSerialize for CrossChainMessage {
         self.payload.serialize(writer)?;
         
         self.nonce.serialize(writer)?;
-        self.timestamp.serialize(writer)
+        self.timestamp.serialize(writer)?;
+        self.expires_at.serialize(writer)?;
+        self.execute_after.serialize(writer)
     }
 }
 
@@ -127,6 +167,8 @@ impl AnchorDeserialize for CrossChainMessage {
         
         let nonce = u64::deserialize(buf)?;
         let timestamp = u64::deserialize(buf)?;
+        let expires_at = Option::<u64>::deserialize(buf)?;
+        let execute_after = Option::<u64>::deserialize(buf)?;
 
         Ok(CrossChainMessage {
             source_chain,
@@ -136,6 +178,8 @@ impl AnchorDeserialize for CrossChainMessage {
             payload,
             nonce,
             timestamp,
+            expires_at,
+            execute_after,
         })
     }
 }
@@ -161,4 +205,10 @@ pub struct CrossChainTransaction {
     pub message: CrossChainMessage,
     pub status: MessageStatus,
     pub transaction_hash: Option<[u8; 32]>,
+}
+
+impl CrossChainTransaction {
+    pub fn message_id(&self) -> MessageId {
+        self.message.id()
+    }
 }
\ No newline at end of file
@@ -0,0 +1,109 @@
+use super::{ChainId, CrossChainMessage};
+use crate::utility::encoding::{from_hex, to_hex};
+use crate::{CCIHSError, CCIHSResult};
+use anchor_lang::solana_program::keccak;
+use std::fmt;
+
+#[cfg(feature = "native")]
+use borsh::{BorshSerialize, BorshDeserialize};
+
+#[cfg(feature = "anchor")]
+use anchor_lang::prelude::*;
+
+/// First byte of the encoding a [`MessageId`] is hashed from. Bumped if the
+/// encoding ever changes, so IDs of different versions cannot collide.
+pub const MESSAGE_ID_VERSION: u8 = 1;
+
+#[cfg_attr(feature = "native", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// Canonical ID of a cross-chain message: keccak256 of
+///
+/// `MESSAGE_ID_VERSION | source_chain: u16 | emitter: [u8; 32] | sequence: u64 | keccak256(payload)`
+///
+/// with integers big endian. Only needs the keccak syscall, so programs and
+/// off-chain code derive the same ID for the same message. It keys status
+/// tracking and replay protection, and correlates the deliveries of one
+/// message by several protocols.
+pub struct MessageId(pub [u8; 32]);
+
+impl MessageId {
+    pub fn compute(source_chain: ChainId, emitter: &[u8; 32], sequence: u64, payload: &[u8]) -> Self {
+        let payload_hash = keccak::hash(payload).to_bytes();
+        MessageId(
+            keccak::hashv(&[
+                &[MESSAGE_ID_VERSION],
+                &source_chain.0.to_be_bytes(),
+                emitter,
+                &sequence.to_be_bytes(),
+                &payload_hash,
+            ])
+            .to_bytes(),
+        )
+    }
+
+    /// ID of a message no transport has given an emitter and sequence yet,
+    /// with its sender and nonce in their place. Nonces are not unique, so
+    /// received messages are keyed on the ID their adapter computed with
+    /// [`compute`](Self::compute) instead, see
+    /// [`CrossChainMessage::message_id`].
+    pub fn of(message: &CrossChainMessage) -> Self {
+        Self::compute(
+            message.source_chain,
+            &message.sender.to_bytes32(),
+            message.nonce as u64,
+            &message.payload,
+        )
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    pub fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+
+    /// Parses the 64 hex digits [`Display`](fmt::Display) prints, with or
+    /// without a `0x` prefix.
+    pub fn from_hex(hex: &str) -> CCIHSResult<Self> {
+        let bytes = from_hex(hex)?;
+        <[u8; 32]>::try_from(bytes.as_slice())
+            .map(MessageId)
+            .map_err(|_| CCIHSError::DeserializationError(format!("message ID must be 32 bytes, got {}", bytes.len())))
+    }
+}
+
+impl From<[u8; 32]> for MessageId {
+    fn from(bytes: [u8; 32]) -> Self {
+        MessageId(bytes)
+    }
+}
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{}", to_hex(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_canonical_encoding() {
+        let emitter = [0xee; 32];
+        let id = MessageId::compute(ChainId::ETHEREUM, &emitter, 9, b"payload");
+
+        let mut encoding = vec![MESSAGE_ID_VERSION, 0, 2];
+        encoding.extend_from_slice(&emitter);
+        encoding.extend_from_slice(&9u64.to_be_bytes());
+        encoding.extend_from_slice(&keccak::hash(b"payload").to_bytes());
+        assert_eq!(id.0, keccak::hash(&encoding).to_bytes());
+
+        assert_ne!(id, MessageId::compute(ChainId::ETHEREUM, &emitter, 10, b"payload"));
+        assert_ne!(id, MessageId::compute(ChainId::SOLANA, &emitter, 9, b"payload"));
+        assert_eq!(MessageId::from_hex(&id.to_string()).unwrap(), id);
+        assert!(MessageId::from_hex("0xabcd").is_err());
+    }
+}
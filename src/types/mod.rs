@@ -7,13 +7,33 @@ mod config;
 mod address;
 mod crosschain_fee;
 mod nonce;
+mod chunk;
+mod batch;
+mod request;
+mod refund;
+mod message_id;
+mod execution_window;
+mod quorum;
 
-pub use chain::ChainId;
-pub use message::{CrossChainMessage, CrossChainTransaction, MessageStatus, PostedCrossChainMessage};
+pub use chain::{ChainId, AddressFormat};
+pub use message::{
+    CrossChainMessage, CrossChainTransaction, MessageStatus, PostedCrossChainMessage,
+    CrossChainPayload, MessageType, PAYLOAD_ID_INITIALIZE, PAYLOAD_ID_MESSAGE,
+};
 pub use result::{CCIHSResult, CrossChainResult};
 pub use protocol::ProtocolType;
 pub use hook::{HookType, Hook};
 pub use config::CCIHSConfig;
 pub use address::CrossChainAddress;
 pub use crosschain_fee::CrossChainFee;
-pub use nonce::Nonce;
\ No newline at end of file
+pub use nonce::Nonce;
+pub use chunk::{
+    MessageChunk, ChunkKey, split_payload, payload_checksum, chunk_transfer_id,
+    PAYLOAD_ID_CHUNK, MAX_CHUNK_DATA_LENGTH, MAX_REASSEMBLED_PAYLOAD_LENGTH, MAX_CHUNKS_PER_TRANSFER,
+};
+pub use batch::{BatchMember, batch_hash, derive_batch_id, PAYLOAD_ID_BATCH_MEMBER, MAX_BATCH_SIZE, MAX_BATCH_MEMBER_PAYLOAD_LENGTH};
+pub use request::{RequestEnvelope, correlation_id, PAYLOAD_ID_REQUEST, PAYLOAD_ID_RESPONSE};
+pub use refund::{RefundNotice, PAYLOAD_ID_REFUND};
+pub use message_id::{MessageId, MESSAGE_ID_VERSION};
+pub use execution_window::ExecutionWindow;
+pub use quorum::{QuorumEnvelope, PAYLOAD_ID_QUORUM};
\ No newline at end of file
@@ -1,11 +1,17 @@
+use crate::types::CCIHSResult;
+use crate::utility::Clock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NONCE_COUNTER: AtomicU64 = AtomicU64::new(0);
 
 //This could help in managing nonces for cross-chain messages to prevent replay attacks.
 pub struct Nonce(pub u64);
 
 impl Nonce {
-    pub fn new() -> Self {
-        // Implementation to generate a unique nonce
-        // This is a placeholder; actual implementation would be more sophisticated
-        Nonce(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos() as u64)
+    pub fn new(clock: &dyn Clock) -> CCIHSResult<Self> {
+        // Timestamp in the high bits, a process wide counter in the low 20
+        // bits, so nonces generated within the same second still differ.
+        let counter = NONCE_COUNTER.fetch_add(1, Ordering::Relaxed) & 0xF_FFFF;
+        Ok(Nonce((clock.unix_timestamp()? << 20) | counter))
     }
-}
\ No newline at end of file
+}
@@ -5,5 +5,44 @@ use std::collections::HashSet;
 pub enum ProtocolType {
     Wormhole,
     LayerZero,
+    /// Circle's Cross-Chain Transfer Protocol, for native USDC.
+    Cctp,
+    Hyperlane,
+    /// Axelar General Message Passing.
+    Axelar,
+    /// IBC packets to Cosmos chains.
+    Ibc,
     // Add more as needed
-}
\ No newline at end of file
+}
+impl ProtocolType {
+    /// Parses the lowercase protocol name used in configuration files and
+    /// environment variables.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "wormhole" => Some(ProtocolType::Wormhole),
+            "layerzero" => Some(ProtocolType::LayerZero),
+            "cctp" => Some(ProtocolType::Cctp),
+            "hyperlane" => Some(ProtocolType::Hyperlane),
+            "axelar" => Some(ProtocolType::Axelar),
+            "ibc" => Some(ProtocolType::Ibc),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProtocolType::Wormhole => "wormhole",
+            ProtocolType::LayerZero => "layerzero",
+            ProtocolType::Cctp => "cctp",
+            ProtocolType::Hyperlane => "hyperlane",
+            ProtocolType::Axelar => "axelar",
+            ProtocolType::Ibc => "ibc",
+        }
+    }
+}
+
+impl std::fmt::Display for ProtocolType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use std::io;
+use std::io::Read;
+
+/// Payload ID used to tag a [`QuorumEnvelope`] on the wire.
+pub const PAYLOAD_ID_QUORUM: u8 = 7;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Wraps the payload of a message sent over several protocols for a quorum.
+/// Every protocol carries the same envelope, so the receiving side can tell
+/// deliveries of one message from deliveries of an identical one sent
+/// later: the sending core gives each message its own `sequence`.
+///
+/// Encoded as (all integers big endian):
+/// `PAYLOAD_ID_QUORUM | sequence: u64 | payload`.
+pub struct QuorumEnvelope {
+    pub sequence: u64,
+    pub payload: Vec<u8>,
+}
+
+impl AnchorSerialize for QuorumEnvelope {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&[PAYLOAD_ID_QUORUM])?;
+        writer.write_all(&self.sequence.to_be_bytes())?;
+        writer.write_all(&self.payload)
+    }
+}
+
+impl AnchorDeserialize for QuorumEnvelope {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        Self::deserialize_reader(buf)
+    }
+
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut payload_id = [0u8; 1];
+        reader.read_exact(&mut payload_id)?;
+        if payload_id[0] != PAYLOAD_ID_QUORUM {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid payload ID"));
+        }
+
+        let mut u64_buf = [0u8; 8];
+        reader.read_exact(&mut u64_buf)?;
+        let mut payload = Vec::new();
+        reader.read_to_end(&mut payload)?;
+
+        Ok(QuorumEnvelope {
+            sequence: u64::from_be_bytes(u64_buf),
+            payload,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let envelope = QuorumEnvelope {
+            sequence: 42,
+            payload: b"hello".to_vec(),
+        };
+        let encoded = envelope.try_to_vec().unwrap();
+        assert_eq!(encoded.len(), 1 + 8 + 5);
+        assert_eq!(encoded[0], PAYLOAD_ID_QUORUM);
+        assert_eq!(QuorumEnvelope::try_from_slice(&encoded).unwrap(), envelope);
+        assert!(QuorumEnvelope::try_from_slice(b"hello").is_err());
+    }
+}
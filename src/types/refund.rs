@@ -0,0 +1,84 @@
+use super::MessageId;
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use std::io;
+use std::io::Read;
+
+/// Payload ID used to tag a [`RefundNotice`] on the wire.
+pub const PAYLOAD_ID_REFUND: u8 = 6;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Payload of the message telling the source chain of an expired token
+/// transfer that the transfer was not executed. It is only a notification:
+/// the notice carries no tokens and CCIHS has no handler that acts on it.
+/// The tokens of the expired transfer stay where they were when it expired,
+/// e.g. in Token Bridge custody, until the application recovers them.
+/// Identifies the original transfer by its [`MessageId`], plus its nonce and
+/// timestamp for senders that do not track IDs.
+///
+/// Encoded as (all integers big endian):
+/// `PAYLOAD_ID_REFUND | original_id: [u8; 32] | original_nonce: u32 | original_timestamp: u64 | amount: u64`.
+pub struct RefundNotice {
+    pub original_id: MessageId,
+    pub original_nonce: u32,
+    pub original_timestamp: u64,
+    pub amount: u64,
+}
+
+impl AnchorSerialize for RefundNotice {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&[PAYLOAD_ID_REFUND])?;
+        writer.write_all(self.original_id.as_bytes())?;
+        writer.write_all(&self.original_nonce.to_be_bytes())?;
+        writer.write_all(&self.original_timestamp.to_be_bytes())?;
+        writer.write_all(&self.amount.to_be_bytes())
+    }
+}
+
+impl AnchorDeserialize for RefundNotice {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        Self::deserialize_reader(buf)
+    }
+
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut payload_id = [0u8; 1];
+        reader.read_exact(&mut payload_id)?;
+        if payload_id[0] != PAYLOAD_ID_REFUND {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid payload ID"));
+        }
+
+        let mut original_id = [0u8; 32];
+        reader.read_exact(&mut original_id)?;
+        let mut u32_buf = [0u8; 4];
+        reader.read_exact(&mut u32_buf)?;
+        let mut u64_buf = [0u8; 8];
+        reader.read_exact(&mut u64_buf)?;
+        let original_timestamp = u64::from_be_bytes(u64_buf);
+        reader.read_exact(&mut u64_buf)?;
+
+        Ok(RefundNotice {
+            original_id: MessageId(original_id),
+            original_nonce: u32::from_be_bytes(u32_buf),
+            original_timestamp,
+            amount: u64::from_be_bytes(u64_buf),
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let notice = RefundNotice {
+            original_id: MessageId([7; 32]),
+            original_nonce: 3,
+            original_timestamp: 1_000,
+            amount: 50,
+        };
+        let encoded = notice.try_to_vec().unwrap();
+        assert_eq!(encoded.len(), 1 + 32 + 4 + 8 + 8);
+        assert_eq!(encoded[0], PAYLOAD_ID_REFUND);
+        assert_eq!(RefundNotice::try_from_slice(&encoded).unwrap(), notice);
+    }
+}
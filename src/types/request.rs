@@ -0,0 +1,149 @@
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use solana_program::keccak;
+use std::io;
+use std::io::Read;
+
+use super::{CrossChainMessage, MAX_REASSEMBLED_PAYLOAD_LENGTH};
+
+/// Payload ID used to tag a request envelope on the wire.
+pub const PAYLOAD_ID_REQUEST: u8 = 4;
+/// Payload ID used to tag a response envelope on the wire.
+pub const PAYLOAD_ID_RESPONSE: u8 = 5;
+
+#[derive(Clone, Debug, PartialEq)]
+/// Envelope for the request/response messaging pattern. A request carries a
+/// correlation ID and the time (unix seconds) after which the origin stops
+/// waiting; the response echoes the correlation ID back to the origin chain.
+///
+/// Encoded as (all integers big endian):
+/// * Request: `PAYLOAD_ID_REQUEST | correlation_id | deadline: u64 | payload length: u32 | payload`
+/// * Response: `PAYLOAD_ID_RESPONSE | correlation_id | success: u8 | payload length: u32 | payload`
+pub enum RequestEnvelope {
+    Request {
+        correlation_id: [u8; 32],
+        deadline: u64,
+        payload: Vec<u8>,
+    },
+    Response {
+        correlation_id: [u8; 32],
+        success: bool,
+        payload: Vec<u8>,
+    },
+}
+
+impl RequestEnvelope {
+    pub fn correlation_id(&self) -> &[u8; 32] {
+        match self {
+            RequestEnvelope::Request { correlation_id, .. } => correlation_id,
+            RequestEnvelope::Response { correlation_id, .. } => correlation_id,
+        }
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        match self {
+            RequestEnvelope::Request { payload, .. } => payload,
+            RequestEnvelope::Response { payload, .. } => payload,
+        }
+    }
+}
+
+fn write_payload<W: io::Write>(writer: &mut W, payload: &[u8]) -> io::Result<()> {
+    if payload.len() > MAX_REASSEMBLED_PAYLOAD_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("payload exceeds {MAX_REASSEMBLED_PAYLOAD_LENGTH} bytes"),
+        ));
+    }
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(payload)
+}
+
+fn read_payload<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut u32_buf = [0u8; 4];
+    reader.read_exact(&mut u32_buf)?;
+    let length = u32::from_be_bytes(u32_buf) as usize;
+    if length > MAX_REASSEMBLED_PAYLOAD_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("payload exceeds {MAX_REASSEMBLED_PAYLOAD_LENGTH} bytes"),
+        ));
+    }
+    let mut payload = vec![0u8; length];
+    reader.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+impl AnchorSerialize for RequestEnvelope {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            RequestEnvelope::Request { correlation_id, deadline, payload } => {
+                writer.write_all(&[PAYLOAD_ID_REQUEST])?;
+                writer.write_all(correlation_id)?;
+                writer.write_all(&deadline.to_be_bytes())?;
+                write_payload(writer, payload)
+            }
+            RequestEnvelope::Response { correlation_id, success, payload } => {
+                writer.write_all(&[PAYLOAD_ID_RESPONSE])?;
+                writer.write_all(correlation_id)?;
+                writer.write_all(&[*success as u8])?;
+                write_payload(writer, payload)
+            }
+        }
+    }
+}
+
+impl AnchorDeserialize for RequestEnvelope {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        Self::deserialize_reader(buf)
+    }
+
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut payload_id = [0u8; 1];
+        reader.read_exact(&mut payload_id)?;
+
+        let mut correlation_id = [0u8; 32];
+        match payload_id[0] {
+            PAYLOAD_ID_REQUEST => {
+                reader.read_exact(&mut correlation_id)?;
+                let mut u64_buf = [0u8; 8];
+                reader.read_exact(&mut u64_buf)?;
+                Ok(RequestEnvelope::Request {
+                    correlation_id,
+                    deadline: u64::from_be_bytes(u64_buf),
+                    payload: read_payload(reader)?,
+                })
+            }
+            PAYLOAD_ID_RESPONSE => {
+                reader.read_exact(&mut correlation_id)?;
+                let mut success = [0u8; 1];
+                reader.read_exact(&mut success)?;
+                let success = match success[0] {
+                    0 => false,
+                    1 => true,
+                    _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid success flag")),
+                };
+                Ok(RequestEnvelope::Response {
+                    correlation_id,
+                    success,
+                    payload: read_payload(reader)?,
+                })
+            }
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "invalid payload ID")),
+        }
+    }
+}
+
+/// Derives the correlation ID of a request from its route, parties and
+/// payload, and a `request_nonce` unique to this request (see
+/// [`Nonce`](super::Nonce)), so identical requests get different IDs.
+pub fn correlation_id(message: &CrossChainMessage, request_nonce: u64) -> [u8; 32] {
+    keccak::hashv(&[
+        &message.source_chain.0.to_be_bytes(),
+        &message.destination_chain.0.to_be_bytes(),
+        &message.sender.to_bytes32(),
+        &message.recipient.to_bytes32(),
+        &request_nonce.to_be_bytes(),
+        &message.payload,
+    ])
+    .to_bytes()
+}
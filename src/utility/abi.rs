@@ -0,0 +1,196 @@
+// src/utility/abi.rs
+
+//! Solidity ABI encoding (`abi.encode`) for the types CCIHS hands to EVM
+//! contracts or hashes the way they do, and decoding of what EVM contracts
+//! hand back, e.g. event data.
+
+use super::ecdsa::EvmAddress;
+use crate::{CCIHSError, CCIHSResult};
+use solana_program::keccak;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    /// Any `uintN` up to 128 bits.
+    Uint(u128),
+    /// A full `uint256`, big endian.
+    Uint256([u8; 32]),
+    Address(EvmAddress),
+    FixedBytes32([u8; 32]),
+    Bytes(Vec<u8>),
+    String(String),
+    /// A dynamic array `T[]`.
+    Array(Vec<Token>),
+    Tuple(Vec<Token>),
+}
+
+impl Token {
+    fn is_dynamic(&self) -> bool {
+        match self {
+            Token::Bytes(_) | Token::String(_) | Token::Array(_) => true,
+            Token::Tuple(tokens) => tokens.iter().any(Token::is_dynamic),
+            _ => false,
+        }
+    }
+}
+
+fn word(value: u128) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+fn encode_token(token: &Token) -> Vec<u8> {
+    match token {
+        Token::Uint(value) => word(*value).to_vec(),
+        Token::Uint256(value) | Token::FixedBytes32(value) => value.to_vec(),
+        Token::Address(address) => {
+            let mut word = [0u8; 32];
+            word[12..].copy_from_slice(address);
+            word.to_vec()
+        }
+        Token::Bytes(bytes) => encode_bytes(bytes),
+        Token::String(text) => encode_bytes(text.as_bytes()),
+        Token::Array(tokens) => [&word(tokens.len() as u128)[..], &encode(tokens)].concat(),
+        Token::Tuple(tokens) => encode(tokens),
+    }
+}
+
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = word(bytes.len() as u128).to_vec();
+    encoded.extend_from_slice(bytes);
+    encoded.resize(32 + (bytes.len() + 31) / 32 * 32, 0);
+    encoded
+}
+
+/// `abi.encode(tokens...)`.
+pub fn encode(tokens: &[Token]) -> Vec<u8> {
+    let head_length: usize = tokens
+        .iter()
+        .map(|token| if token.is_dynamic() { 32 } else { encode_token(token).len() })
+        .sum();
+    let mut head = Vec::with_capacity(head_length);
+    let mut tail = Vec::new();
+    for token in tokens {
+        if token.is_dynamic() {
+            head.extend_from_slice(&word((head_length + tail.len()) as u128));
+            tail.extend(encode_token(token));
+        } else {
+            head.extend(encode_token(token));
+        }
+    }
+    head.extend(tail);
+    head
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Types [`decode`] understands.
+pub enum ParamType {
+    /// Any `uintN` up to 128 bits.
+    Uint,
+    Uint256,
+    Address,
+    FixedBytes32,
+    Bytes,
+    String,
+}
+
+fn decode_error(reason: impl Into<String>) -> CCIHSError {
+    CCIHSError::DeserializationError(format!("ABI: {}", reason.into()))
+}
+
+fn read_word(data: &[u8], offset: usize) -> CCIHSResult<[u8; 32]> {
+    let mut word = [0u8; 32];
+    word.copy_from_slice(
+        data.get(offset..offset + 32)
+            .ok_or_else(|| decode_error(format!("data truncated at byte {}", offset)))?,
+    );
+    Ok(word)
+}
+
+fn read_usize(data: &[u8], offset: usize) -> CCIHSResult<usize> {
+    let word = read_word(data, offset)?;
+    if word[..24].iter().any(|&byte| byte != 0) {
+        return Err(decode_error(format!("offset or length at byte {} out of range", offset)));
+    }
+    Ok(u64::from_be_bytes(word[24..].try_into().expect("8 bytes")) as usize)
+}
+
+/// Decodes `abi.encode` output of the given types.
+pub fn decode(types: &[ParamType], data: &[u8]) -> CCIHSResult<Vec<Token>> {
+    types
+        .iter()
+        .enumerate()
+        .map(|(index, param)| {
+            let word = read_word(data, index * 32)?;
+            Ok(match param {
+                ParamType::Uint => {
+                    if word[..16].iter().any(|&byte| byte != 0) {
+                        return Err(decode_error(format!("value {} exceeds 128 bits", index)));
+                    }
+                    Token::Uint(u128::from_be_bytes(word[16..].try_into().expect("16 bytes")))
+                }
+                ParamType::Uint256 => Token::Uint256(word),
+                ParamType::Address => {
+                    let mut address = [0u8; 20];
+                    address.copy_from_slice(&word[12..]);
+                    Token::Address(address)
+                }
+                ParamType::FixedBytes32 => Token::FixedBytes32(word),
+                ParamType::Bytes | ParamType::String => {
+                    let offset = read_usize(data, index * 32)?;
+                    let length = read_usize(data, offset)?;
+                    let bytes = length
+                        .checked_add(32)
+                        .and_then(|size| offset.checked_add(size))
+                        .and_then(|end| data.get(end - length..end))
+                        .ok_or_else(|| decode_error(format!("value {} truncated", index)))?
+                        .to_vec();
+                    if *param == ParamType::String {
+                        Token::String(String::from_utf8(bytes).map_err(|_| decode_error("string is not UTF-8"))?)
+                    } else {
+                        Token::Bytes(bytes)
+                    }
+                }
+            })
+        })
+        .collect()
+}
+
+/// First four bytes of the keccak256 of a function signature such as
+/// `"transfer(address,uint256)"`.
+pub fn selector(signature: &str) -> [u8; 4] {
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&keccak::hash(signature.as_bytes()).to_bytes()[..4]);
+    selector
+}
+
+/// Calldata for calling `signature` with `tokens`.
+pub fn encode_call(signature: &str, tokens: &[Token]) -> Vec<u8> {
+    [&selector(signature)[..], &encode(tokens)].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utility::encoding::to_hex;
+
+    #[test]
+    fn encodes_static_and_dynamic_tokens() {
+        let encoded = encode(&[Token::Uint(1), Token::String("a".to_string())]);
+        assert_eq!(
+            to_hex(&encoded),
+            [
+                format!("{:064x}", 1),
+                format!("{:064x}", 0x40),
+                format!("{:064x}", 1),
+                format!("61{}", "0".repeat(62)),
+            ]
+            .concat()
+        );
+        assert_eq!(to_hex(&selector("transfer(address,uint256)")), "a9059cbb");
+        assert_eq!(
+            decode(&[ParamType::Uint, ParamType::String], &encoded).unwrap(),
+            vec![Token::Uint(1), Token::String("a".to_string())]
+        );
+    }
+}
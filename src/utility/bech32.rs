@@ -0,0 +1,117 @@
+// src/utility/bech32.rs
+
+//! Bech32 (BIP-173) as used for Cosmos SDK account addresses.
+
+use crate::{CCIHSError, CCIHSResult};
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+/// Longest string BIP-173 allows.
+const MAX_LENGTH: usize = 90;
+
+fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    values.into_iter().fold(1u32, |checksum, value| {
+        let top = checksum >> 25;
+        let mut checksum = (checksum & 0x1ffffff) << 5 ^ value as u32;
+        for (bit, generator) in GENERATOR.iter().enumerate() {
+            if top >> bit & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+        checksum
+    })
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    hrp.bytes()
+        .map(|byte| byte >> 5)
+        .chain([0])
+        .chain(hrp.bytes().map(|byte| byte & 31))
+        .collect()
+}
+
+/// Regroups `data` from `from` bit groups into `to` bit groups, padding the
+/// last group when `pad` is set.
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut accumulator = 0u32;
+    let mut bits = 0;
+    let mut output = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    let max = (1u32 << to) - 1;
+    for &value in data {
+        if (value as u32) >> from != 0 {
+            return None;
+        }
+        accumulator = accumulator << from | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            output.push((accumulator >> bits & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            output.push((accumulator << (to - bits) & max) as u8);
+        }
+    } else if bits >= from || (accumulator << (to - bits) & max) != 0 {
+        return None;
+    }
+    Some(output)
+}
+
+/// Encodes `data` under the human readable part `hrp`, e.g. `cosmos`.
+pub fn encode(hrp: &str, data: &[u8]) -> String {
+    let words = convert_bits(data, 8, 5, true).expect("bytes fit in eight bits");
+    let checksum = polymod(hrp_expand(hrp).into_iter().chain(words.iter().copied()).chain([0; 6])) ^ 1;
+    let mut output = format!("{}1", hrp);
+    for word in words.iter().copied().chain((0..6).map(|index| (checksum >> (5 * (5 - index)) & 31) as u8)) {
+        output.push(CHARSET[word as usize] as char);
+    }
+    output
+}
+
+/// Decodes a bech32 string into its human readable part and data.
+pub fn decode(text: &str) -> CCIHSResult<(String, Vec<u8>)> {
+    let invalid = |reason: &str| CCIHSError::DeserializationError(format!("invalid bech32: {}", reason));
+
+    if text.len() > MAX_LENGTH {
+        return Err(invalid("too long"));
+    }
+    if text.bytes().any(|byte| byte.is_ascii_lowercase()) && text.bytes().any(|byte| byte.is_ascii_uppercase()) {
+        return Err(invalid("mixed case"));
+    }
+    let text = text.to_ascii_lowercase();
+    let (hrp, data) = text.rsplit_once('1').ok_or_else(|| invalid("no separator"))?;
+    if hrp.is_empty() || data.len() < 6 || hrp.bytes().any(|byte| !(33..=126).contains(&byte)) {
+        return Err(invalid("malformed"));
+    }
+    let words = data
+        .bytes()
+        .map(|byte| CHARSET.iter().position(|&c| c == byte).map(|word| word as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| invalid("character outside the charset"))?;
+    if polymod(hrp_expand(hrp).into_iter().chain(words.iter().copied())) != 1 {
+        return Err(invalid("checksum mismatch"));
+    }
+    let bytes = convert_bits(&words[..words.len() - 6], 5, 8, false).ok_or_else(|| invalid("bad padding"))?;
+    Ok((hrp.to_string(), bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_cosmos_addresses() {
+        // BIP-173 test vector.
+        assert_eq!(decode("A12UEL5L").unwrap(), ("a".to_string(), Vec::new()));
+
+        let address = encode("cosmos", &[0x42; 20]);
+        assert!(address.starts_with("cosmos1"));
+        assert_eq!(decode(&address).unwrap(), ("cosmos".to_string(), vec![0x42; 20]));
+
+        let mut corrupted = address.into_bytes();
+        let last = corrupted.len() - 1;
+        corrupted[last] = if corrupted[last] == b'q' { b'p' } else { b'q' };
+        assert!(decode(std::str::from_utf8(&corrupted).unwrap()).is_err());
+    }
+}
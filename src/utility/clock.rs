@@ -0,0 +1,154 @@
+// src/utility/clock.rs
+
+use crate::types::CCIHSResult;
+use crate::CCIHSError;
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Source of the current time for everything time based in CCIHS (message
+/// timestamps, expiry checks, rate limiting, request deadlines).
+///
+/// `std::time::SystemTime` is unavailable inside a Solana program, so on-chain
+/// code must use [`SolanaClock`]; off-chain code uses [`SystemClock`] and
+/// tests use [`TestClock`].
+pub trait Clock: Send + Sync {
+    /// Current unix timestamp in seconds.
+    fn unix_timestamp(&self) -> CCIHSResult<u64>;
+
+    /// Current unix timestamp in milliseconds. Clocks with a resolution of
+    /// one second return the start of the current second.
+    fn unix_timestamp_millis(&self) -> CCIHSResult<u64> {
+        self.unix_timestamp()?
+            .checked_mul(1_000)
+            .ok_or(CCIHSError::TimestampError)
+    }
+}
+
+/// Reads the time from the Solana `Clock` sysvar.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SolanaClock;
+
+impl Clock for SolanaClock {
+    fn unix_timestamp(&self) -> CCIHSResult<u64> {
+        use solana_program::sysvar::Sysvar;
+
+        let clock = solana_program::clock::Clock::get().map_err(|_| CCIHSError::TimestampError)?;
+        u64::try_from(clock.unix_timestamp).map_err(|_| CCIHSError::TimestampError)
+    }
+}
+
+/// Reads the time from the operating system. Off-chain only.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn unix_timestamp(&self) -> CCIHSResult<u64> {
+        Ok(self.unix_timestamp_millis()? / 1_000)
+    }
+
+    fn unix_timestamp_millis(&self) -> CCIHSResult<u64> {
+        let elapsed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|_| CCIHSError::TimestampError)?;
+        u64::try_from(elapsed.as_millis()).map_err(|_| CCIHSError::TimestampError)
+    }
+}
+
+/// Clock that only moves when told to. Clones share the same time.
+#[derive(Clone, Debug, Default)]
+pub struct TestClock {
+    now_millis: Arc<AtomicU64>,
+}
+
+impl TestClock {
+    /// Starts at `now` seconds.
+    pub fn new(now: u64) -> Self {
+        Self {
+            now_millis: Arc::new(AtomicU64::new(now * 1_000)),
+        }
+    }
+
+    pub fn set(&self, now: u64) {
+        self.now_millis.store(now * 1_000, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, seconds: u64) {
+        self.advance_millis(seconds * 1_000);
+    }
+
+    pub fn advance_millis(&self, millis: u64) {
+        self.now_millis.fetch_add(millis, Ordering::SeqCst);
+    }
+}
+
+impl Clock for TestClock {
+    fn unix_timestamp(&self) -> CCIHSResult<u64> {
+        Ok(self.now_millis.load(Ordering::SeqCst) / 1_000)
+    }
+
+    fn unix_timestamp_millis(&self) -> CCIHSResult<u64> {
+        Ok(self.now_millis.load(Ordering::SeqCst))
+    }
+}
+
+/// Clock reading from another clock that can be replaced while hooks and
+/// other components hold on to it. `CCIHSCore` hands this out, so
+/// `set_clock` reaches everything built from its clock.
+pub struct SharedClock {
+    inner: RwLock<Arc<dyn Clock>>,
+}
+
+impl SharedClock {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            inner: RwLock::new(clock),
+        }
+    }
+
+    pub fn replace(&self, clock: Arc<dyn Clock>) {
+        *self.inner.write() = clock;
+    }
+}
+
+impl Clock for SharedClock {
+    fn unix_timestamp(&self) -> CCIHSResult<u64> {
+        self.inner.read().unix_timestamp()
+    }
+
+    fn unix_timestamp_millis(&self) -> CCIHSResult<u64> {
+        self.inner.read().unix_timestamp_millis()
+    }
+}
+
+/// Clock used when none is injected: the sysvar inside a Solana program, the
+/// system time everywhere else.
+pub fn default_clock() -> Arc<dyn Clock> {
+    #[cfg(target_os = "solana")]
+    {
+        Arc::new(SolanaClock)
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        Arc::new(SystemClock)
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    #[test]
+    fn test_shared_clock_follows_replacement() {
+        let first = TestClock::new(10);
+        let shared = Arc::new(SharedClock::new(Arc::new(first.clone())));
+        let handle: Arc<dyn Clock> = shared.clone();
+
+        first.advance_millis(1_500);
+        assert_eq!(handle.unix_timestamp().unwrap(), 11);
+        assert_eq!(handle.unix_timestamp_millis().unwrap(), 11_500);
+
+        shared.replace(Arc::new(TestClock::new(99)));
+        assert_eq!(handle.unix_timestamp().unwrap(), 99);
+    }
+}
@@ -0,0 +1,71 @@
+// src/utility/ecdsa.rs
+
+//! secp256k1 signatures as produced by EVM signers: 65 bytes `r | s | v`
+//! with `v` either 0/1 or 27/28. Signers are identified by their 20 byte EVM
+//! address.
+
+use crate::{CCIHSError, CCIHSResult};
+use solana_program::keccak;
+use solana_program::secp256k1_recover::secp256k1_recover;
+
+/// Half the secp256k1 group order. Signatures with a larger `s` are the
+/// malleated twin of a valid signature and are rejected, as EVM contracts do.
+const HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+pub type EvmAddress = [u8; 20];
+
+/// Length of a `r | s | v` signature.
+pub const SIGNATURE_LENGTH: usize = 65;
+
+/// `keccak256("\x19Ethereum Signed Message:\n32" | hash)`.
+pub fn eth_signed_message_hash(hash: &[u8; 32]) -> [u8; 32] {
+    keccak::hashv(&[b"\x19Ethereum Signed Message:\n32", hash]).to_bytes()
+}
+
+/// Recovers the address that signed `digest`.
+pub fn recover_address(digest: &[u8; 32], signature: &[u8; SIGNATURE_LENGTH]) -> CCIHSResult<EvmAddress> {
+    let invalid = |reason: &str| CCIHSError::InvalidAttestation(reason.to_string());
+
+    if signature[32..64] > HALF_ORDER[..] {
+        return Err(invalid("signature has a high s value"));
+    }
+    let recovery_id = match signature[64] {
+        0 | 27 => 0,
+        1 | 28 => 1,
+        _ => return Err(invalid("invalid signature recovery ID")),
+    };
+    let public_key = secp256k1_recover(digest, recovery_id, &signature[..64])
+        .map_err(|_| invalid("signature does not recover to a public key"))?;
+
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&keccak::hash(&public_key.to_bytes()).to_bytes()[12..]);
+    Ok(address)
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::EvmAddress;
+    use solana_program::keccak;
+
+    pub fn secret_key(seed: u8) -> libsecp256k1::SecretKey {
+        libsecp256k1::SecretKey::parse(&[seed; 32]).expect("valid secret key")
+    }
+
+    pub fn address(secret_key: &libsecp256k1::SecretKey) -> EvmAddress {
+        let public_key = libsecp256k1::PublicKey::from_secret_key(secret_key).serialize();
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&keccak::hash(&public_key[1..]).to_bytes()[12..]);
+        address
+    }
+
+    pub fn sign(secret_key: &libsecp256k1::SecretKey, digest: &[u8; 32]) -> [u8; 65] {
+        let (signature, recovery_id) = libsecp256k1::sign(&libsecp256k1::Message::parse(digest), secret_key);
+        let mut bytes = [0u8; 65];
+        bytes[..64].copy_from_slice(&signature.serialize());
+        bytes[64] = recovery_id.serialize() + 27;
+        bytes
+    }
+}
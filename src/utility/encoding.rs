@@ -0,0 +1,107 @@
+// src/utility/encoding.rs
+
+use crate::{CCIHSError, CCIHSResult};
+use std::io::{self, Read};
+
+/// Lowercase hex without a `0x` prefix.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Parses hex with or without a `0x` prefix.
+pub fn from_hex(hex: &str) -> CCIHSResult<Vec<u8>> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if hex.len() % 2 != 0 {
+        return Err(CCIHSError::DeserializationError("hex string has an odd length".to_string()));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| {
+            hex.get(index..index + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| CCIHSError::DeserializationError(format!("invalid hex at offset {index}")))
+        })
+        .collect()
+}
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard base64 with padding.
+pub fn to_base64(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let group = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for index in 0..4 {
+            if index <= chunk.len() {
+                output.push(BASE64_ALPHABET[(group >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// Parses standard base64. Padding is optional.
+pub fn from_base64(text: &str) -> CCIHSResult<Vec<u8>> {
+    let text = text.trim_end_matches('=');
+    let mut output = Vec::with_capacity(text.len() * 3 / 4);
+    let mut group = 0u32;
+    let mut bits = 0;
+    for (offset, character) in text.bytes().enumerate() {
+        let value = BASE64_ALPHABET
+            .iter()
+            .position(|&c| c == character)
+            .ok_or_else(|| CCIHSError::DeserializationError(format!("invalid base64 at offset {offset}")))?;
+        group = group << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((group >> bits) as u8);
+            group &= (1 << bits) - 1;
+        }
+    }
+    Ok(output)
+}
+
+/// Reads a big-endian `u32`, as EVM-originated wire formats encode them.
+pub fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+pub fn read_bytes32<R: Read>(reader: &mut R) -> io::Result<[u8; 32]> {
+    let mut buf = [0u8; 32];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Parses hex (with a `0x` prefix or only hex digits) or else base64.
+pub fn from_hex_or_base64(text: &str) -> CCIHSResult<Vec<u8>> {
+    let text = text.trim();
+    let looks_like_hex = text.starts_with("0x")
+        || (text.len() % 2 == 0 && text.bytes().all(|byte| byte.is_ascii_hexdigit()));
+    if looks_like_hex {
+        from_hex(text)
+    } else {
+        from_base64(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_round_trips() {
+        for bytes in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+            assert_eq!(from_base64(&to_base64(bytes)).unwrap(), bytes);
+        }
+        assert_eq!(to_base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(to_base64(b"fo"), "Zm8=");
+        assert_eq!(from_hex_or_base64("0x0aff").unwrap(), vec![0x0a, 0xff]);
+    }
+}
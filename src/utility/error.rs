@@ -55,11 +55,76 @@ pub enum CCIHSError {
     #[error("Message expired")]
     MessageExpired,
 
+    #[error("Message cannot be executed before {0}")]
+    MessageNotYetExecutable(u64),
+
     #[error("Message not executed")]
     MessageNotExecuted,
     
     #[error("Timestamp error")]
     TimestampError,
 
+    #[error("Invalid chunk")]
+    InvalidChunk,
+
+    #[error("Chunk does not belong to transfer")]
+    ChunkTransferMismatch,
+
+    #[error("Chunk checksum mismatch")]
+    ChunkChecksumMismatch,
+
+    #[error("Invalid batch")]
+    InvalidBatch,
+
+    #[error("Batch hash mismatch")]
+    BatchHashMismatch,
+
+    #[error("Invalid protocol settings: {0}")]
+    InvalidProtocolSettings(String),
+
+    #[error("{path}: `{key}`: {reason}")]
+    InvalidConfigEntry { path: String, key: String, reason: String },
+
+    #[error("Invalid configuration: {0}")]
+    InvalidConfiguration(crate::config::ValidationReport),
+
+    #[error("Route from chain {source_chain} ({source_network}) to chain {destination_chain} ({destination_network}) crosses networks")]
+    NetworkMismatch {
+        source_chain: u16,
+        source_network: String,
+        destination_chain: u16,
+        destination_network: String,
+    },
+
+    #[error("Invalid attestation: {0}")]
+    InvalidAttestation(String),
+
+    #[error("Message already processed")]
+    MessageAlreadyProcessed,
+
+    #[error("Unsupported token")]
+    UnsupportedToken,
+
+    #[error("Invalid address")]
+    InvalidAddress,
+
+    #[error("Missing amount")]
+    MissingAmount,
+
+    #[error("No pending message")]
+    NoPendingMessage,
+
+    #[error("Route requires a quorum of protocols")]
+    QuorumRequired,
+
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    #[error("Request already pending")]
+    RequestAlreadyPending,
+
+    #[error("Too many pending requests")]
+    TooManyPendingRequests,
+
     // Add more error types as needed
 }
\ No newline at end of file
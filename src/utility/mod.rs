@@ -2,5 +2,11 @@
 pub mod error;
 pub use error::*;
 pub mod serialization;
+pub mod clock;
+pub use clock::{Clock, SharedClock, SolanaClock, SystemClock, TestClock, default_clock};
+pub mod encoding;
+pub mod ecdsa;
+pub mod abi;
+pub mod bech32;
 
 // ... other utility module exports
\ No newline at end of file
@@ -45,6 +45,11 @@ impl BorshSerialize for CrossChainAddress {
                 1u8.serialize(writer)?;
                 addr.0.serialize(writer)
             },
+            CrossChainAddress::Cosmos { hrp, bytes } => {
+                2u8.serialize(writer)?;
+                hrp.serialize(writer)?;
+                bytes.serialize(writer)
+            },
         }
     }
 }
@@ -60,6 +65,10 @@ impl BorshDeserialize for CrossChainAddress {
                 *buf = &buf[20..];
                 Ok(CrossChainAddress::Ethereum(EthereumAddress::from(eth_addr)))
             },
+            2 => Ok(CrossChainAddress::Cosmos {
+                hrp: String::deserialize(buf)?,
+                bytes: Vec::<u8>::deserialize(buf)?,
+            }),
             _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid CrossChainAddress variant")),
         }
     }
@@ -73,6 +82,10 @@ impl BorshDeserialize for CrossChainAddress {
                 reader.read_exact(&mut eth_addr)?;
                 Ok(CrossChainAddress::Ethereum(EthereumAddress::from(eth_addr)))
             },
+            2 => Ok(CrossChainAddress::Cosmos {
+                hrp: String::deserialize_reader(reader)?,
+                bytes: Vec::<u8>::deserialize_reader(reader)?,
+            }),
             _ => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid CrossChainAddress variant")),
         }
     }